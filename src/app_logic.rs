@@ -1,5 +1,11 @@
+pub mod clock;
+pub mod diff_jobs;
 pub mod handler;
 pub mod ids;
+pub mod main_menu;
+pub mod moved_block_connectors;
+pub mod pattern_test_panel;
+pub mod timing_report;
 
 #[cfg(test)]
 mod handler_tests;
@@ -0,0 +1,17 @@
+use std::time::Instant;
+
+/// Source of the current time for presenter timers such as the timestamp debounce in
+/// [CSV-UX-TimestampFeedbackV3]. Injected per [CSV-Tech-DIV1] so tests can drive time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock implementation used by the application.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
@@ -0,0 +1,710 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::core::text_decoding::UTF16_SNIFF_BYTES;
+use crate::core::{
+    CancellationToken, ComparableLine, DecodingOutcome, DiffControl, DiffEngineOperations,
+    DiffResult, LineNormalizer, LineOrigins, LineStore, LineText, LogDecompressors, LogSource,
+    PairOutcome, PairSummary, TextEncoding, TimestampParserError, TimestampParserOperations,
+    decode_text, decodes_as_itself, list_directory_files, pair_files, parse_line_timestamps,
+};
+use commanductui::PlatformWaker;
+use regex::Regex;
+use time::OffsetDateTime;
+
+/// Bytes read from the start of a file, after decompression, to sample its first lines.
+const SAMPLE_READ_LIMIT: u64 = 64 * 1024;
+
+/// Files at least this large are memory-mapped rather than read into memory, per
+/// [CSV-Core-LineStoreV1]. Smaller ones gain nothing from it.
+const MAP_THRESHOLD_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Unit of work handed to a `JobExecutor`.
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs jobs away from the UI thread per [CSV-UX-ResponsiveV1]. Injected into `AppLogic`
+/// so tests can substitute a deterministic executor per [CSV-Tech-DIV1].
+pub trait JobExecutor: Send + Sync {
+    fn spawn(&self, job: Job);
+}
+
+/// Told on a worker thread whenever a job has finished, so the UI thread can pick up the
+/// result right away instead of on its next event, per [CSV-UX-ResponsiveV1].
+pub trait JobCompletionNotifier: Send + Sync {
+    fn job_finished(&self);
+}
+
+/// Posts a wake-up event to the window the waker was created for.
+impl JobCompletionNotifier for PlatformWaker {
+    fn job_finished(&self) {
+        self.wake();
+    }
+}
+
+/// Production executor running every job on its own worker thread.
+#[derive(Default)]
+pub struct ThreadJobExecutor {
+    notifier: Option<Arc<dyn JobCompletionNotifier>>,
+}
+
+impl ThreadJobExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifies `notifier` after each job, once its result has been handed over.
+    pub fn with_notifier(mut self, notifier: Arc<dyn JobCompletionNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+}
+
+impl JobExecutor for ThreadJobExecutor {
+    fn spawn(&self, job: Job) {
+        let notifier = self.notifier.clone();
+        if let Err(err) = thread::Builder::new()
+            .name("diff-worker".to_string())
+            .spawn(move || {
+                job();
+                if let Some(notifier) = notifier {
+                    notifier.job_finished();
+                }
+            })
+        {
+            log::error!("[CSV-UX-ResponsiveV1] Failed to spawn diff worker: {err}");
+        }
+    }
+}
+
+/// Monotonic identifier distinguishing successive diff requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DiffJobId(u64);
+
+/// Timestamp patterns for each side and what their matches are replaced with, per
+/// [CSV-Core-TSPatternListV1] and [CSV-Core-TSReplaceV1], plus the format the matches are
+/// parsed with per [CSV-Core-TSExtractV1].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimestampStripping {
+    left_patterns: Vec<String>,
+    right_patterns: Vec<String>,
+    replacement: String,
+    format: String,
+}
+
+impl TimestampStripping {
+    pub fn new(
+        left_patterns: Vec<String>,
+        right_patterns: Vec<String>,
+        replacement: String,
+    ) -> Self {
+        Self {
+            left_patterns,
+            right_patterns,
+            replacement,
+            format: String::new(),
+        }
+    }
+
+    pub fn with_format(mut self, format: String) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// Work the `DiffJobScheduler` runs on the job executor.
+pub(crate) trait DiffJob: Send + 'static {
+    type Output: Send + 'static;
+
+    fn run(&self, cancellation: CancellationToken) -> Result<Self::Output, DiffWorkflowError>;
+}
+
+/// Inputs captured when a diff is requested, so the worker never touches presenter state.
+#[derive(Clone)]
+pub struct DiffJobRequest {
+    left_source: LogSource,
+    right_source: LogSource,
+    timestamps: TimestampStripping,
+    left_encoding: Option<TextEncoding>,
+    right_encoding: Option<TextEncoding>,
+    decompressors: Arc<LogDecompressors>,
+    line_normalizer: Arc<LineNormalizer>,
+    diff_engine: Arc<dyn DiffEngineOperations>,
+    min_moved_block_size: Option<usize>,
+    timestamp_parser: Arc<dyn TimestampParserOperations>,
+}
+
+impl DiffJobRequest {
+    pub fn new(
+        left_source: LogSource,
+        right_source: LogSource,
+        timestamps: TimestampStripping,
+        line_normalizer: Arc<LineNormalizer>,
+        diff_engine: Arc<dyn DiffEngineOperations>,
+        timestamp_parser: Arc<dyn TimestampParserOperations>,
+    ) -> Self {
+        Self {
+            left_source,
+            right_source,
+            timestamps,
+            left_encoding: None,
+            right_encoding: None,
+            decompressors: Arc::new(LogDecompressors::default()),
+            line_normalizer,
+            diff_engine,
+            min_moved_block_size: None,
+            timestamp_parser,
+        }
+    }
+
+    /// [CSV-UI-MovedBlocksV1] Minimum moved block size to diff with in place of the
+    /// engine's own.
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = Some(min_moved_block_size);
+        self
+    }
+
+    /// Encodings the user chose for each file per [CSV-File-EncodingV1]; `None` detects it.
+    pub fn with_encodings(
+        mut self,
+        left_encoding: Option<TextEncoding>,
+        right_encoding: Option<TextEncoding>,
+    ) -> Self {
+        self.left_encoding = left_encoding;
+        self.right_encoding = right_encoding;
+        self
+    }
+
+    /// Decompressors for compressed log files per [CSV-File-CompressedV1].
+    pub fn with_decompressors(mut self, decompressors: Arc<LogDecompressors>) -> Self {
+        self.decompressors = decompressors;
+        self
+    }
+
+    /// The same comparison of other sources, as used for each pair of a directory comparison.
+    /// [CSV-File-EncodingV1] Encodings chosen for the files in the viewers belong to those
+    /// files, so the encoding of each new source is detected.
+    pub fn for_sources(&self, left_source: LogSource, right_source: LogSource) -> Self {
+        Self {
+            left_source,
+            right_source,
+            left_encoding: None,
+            right_encoding: None,
+            ..self.clone()
+        }
+    }
+
+    /// Reads both sides, normalizes them and diffs them per [CSV-Core-CompareV1].
+    fn diff(&self, cancellation: CancellationToken) -> Result<DiffJobOutput, DiffWorkflowError> {
+        let left = self.load_side(
+            &self.left_source,
+            self.left_encoding,
+            &self.timestamps.left_patterns,
+        )?;
+        let right = self.load_side(
+            &self.right_source,
+            self.right_encoding,
+            &self.timestamps.right_patterns,
+        )?;
+        if cancellation.is_cancelled() {
+            return Err(DiffWorkflowError::Cancelled);
+        }
+
+        let mut control = DiffControl::new(cancellation);
+        if let Some(min_moved_block_size) = self.min_moved_block_size {
+            control = control.with_min_moved_block_size(min_moved_block_size);
+        }
+        let diff_result = self
+            .diff_engine
+            .compute_diff_with_control(&left.lines, &right.lines, &control)
+            .map_err(|_| DiffWorkflowError::Cancelled)?;
+
+        Ok(DiffJobOutput {
+            diff_result,
+            left_timestamps: left.timestamps,
+            right_timestamps: right.timestamps,
+            left_decoding: left.decoding,
+            right_decoding: right.decoding,
+            left_origins: left.origins,
+            right_origins: right.origins,
+        })
+    }
+
+    fn load_side(
+        &self,
+        source: &LogSource,
+        encoding: Option<TextEncoding>,
+        timestamp_patterns: &[String],
+    ) -> Result<LoadedSide, DiffWorkflowError> {
+        let (lines, decoding, origins) = read_source_lines(source, encoding, &self.decompressors)?;
+        // [CSV-Core-TimingV1] Parse the times from the original lines before they are stripped.
+        let timestamps = parse_line_timestamps(&lines, timestamp_patterns, &self.timestamps.format);
+        let stripped = self
+            .timestamp_parser
+            .strip_timestamps(&lines, timestamp_patterns, &self.timestamps.replacement)
+            .map_err(DiffWorkflowError::Timestamp)?;
+        debug_assert_eq!(lines.len(), stripped.len());
+        // [CSV-Core-NormalizationV1] The user's rules run on what the timestamp pattern left.
+        let normalized = self.line_normalizer.normalize_lines(stripped);
+
+        Ok(LoadedSide {
+            lines: build_comparable_lines(lines, normalized),
+            timestamps,
+            decoding,
+            origins,
+        })
+    }
+}
+
+impl DiffJob for DiffJobRequest {
+    type Output = DiffJobOutput;
+
+    fn run(&self, cancellation: CancellationToken) -> Result<DiffJobOutput, DiffWorkflowError> {
+        self.diff(cancellation)
+    }
+}
+
+/// [CSV-File-DirectoryCompareV1] Pairs the files of two run directories and diffs every
+/// pair the way the side-by-side view would.
+pub struct DirectoryJobRequest {
+    left_directory: PathBuf,
+    right_directory: PathBuf,
+    name_pattern: Option<Regex>,
+    diff: DiffJobRequest,
+}
+
+impl DirectoryJobRequest {
+    /// `diff` supplies the patterns, rules and engine; its sources are ignored.
+    pub fn new(left_directory: PathBuf, right_directory: PathBuf, diff: DiffJobRequest) -> Self {
+        Self {
+            left_directory,
+            right_directory,
+            name_pattern: None,
+            diff,
+        }
+    }
+
+    /// Pattern removed from relative paths before pairing them, as in `pair_files`.
+    pub fn with_name_pattern(mut self, name_pattern: Option<Regex>) -> Self {
+        self.name_pattern = name_pattern;
+        self
+    }
+
+    fn list_files(directory: &Path) -> Result<Vec<(String, PathBuf)>, DiffWorkflowError> {
+        list_directory_files(directory).map_err(|source| DiffWorkflowError::Io {
+            path: directory.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl DiffJob for DirectoryJobRequest {
+    type Output = Vec<PairSummary>;
+
+    fn run(&self, cancellation: CancellationToken) -> Result<Vec<PairSummary>, DiffWorkflowError> {
+        let pairs = pair_files(
+            Self::list_files(&self.left_directory)?,
+            Self::list_files(&self.right_directory)?,
+            self.name_pattern.as_ref(),
+        );
+
+        let mut summaries = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            if cancellation.is_cancelled() {
+                return Err(DiffWorkflowError::Cancelled);
+            }
+            let outcome = match pair.files() {
+                None => PairOutcome::Unpaired,
+                Some((left, right)) => match self
+                    .diff
+                    .for_sources(
+                        LogSource::File(left.to_path_buf()),
+                        LogSource::File(right.to_path_buf()),
+                    )
+                    .diff(cancellation.clone())
+                {
+                    Ok(output) => PairOutcome::Compared {
+                        total_changes: output.diff_result.statistics().total_changes(),
+                    },
+                    Err(DiffWorkflowError::Cancelled) => return Err(DiffWorkflowError::Cancelled),
+                    Err(err) => PairOutcome::Failed(err.to_string()),
+                },
+            };
+            summaries.push(PairSummary::new(pair, outcome));
+        }
+        Ok(summaries)
+    }
+}
+
+/// [CSV-UX-PatternTesterV1][CSV-Core-TSDetectV1] Reads the first lines of both files,
+/// which takes a while for files on a network share.
+pub(crate) struct SampleJobRequest {
+    left: Option<(LogSource, Option<TextEncoding>)>,
+    right: Option<(LogSource, Option<TextEncoding>)>,
+    line_count: usize,
+    decompressors: Arc<LogDecompressors>,
+}
+
+impl SampleJobRequest {
+    /// Samples `line_count` lines of each side that has a source, in its chosen encoding.
+    pub(crate) fn new(
+        left: Option<(LogSource, Option<TextEncoding>)>,
+        right: Option<(LogSource, Option<TextEncoding>)>,
+        line_count: usize,
+        decompressors: Arc<LogDecompressors>,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            line_count,
+            decompressors,
+        }
+    }
+
+    fn sample(&self, side: Option<&(LogSource, Option<TextEncoding>)>) -> Option<Vec<String>> {
+        let (source, encoding) = side?;
+        read_sample_lines(source, self.line_count, *encoding, &self.decompressors)
+            .inspect_err(|err| {
+                log::debug!("[CSV-UX-PatternTesterV1] Cannot sample '{source}': {err}")
+            })
+            .ok()
+    }
+}
+
+impl DiffJob for SampleJobRequest {
+    type Output = SampledLines;
+
+    fn run(&self, cancellation: CancellationToken) -> Result<SampledLines, DiffWorkflowError> {
+        let sample = |side: &Option<(LogSource, Option<TextEncoding>)>| {
+            if cancellation.is_cancelled() {
+                return Err(DiffWorkflowError::Cancelled);
+            }
+            Ok(self.sample(side.as_ref()))
+        };
+        let left = sample(&self.left)?;
+        let right = sample(&self.right)?;
+        Ok(SampledLines { left, right })
+    }
+}
+
+/// First lines of the left and the right file; `None` for a side without a file or whose
+/// file could not be read.
+pub(crate) struct SampledLines {
+    left: Option<Vec<String>>,
+    right: Option<Vec<String>>,
+}
+
+impl SampledLines {
+    pub(crate) fn into_parts(self) -> (Option<Vec<String>>, Option<Vec<String>>) {
+        (self.left, self.right)
+    }
+}
+
+/// One side read and prepared for diffing.
+struct LoadedSide {
+    lines: Vec<ComparableLine>,
+    timestamps: Vec<Option<OffsetDateTime>>,
+    decoding: DecodingOutcome,
+    origins: LineOrigins,
+}
+
+/// A finished diff together with the parsed timestamp of every line of each side, indexed
+/// by zero-based line number, how each side was decoded and which files its lines came from.
+pub(crate) struct DiffJobOutput {
+    diff_result: DiffResult,
+    left_timestamps: Vec<Option<OffsetDateTime>>,
+    right_timestamps: Vec<Option<OffsetDateTime>>,
+    left_decoding: DecodingOutcome,
+    right_decoding: DecodingOutcome,
+    left_origins: LineOrigins,
+    right_origins: LineOrigins,
+}
+
+impl DiffJobOutput {
+    /// Files the lines of the left and the right side came from per [CSV-File-RotatedSetV1].
+    pub(crate) fn line_origins(&self) -> (&LineOrigins, &LineOrigins) {
+        (&self.left_origins, &self.right_origins)
+    }
+
+    /// How the left and the right file were decoded per [CSV-File-EncodingV1].
+    pub(crate) fn decodings(&self) -> (DecodingOutcome, DecodingOutcome) {
+        (self.left_decoding, self.right_decoding)
+    }
+
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        DiffResult,
+        Vec<Option<OffsetDateTime>>,
+        Vec<Option<OffsetDateTime>>,
+    ) {
+        (
+            self.diff_result,
+            self.left_timestamps,
+            self.right_timestamps,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum DiffWorkflowError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Timestamp(TimestampParserError),
+    /// The job was superseded before it finished.
+    Cancelled,
+}
+
+impl fmt::Display for DiffWorkflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffWorkflowError::Io { path, source } => {
+                write!(f, "Failed to read '{}': {}", path.display(), source)
+            }
+            DiffWorkflowError::Timestamp(TimestampParserError::InvalidPattern {
+                index,
+                pattern,
+                message,
+                ..
+            }) => write!(
+                f,
+                "Timestamp pattern #{} '{}' is invalid: {}",
+                index + 1,
+                pattern,
+                message
+            ),
+            DiffWorkflowError::Timestamp(TimestampParserError::ProcessingFailed { message }) => {
+                write!(f, "Failed to strip timestamps: {message}")
+            }
+            DiffWorkflowError::Cancelled => f.write_str("The diff was cancelled"),
+        }
+    }
+}
+
+/// Outcome of a finished job, tagged with the job that produced it.
+pub(crate) struct DiffJobCompletion<T = DiffJobOutput> {
+    job_id: DiffJobId,
+    outcome: Result<T, DiffWorkflowError>,
+}
+
+impl<T> DiffJobCompletion<T> {
+    pub(crate) fn job_id(&self) -> DiffJobId {
+        self.job_id
+    }
+
+    pub(crate) fn into_outcome(self) -> Result<T, DiffWorkflowError> {
+        self.outcome
+    }
+}
+
+/// Hands diff jobs to the executor and filters their completions so that only the most
+/// recently submitted job can update the UI.
+pub(crate) struct DiffJobScheduler<J: DiffJob = DiffJobRequest> {
+    executor: Arc<dyn JobExecutor>,
+    next_job_id: u64,
+    active_job: Option<(DiffJobId, CancellationToken)>,
+    completion_sender: Sender<DiffJobCompletion<J::Output>>,
+    completion_receiver: Receiver<DiffJobCompletion<J::Output>>,
+}
+
+impl<J: DiffJob> DiffJobScheduler<J> {
+    pub(crate) fn new(executor: Arc<dyn JobExecutor>) -> Self {
+        let (completion_sender, completion_receiver) = mpsc::channel();
+        Self {
+            executor,
+            next_job_id: 0,
+            active_job: None,
+            completion_sender,
+            completion_receiver,
+        }
+    }
+
+    pub(crate) fn set_executor(&mut self, executor: Arc<dyn JobExecutor>) {
+        self.executor = executor;
+    }
+
+    /// Cancels any running job and starts `request` in its place.
+    pub(crate) fn submit(&mut self, request: J) -> DiffJobId {
+        self.cancel_active();
+
+        self.next_job_id += 1;
+        let job_id = DiffJobId(self.next_job_id);
+        let cancellation = CancellationToken::new();
+        self.active_job = Some((job_id, cancellation.clone()));
+
+        log::debug!("[CSV-UX-ResponsiveV1] Submitting diff job {job_id:?}");
+        let sender = self.completion_sender.clone();
+        self.executor.spawn(Box::new(move || {
+            let outcome = request.run(cancellation);
+            // The receiver is gone only when the presenter has been dropped.
+            let _ = sender.send(DiffJobCompletion { job_id, outcome });
+        }));
+
+        job_id
+    }
+
+    pub(crate) fn cancel_active(&mut self) {
+        if let Some((job_id, cancellation)) = self.active_job.take() {
+            log::debug!("[CSV-UX-ResponsiveV1] Cancelling superseded diff job {job_id:?}");
+            cancellation.cancel();
+        }
+    }
+
+    /// Returns the completion of the active job if it has finished, discarding any
+    /// results from superseded jobs that arrived in the meantime.
+    pub(crate) fn take_finished(&mut self) -> Option<DiffJobCompletion<J::Output>> {
+        while let Ok(completion) = self.completion_receiver.try_recv() {
+            let is_active = self
+                .active_job
+                .as_ref()
+                .is_some_and(|(job_id, _)| *job_id == completion.job_id());
+            if is_active {
+                self.active_job = None;
+                return Some(completion);
+            }
+            log::debug!(
+                "[CSV-UX-ResponsiveV1] Discarding stale result of diff job {:?}",
+                completion.job_id()
+            );
+        }
+
+        None
+    }
+}
+
+/// [CSV-File-CompressedV1] Compressed files are decompressed first. [CSV-File-EncodingV1]
+/// Bytes that are invalid in the file's encoding are decoded lossily and reported in the
+/// outcome rather than failing the diff.
+fn read_file_lines(
+    path: &Path,
+    encoding: Option<TextEncoding>,
+    decompressors: &LogDecompressors,
+) -> io::Result<(Arc<LineStore>, DecodingOutcome)> {
+    if let Some(store) = map_file_lines(path, encoding, decompressors)? {
+        let decoding = DecodingOutcome::new(TextEncoding::Utf8, encoding.is_none(), false);
+        return Ok((Arc::new(store), decoding));
+    }
+
+    let mut bytes = Vec::new();
+    decompressors.open(path)?.read_to_end(&mut bytes)?;
+    let decoded = decode_text(bytes, encoding);
+    let decoding = decoded.outcome();
+    Ok((
+        Arc::new(LineStore::from_text(decoded.into_text())),
+        decoding,
+    ))
+}
+
+/// [CSV-Core-LineStoreV1] Maps a large file whose bytes are its text as they are, so its
+/// lines are read from the page cache instead of being copied. Compressed files, files
+/// in another encoding and files with a BOM are decoded as usual.
+fn map_file_lines(
+    path: &Path,
+    encoding: Option<TextEncoding>,
+    decompressors: &LogDecompressors,
+) -> io::Result<Option<LineStore>> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < MAP_THRESHOLD_BYTES {
+        return Ok(None);
+    }
+
+    let mut start = Vec::new();
+    (&file)
+        .take(UTF16_SNIFF_BYTES as u64)
+        .read_to_end(&mut start)?;
+    if decompressors.decompressor_for(&start).is_some() || !decodes_as_itself(&start, encoding) {
+        return Ok(None);
+    }
+
+    let store = LineStore::map(&file, len)?;
+    if store.is_some() {
+        log::debug!("[CSV-Core-LineStoreV1] Memory-mapped '{}'", path.display());
+    }
+    Ok(store)
+}
+
+/// [CSV-File-RotatedSetV1] Reads the files of `source` one after the other, recording
+/// which lines came from which file. A side whose files decoded differently reports the
+/// most troublesome decoding.
+fn read_source_lines(
+    source: &LogSource,
+    encoding: Option<TextEncoding>,
+    decompressors: &LogDecompressors,
+) -> Result<(Vec<LineText>, DecodingOutcome, LineOrigins), DiffWorkflowError> {
+    let files = source.resolve().map_err(|err| DiffWorkflowError::Io {
+        path: PathBuf::from(source.to_string()),
+        source: err,
+    })?;
+
+    let mut lines = Vec::new();
+    let mut decodings = Vec::new();
+    let mut origins = LineOrigins::default();
+    for path in files {
+        let (store, decoding) =
+            read_file_lines(&path, encoding, decompressors).map_err(|source| {
+                DiffWorkflowError::Io {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+        origins.push_file(path, store.len());
+        lines.extend(LineText::all_of(&store));
+        decodings.push(decoding);
+    }
+    let decoding = decodings
+        .into_iter()
+        .max_by_key(|decoding| {
+            (
+                decoding.is_lossy(),
+                decoding.was_detected() && decoding.encoding() != TextEncoding::Utf8,
+            )
+        })
+        .unwrap_or_default();
+
+    Ok((lines, decoding, origins))
+}
+
+/// Reads at most `count` lines from the start of `source`, for sampling its contents.
+fn read_sample_lines(
+    source: &LogSource,
+    count: usize,
+    encoding: Option<TextEncoding>,
+    decompressors: &LogDecompressors,
+) -> io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    for path in source.resolve()? {
+        if lines.len() >= count {
+            break;
+        }
+        let mut bytes = Vec::new();
+        decompressors
+            .open(&path)?
+            .take(SAMPLE_READ_LIMIT)
+            .read_to_end(&mut bytes)?;
+        let decoded = decode_text(bytes, encoding);
+        lines.extend(
+            decoded
+                .text()
+                .lines()
+                .take(count - lines.len())
+                .map(str::to_string),
+        );
+    }
+    Ok(lines)
+}
+
+fn build_comparable_lines(original: Vec<LineText>, stripped: Vec<LineText>) -> Vec<ComparableLine> {
+    debug_assert_eq!(original.len(), stripped.len());
+    original
+        .into_iter()
+        .zip(stripped)
+        .map(|(original_text, comparable_text)| ComparableLine::new(original_text, comparable_text))
+        .collect()
+}
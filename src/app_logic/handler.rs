@@ -1,52 +1,231 @@
-use std::collections::VecDeque;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use regex::Regex;
+use time::OffsetDateTime;
+
+use crate::app_logic::clock::{Clock, SystemClock};
+use crate::app_logic::diff_jobs::{
+    DiffJobRequest, DiffJobScheduler, DiffWorkflowError, DirectoryJobRequest, JobExecutor,
+    SampleJobRequest, ThreadJobExecutor, TimestampStripping,
+};
 use crate::app_logic::ids::{
-    CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER, CONTROL_ID_TIMESTAMP_INPUT, MENU_ACTION_EXIT,
-    MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_RIGHT,
+    CONTROL_ID_LEFT_VIEWER, CONTROL_ID_MIN_MOVED_BLOCK_SIZE, CONTROL_ID_MOVE_GUTTER,
+    CONTROL_ID_NORMALIZATION_RULES, CONTROL_ID_PAIRING_PATTERN, CONTROL_ID_PATTERN_TESTER,
+    CONTROL_ID_PRESET_NAME, CONTROL_ID_RIGHT_TIMESTAMP_INPUT, CONTROL_ID_RIGHT_VIEWER,
+    CONTROL_ID_SLOWDOWN_RATIO, CONTROL_ID_TIMESTAMP_FORMAT, CONTROL_ID_TIMESTAMP_INPUT,
+    CONTROL_ID_TIMESTAMP_REPLACEMENT, CONTROL_ID_TIMING_COLUMN, LABEL_STATUS,
+    LABEL_TIMESTAMP_DIAGNOSTIC, MENU_ACTION_ADD_COMMON_RULES, MENU_ACTION_ALGORITHM_HECKEL,
+    MENU_ACTION_ALGORITHM_MYERS, MENU_ACTION_ALGORITHM_PATIENCE, MENU_ACTION_APPEND_LEFT,
+    MENU_ACTION_APPEND_RIGHT, MENU_ACTION_COMPARE_DIRECTORIES, MENU_ACTION_EXIT,
+    MENU_ACTION_EXPORT_PRESETS, MENU_ACTION_IMPORT_PRESETS, MENU_ACTION_MOVED_BLOCKS_REPORT,
+    MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_LEFT_ROTATION, MENU_ACTION_OPEN_RIGHT,
+    MENU_ACTION_OPEN_RIGHT_ROTATION, MENU_ACTION_SAVE_PRESET, MENU_ACTION_TIMING_REPORT,
+    TIMER_ID_DEBOUNCE,
+};
+use crate::app_logic::main_menu::{
+    DirectoryMenuState, DirectoryPairMenuAction, EncodingMenuAction, PresetMenuAction,
+    build_main_menu_items,
+};
+use crate::app_logic::moved_block_connectors::{
+    MovedBlockConnector, build_gutter_text, build_moved_block_connectors, build_moved_blocks_report,
 };
+use crate::app_logic::pattern_test_panel::build_pattern_test_text;
+use crate::app_logic::timing_report::{build_timing_column_text, build_timing_report};
+use crate::core::line_normalizer::{common_normalization_rules, format_rules, parse_rules};
+use crate::core::timestamp_presets::{export_presets, import_presets, upsert_preset};
 use crate::core::{
-    AppSettings, ComparableLine, DiffEngineOperations, DiffLine, DiffState, LineContent,
-    SettingsManagerOperations, TimestampParserError, TimestampParserOperations,
+    AppSettings, ChangeSpan, ComparisonAlgorithm, DEFAULT_MIN_MOVED_BLOCK_SIZE,
+    DEFAULT_REGEX_CACHE_CAPACITY, DEFAULT_SLOWDOWN_RATIO, DecodingOutcome, DiffAlgorithm,
+    DiffEngineOperations, DiffLine, DiffState, LineContent, LineNormalizer, LineOrigins,
+    LogDecompressors, LogSource, MovedBlock, NormalizationError, NormalizationRule,
+    PATTERN_TEST_SAMPLE_LINES, PairSummary, PatternErrorKind, PatternTestResult,
+    SettingsManagerOperations, TIMESTAMP_DETECTION_SAMPLE_LINES, TextEncoding,
+    TimestampExtractorError, TimestampParserError, TimestampParserOperations, TimestampPreset,
+    TimingAnalysis, detect_timestamp_pattern, split_pattern_list, test_pattern_list,
+    validate_pattern_list, validate_timestamp_format,
 };
 use commanductui::StyleId;
 use commanductui::types::{
     AppEvent, ControlId, MenuActionId, MessageSeverity, PlatformCommand, PlatformEventHandler,
     TreeItemId, UiStateProvider, WindowId,
 };
-use regex::Regex;
 
 const LOG_FILE_DIALOG_FILTER: &str = concat!(
-    "Log Files (*.log; *.txt)\0*.log;*.txt\0",
+    // [CSV-File-CompressedV1]
+    "Log Files (*.log; *.txt; *.gz; *.zst; *.bz2; *.xz)\0",
+    "*.log;*.txt;*.gz;*.zst;*.bz2;*.xz\0",
+    "All Files (*.*)\0*.*\0\0"
+);
+const PRESET_FILE_DIALOG_FILTER: &str = concat!(
+    "Preset Files (*.json)\0*.json\0",
     "All Files (*.*)\0*.*\0\0"
 );
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum PendingFileDialog {
-    Left,
-    Right,
+    Left(LogOpening),
+    Right(LogOpening),
+    /// [CSV-File-DirectoryCompareV1] Run directories are chosen in a folder picker, the
+    /// left one first.
+    LeftDirectory,
+    RightDirectory {
+        left: PathBuf,
+    },
+    ImportPresets,
+    ExportPresets,
+}
+
+/// Platform dialog a `PendingFileDialog` is answered by.
+#[derive(Debug, Clone, Copy)]
+enum DialogKind {
+    /// Opens an existing file matching the filter.
+    Open(&'static str),
+    /// Names a file to write, suggesting `default_filename`.
+    Save {
+        filter_spec: &'static str,
+        default_filename: &'static str,
+    },
+    /// Chooses a directory; folder pickers have no filter.
+    Folder,
+}
+
+/// Two run directories compared pair by pair per [CSV-File-DirectoryCompareV1].
+struct DirectoryComparison {
+    left: PathBuf,
+    right: PathBuf,
+    /// `None` while the comparison runs.
+    summaries: Option<Vec<PairSummary>>,
+    /// Algorithm the running or finished comparison diffs the pairs with.
+    algorithm: DiffAlgorithm,
+    /// Pair the viewers show.
+    shown: Option<usize>,
+}
+
+impl DirectoryComparison {
+    fn menu_state(&self) -> DirectoryMenuState<'_> {
+        match &self.summaries {
+            None => DirectoryMenuState::Comparing,
+            Some(summaries) => DirectoryMenuState::Compared {
+                summaries,
+                algorithm: self.algorithm,
+                shown: self.shown,
+            },
+        }
+    }
+}
+
+/// What the log file picked in the open dialog becomes, per [CSV-File-RotatedSetV1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogOpening {
+    /// The side reads only this file.
+    Replace,
+    /// The side reads this file with its rotations, oldest first.
+    RotationSet,
+    /// The side reads this file after the ones it already reads.
+    Append,
+}
+
+impl LogOpening {
+    fn dialog_title(self, side: &str) -> String {
+        match self {
+            LogOpening::Replace => format!("Open {side} Log File"),
+            LogOpening::RotationSet => format!("Open {side} Rotated Log Set"),
+            LogOpening::Append => format!("Append to {side} Log"),
+        }
+    }
+
+    fn apply(self, current: Option<LogSource>, path: PathBuf) -> LogSource {
+        match (self, current) {
+            (LogOpening::RotationSet, _) => LogSource::rotation_of(&path),
+            (LogOpening::Append, Some(current)) => current.with_appended(path),
+            (LogOpening::Replace | LogOpening::Append, _) => LogSource::File(path),
+        }
+    }
 }
 
 const MAX_TIMESTAMP_HISTORY: usize = 5; // [CSV-UX-TimestampHistoryV1] Limit recent patterns to a small MRU list.
+/// [CSV-Diff-AlgorithmChoiceV1] Pairs of sources whose chosen algorithm is remembered.
+const MAX_COMPARISON_ALGORITHMS: usize = 20;
+/// [CSV-UX-TimestampFeedbackV3] Quiet period after the last valid edit before re-diffing.
+pub(crate) const TIMESTAMP_DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
 
 /// Presenter orchestrating file loading and diff requests per [CSV-Core-CompareV1].
 pub struct AppLogic {
     diff_engine: Arc<dyn DiffEngineOperations>,
+    alternative_diff_engines: HashMap<DiffAlgorithm, Arc<dyn DiffEngineOperations>>,
+    /// Algorithm of the shown comparison.
+    diff_algorithm: DiffAlgorithm,
+    /// Algorithms chosen for earlier pairs of sources, most recent first.
+    comparison_algorithms: Vec<ComparisonAlgorithm>,
+    /// Sent with every diff request in place of the engine's own per [CSV-UI-MovedBlocksV1].
+    min_moved_block_size: usize,
     timestamp_parser: Arc<dyn TimestampParserOperations>,
+    /// Compiled patterns the parser keeps per [CSV-Core-RegexCacheV2].
+    regex_cache_capacity: usize,
     settings_manager: Arc<dyn SettingsManagerOperations>,
     app_identifier: String,
-    left_file_path: Option<PathBuf>,
-    right_file_path: Option<PathBuf>,
+    left_source: Option<LogSource>,
+    right_source: Option<LogSource>,
+    /// Encodings chosen for the files; `None` detects them.
+    left_encoding: Option<TextEncoding>,
+    right_encoding: Option<TextEncoding>,
+    decompressors: Arc<LogDecompressors>,
     timestamp_pattern: String,
+    right_timestamp_pattern: String,
+    timestamp_replacement: String,
+    timestamp_format: String,
     timestamp_history: VecDeque<String>,
+    /// Set once the user edited the pattern or one was proposed, so detection stops.
+    timestamp_pattern_touched: bool,
+    timestamp_presets: Vec<TimestampPreset>,
+    preset_name: String,
+    /// First lines of each file, previewed by the pattern tester.
+    left_sample_lines: Vec<String>,
+    right_sample_lines: Vec<String>,
+    pattern_test_text: String,
+    normalization_rules: Vec<NormalizationRule>,
+    line_normalizer: Arc<LineNormalizer>,
+    clock: Arc<dyn Clock>,
+    pending_pattern_commit: Option<Instant>,
+    pending_rules_commit: Option<Instant>,
+    pending_min_moved_block_commit: Option<Instant>,
     diff_lines: Vec<DiffLine>,
+    moved_blocks: Vec<MovedBlock>,
+    moved_block_connectors: Vec<MovedBlockConnector>,
+    left_timestamps: Vec<Option<OffsetDateTime>>,
+    right_timestamps: Vec<Option<OffsetDateTime>>,
+    /// Files the lines of each side came from, naming them wherever line numbers are shown
+    /// per [CSV-File-RotatedSetV1].
+    left_line_origins: LineOrigins,
+    right_line_origins: LineOrigins,
+    slowdown_ratio: f64,
+    timing_analysis: TimingAnalysis,
+    diff_jobs: DiffJobScheduler,
+    sample_jobs: DiffJobScheduler<SampleJobRequest>,
+    directory_comparison: Option<DirectoryComparison>,
+    directory_jobs: DiffJobScheduler<DirectoryJobRequest>,
+    /// Regex removed from relative paths before pairing directory files; empty for none.
+    pairing_pattern: String,
+    pairing_pattern_is_valid: bool,
+    pending_pairing_commit: Option<Instant>,
     pending_commands: VecDeque<PlatformCommand>,
     active_window: Option<WindowId>,
     pending_file_dialog: Option<PendingFileDialog>,
     timestamp_pattern_is_valid: bool,
+    right_timestamp_pattern_is_valid: bool,
+    timestamp_format_is_valid: bool,
+    timestamp_status: Option<String>,
+    /// Warning about files that did not decode cleanly, shown when the timestamps are fine.
+    decoding_warning: Option<String>,
+    /// Text the status bar currently shows.
+    status_text: Option<String>,
+    timestamp_diagnostic: Option<String>,
+    normalization_rules_are_valid: bool,
+    min_moved_block_size_is_valid: bool,
+    slowdown_ratio_is_valid: bool,
     is_syncing_scroll: bool,
 }
 
@@ -60,22 +239,117 @@ impl AppLogic {
     ) -> Self {
         Self {
             diff_engine,
+            alternative_diff_engines: HashMap::new(),
+            diff_algorithm: DiffAlgorithm::default(),
+            comparison_algorithms: Vec::new(),
+            min_moved_block_size: DEFAULT_MIN_MOVED_BLOCK_SIZE,
             timestamp_parser,
+            regex_cache_capacity: DEFAULT_REGEX_CACHE_CAPACITY,
             settings_manager,
             app_identifier: app_identifier.into(),
-            left_file_path: None,
-            right_file_path: None,
+            left_source: None,
+            right_source: None,
+            left_encoding: None,
+            right_encoding: None,
+            decompressors: Arc::new(LogDecompressors::default()),
             timestamp_pattern: String::new(),
+            right_timestamp_pattern: String::new(),
+            timestamp_replacement: String::new(),
+            timestamp_format: String::new(),
             timestamp_history: VecDeque::new(),
+            timestamp_pattern_touched: false,
+            timestamp_presets: Vec::new(),
+            preset_name: String::new(),
+            left_sample_lines: Vec::new(),
+            right_sample_lines: Vec::new(),
+            pattern_test_text: String::new(),
+            normalization_rules: Vec::new(),
+            line_normalizer: Arc::new(LineNormalizer::default()),
+            clock: Arc::new(SystemClock),
+            pending_pattern_commit: None,
+            pending_rules_commit: None,
+            pending_min_moved_block_commit: None,
             diff_lines: Vec::new(),
+            moved_blocks: Vec::new(),
+            moved_block_connectors: Vec::new(),
+            left_timestamps: Vec::new(),
+            right_timestamps: Vec::new(),
+            left_line_origins: LineOrigins::default(),
+            right_line_origins: LineOrigins::default(),
+            slowdown_ratio: DEFAULT_SLOWDOWN_RATIO,
+            timing_analysis: TimingAnalysis::default(),
+            diff_jobs: DiffJobScheduler::new(Arc::new(ThreadJobExecutor::new())),
+            sample_jobs: DiffJobScheduler::new(Arc::new(ThreadJobExecutor::new())),
+            directory_comparison: None,
+            directory_jobs: DiffJobScheduler::new(Arc::new(ThreadJobExecutor::new())),
+            pairing_pattern: String::new(),
+            pairing_pattern_is_valid: true,
+            pending_pairing_commit: None,
             pending_commands: VecDeque::new(),
             active_window: None,
             pending_file_dialog: None,
             timestamp_pattern_is_valid: true,
+            right_timestamp_pattern_is_valid: true,
+            timestamp_format_is_valid: true,
+            timestamp_status: None,
+            decoding_warning: None,
+            status_text: None,
+            timestamp_diagnostic: None,
+            normalization_rules_are_valid: true,
+            min_moved_block_size_is_valid: true,
+            slowdown_ratio_is_valid: true,
             is_syncing_scroll: false,
         }
     }
 
+    /// Registers the engine used when the user selects `algorithm` per [CSV-Diff-AlgorithmChoiceV1].
+    /// Algorithms without a registered engine fall back to the engine passed to `new`.
+    pub fn register_diff_engine(
+        &mut self,
+        algorithm: DiffAlgorithm,
+        engine: Arc<dyn DiffEngineOperations>,
+    ) {
+        self.alternative_diff_engines.insert(algorithm, engine);
+    }
+
+    /// Replaces the executor that runs diff jobs per [CSV-UX-ResponsiveV1]. Defaults to a
+    /// worker thread per job; tests inject a deterministic executor.
+    pub fn with_job_executor(mut self, executor: Arc<dyn JobExecutor>) -> Self {
+        self.diff_jobs.set_executor(Arc::clone(&executor));
+        self.sample_jobs.set_executor(Arc::clone(&executor));
+        self.directory_jobs.set_executor(executor);
+        self
+    }
+
+    /// Replaces the decompressors for compressed log files per [CSV-File-CompressedV1].
+    /// Defaults to gzip, zstd, bzip2 and xz.
+    pub fn with_decompressors(mut self, decompressors: Arc<LogDecompressors>) -> Self {
+        self.decompressors = decompressors;
+        self
+    }
+
+    /// Replaces the clock driving the timestamp debounce per [CSV-UX-TimestampFeedbackV3].
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Connectors for the moved blocks of the latest diff per [CSV-UI-MovedBlocksV1].
+    pub fn moved_block_connectors(&self) -> &[MovedBlockConnector] {
+        &self.moved_block_connectors
+    }
+
+    /// Step times of the current diff per [CSV-Core-TimingV1].
+    pub fn timing_analysis(&self) -> &TimingAnalysis {
+        &self.timing_analysis
+    }
+
+    fn active_diff_engine(&self) -> &Arc<dyn DiffEngineOperations> {
+        self.alternative_diff_engines
+            .get(&self.diff_algorithm)
+            .unwrap_or(&self.diff_engine)
+    }
+
     fn enqueue_command(&mut self, command: PlatformCommand) {
         self.pending_commands.push_back(command);
     }
@@ -83,37 +357,197 @@ impl AppLogic {
     fn handle_menu_action(&mut self, action_id: MenuActionId) {
         match action_id {
             id if id == MENU_ACTION_OPEN_LEFT => {
-                self.request_open_file_dialog(PendingFileDialog::Left);
+                self.request_file_dialog(PendingFileDialog::Left(LogOpening::Replace));
             }
             id if id == MENU_ACTION_OPEN_RIGHT => {
-                self.request_open_file_dialog(PendingFileDialog::Right);
+                self.request_file_dialog(PendingFileDialog::Right(LogOpening::Replace));
+            }
+            id if id == MENU_ACTION_OPEN_LEFT_ROTATION => {
+                self.request_file_dialog(PendingFileDialog::Left(LogOpening::RotationSet));
+            }
+            id if id == MENU_ACTION_OPEN_RIGHT_ROTATION => {
+                self.request_file_dialog(PendingFileDialog::Right(LogOpening::RotationSet));
+            }
+            id if id == MENU_ACTION_APPEND_LEFT => {
+                self.request_file_dialog(PendingFileDialog::Left(LogOpening::Append));
+            }
+            id if id == MENU_ACTION_APPEND_RIGHT => {
+                self.request_file_dialog(PendingFileDialog::Right(LogOpening::Append));
             }
             id if id == MENU_ACTION_EXIT => self.request_exit(),
-            _ => {}
+            id if id == MENU_ACTION_ALGORITHM_HECKEL => {
+                self.select_diff_algorithm(DiffAlgorithm::Heckel);
+            }
+            id if id == MENU_ACTION_ALGORITHM_MYERS => {
+                self.select_diff_algorithm(DiffAlgorithm::Myers);
+            }
+            id if id == MENU_ACTION_ALGORITHM_PATIENCE => {
+                self.select_diff_algorithm(DiffAlgorithm::Patience);
+            }
+            id if id == MENU_ACTION_ADD_COMMON_RULES => self.add_common_normalization_rules(),
+            id if id == MENU_ACTION_TIMING_REPORT => self.show_timing_report(),
+            id if id == MENU_ACTION_MOVED_BLOCKS_REPORT => self.show_moved_blocks_report(),
+            id if id == MENU_ACTION_SAVE_PRESET => self.save_timestamp_preset(),
+            id if id == MENU_ACTION_IMPORT_PRESETS => {
+                self.request_file_dialog(PendingFileDialog::ImportPresets);
+            }
+            id if id == MENU_ACTION_EXPORT_PRESETS => {
+                self.request_file_dialog(PendingFileDialog::ExportPresets);
+            }
+            id if id == MENU_ACTION_COMPARE_DIRECTORIES => {
+                self.request_file_dialog(PendingFileDialog::LeftDirectory);
+            }
+            id => match PresetMenuAction::from_action_id(id) {
+                Some(PresetMenuAction::Apply(index)) => self.apply_timestamp_preset(index),
+                Some(PresetMenuAction::Remove(index)) => self.remove_timestamp_preset(index),
+                None => {
+                    if let Some(action) = EncodingMenuAction::from_action_id(id) {
+                        self.select_file_encoding(action);
+                    } else if let Some(DirectoryPairMenuAction(index)) =
+                        DirectoryPairMenuAction::from_action_id(id)
+                    {
+                        self.show_directory_pair(index);
+                    }
+                }
+            },
         }
     }
 
-    fn request_open_file_dialog(&mut self, dialog: PendingFileDialog) {
-        let Some(window_id) = self.active_window else {
+    fn select_diff_algorithm(&mut self, algorithm: DiffAlgorithm) {
+        let is_switch = self.diff_algorithm != algorithm;
+        self.diff_algorithm = algorithm;
+        self.remember_comparison_algorithm();
+        if !is_switch {
+            return;
+        }
+
+        log::debug!("[CSV-Diff-AlgorithmChoiceV1] Switching diff algorithm to {algorithm:?}");
+        self.trigger_diffs_if_ready();
+    }
+
+    /// [CSV-Diff-AlgorithmChoiceV1] Records the current algorithm for the shown pair, which
+    /// is diffed with it whenever it is compared again.
+    fn remember_comparison_algorithm(&mut self) {
+        let (Some(left), Some(right)) = (&self.left_source, &self.right_source) else {
             return;
         };
+        self.comparison_algorithms
+            .retain(|entry| !entry.compares(left, right));
+        self.comparison_algorithms.insert(
+            0,
+            ComparisonAlgorithm::new(left.clone(), right.clone(), self.diff_algorithm),
+        );
+        self.comparison_algorithms
+            .truncate(MAX_COMPARISON_ALGORITHMS);
+    }
 
-        let title = match dialog {
-            PendingFileDialog::Left => "Open Left Log File",
-            PendingFileDialog::Right => "Open Right Log File",
+    /// [CSV-Diff-AlgorithmChoiceV1] Switches to the algorithm chosen when the shown pair was
+    /// compared before; a pair compared for the first time keeps the current one.
+    fn restore_comparison_algorithm(&mut self) {
+        let (Some(left), Some(right)) = (&self.left_source, &self.right_source) else {
+            return;
+        };
+        if let Some(entry) = self
+            .comparison_algorithms
+            .iter()
+            .find(|entry| entry.compares(left, right))
+        {
+            log::debug!(
+                "[CSV-Diff-AlgorithmChoiceV1] Diffing '{left}' and '{right}' with {:?} as before",
+                entry.algorithm()
+            );
+            self.diff_algorithm = entry.algorithm();
         }
-        .to_string();
+    }
 
-        let initial_dir = self
-            .path_for_dialog(dialog)
-            .and_then(|path| path.parent().map(Path::to_path_buf));
+    /// [CSV-File-EncodingV1] Rereads the file in the encoding the user chose.
+    fn select_file_encoding(&mut self, action: EncodingMenuAction) {
+        let (side, current, encoding) = match action {
+            EncodingMenuAction::Left(encoding) => ("left", &mut self.left_encoding, encoding),
+            EncodingMenuAction::Right(encoding) => ("right", &mut self.right_encoding, encoding),
+        };
+        if *current == encoding {
+            return;
+        }
+
+        log::info!("[CSV-File-EncodingV1] Reading the {side} file as {encoding:?}");
+        *current = encoding;
+        self.refresh_main_menu();
+        self.sample_files_then_diff();
+    }
+
+    /// Asks for the file or folder `dialog` is waiting for, in the dialog that fits it.
+    fn request_file_dialog(&mut self, dialog: PendingFileDialog) {
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let (title, kind) = match &dialog {
+            PendingFileDialog::Left(opening) => (
+                opening.dialog_title("Left"),
+                DialogKind::Open(LOG_FILE_DIALOG_FILTER),
+            ),
+            PendingFileDialog::Right(opening) => (
+                opening.dialog_title("Right"),
+                DialogKind::Open(LOG_FILE_DIALOG_FILTER),
+            ),
+            PendingFileDialog::LeftDirectory => (
+                "Choose the Left Run Directory".to_string(),
+                DialogKind::Folder,
+            ),
+            PendingFileDialog::RightDirectory { .. } => (
+                "Choose the Right Run Directory".to_string(),
+                DialogKind::Folder,
+            ),
+            PendingFileDialog::ImportPresets => (
+                "Import Timestamp Presets".to_string(),
+                DialogKind::Open(PRESET_FILE_DIALOG_FILTER),
+            ),
+            PendingFileDialog::ExportPresets => (
+                "Export Timestamp Presets".to_string(),
+                DialogKind::Save {
+                    filter_spec: PRESET_FILE_DIALOG_FILTER,
+                    default_filename: "timestamp_presets.json",
+                },
+            ),
+        };
+
+        // [CSV-File-DirectoryCompareV1] Run directories usually sit side by side, so the
+        // dialogs start next to the last ones.
+        let initial_dir = match &dialog {
+            PendingFileDialog::LeftDirectory => self
+                .directory_comparison
+                .as_ref()
+                .and_then(|comparison| comparison.left.parent().map(Path::to_path_buf)),
+            PendingFileDialog::RightDirectory { left } => left.parent().map(Path::to_path_buf),
+            _ => self
+                .source_for_dialog(&dialog)
+                .and_then(|source| source.directory().map(Path::to_path_buf)),
+        };
 
         self.pending_file_dialog = Some(dialog);
-        self.enqueue_command(PlatformCommand::ShowOpenFileDialog {
-            window_id,
-            title,
-            filter_spec: LOG_FILE_DIALOG_FILTER.to_string(),
-            initial_dir,
+        self.enqueue_command(match kind {
+            DialogKind::Open(filter_spec) => PlatformCommand::ShowOpenFileDialog {
+                window_id,
+                title,
+                filter_spec: filter_spec.to_string(),
+                initial_dir,
+            },
+            DialogKind::Save {
+                filter_spec,
+                default_filename,
+            } => PlatformCommand::ShowSaveFileDialog {
+                window_id,
+                title,
+                default_filename: default_filename.to_string(),
+                filter_spec: filter_spec.to_string(),
+                initial_dir,
+            },
+            DialogKind::Folder => PlatformCommand::ShowFolderPickerDialog {
+                window_id,
+                title,
+                initial_dir,
+            },
         });
     }
 
@@ -129,32 +563,609 @@ impl AppLogic {
         if Some(window_id) != self.active_window {
             return;
         }
-
-        let Some(dialog) = self.pending_file_dialog.take() else {
-            return;
-        };
-
-        if let Some(path) = result {
-            match dialog {
-                PendingFileDialog::Left => self.left_file_path = Some(path),
-                PendingFileDialog::Right => self.right_file_path = Some(path),
+
+        let Some(dialog) = self.pending_file_dialog.take() else {
+            return;
+        };
+
+        let Some(path) = result else {
+            return;
+        };
+        let (opening, source, encoding) = match dialog {
+            PendingFileDialog::Left(opening) => {
+                (opening, &mut self.left_source, &mut self.left_encoding)
+            }
+            PendingFileDialog::Right(opening) => {
+                (opening, &mut self.right_source, &mut self.right_encoding)
+            }
+            PendingFileDialog::LeftDirectory => {
+                return self.request_file_dialog(PendingFileDialog::RightDirectory { left: path });
+            }
+            PendingFileDialog::RightDirectory { left } => {
+                return self.start_directory_comparison(left, path);
+            }
+            PendingFileDialog::ImportPresets => return self.import_timestamp_presets(&path),
+            PendingFileDialog::ExportPresets => return self.export_timestamp_presets(&path),
+        };
+        // [CSV-File-RotatedSetV1] Appended files are read like the ones before them.
+        let new_source = opening.apply(source.take(), path);
+        log::info!("[CSV-File-RotatedSetV1] Reading '{new_source}'");
+        *source = Some(new_source);
+        // [CSV-File-EncodingV1] A chosen encoding belongs to the file it was chosen for.
+        let chosen_encoding = match opening {
+            LogOpening::Append => None,
+            LogOpening::Replace | LogOpening::RotationSet => encoding.take(),
+        };
+        // [CSV-File-DirectoryCompareV1] The viewers no longer show a pair of the directories.
+        let was_showing_pair = self
+            .directory_comparison
+            .as_mut()
+            .is_some_and(|comparison| comparison.shown.take().is_some());
+        if chosen_encoding.is_some() || was_showing_pair {
+            self.refresh_main_menu();
+        }
+        self.restore_comparison_algorithm();
+        self.sample_files_then_diff();
+    }
+
+    /// [CSV-Core-TSPresetsV1] Stores the current pattern and format under the name typed in
+    /// the preset name input, replacing a preset of the same name.
+    fn save_timestamp_preset(&mut self) {
+        let name = self.preset_name.trim();
+        let problem = if name.is_empty() {
+            Some("Type a name for the preset first.")
+        } else if self.timestamp_pattern.trim().is_empty() {
+            Some("There is no timestamp pattern to save.")
+        } else if !self.timestamp_pattern_is_valid {
+            Some("The timestamp pattern is invalid.")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            self.show_preset_message(problem.to_string(), MessageSeverity::Warning);
+            return;
+        }
+
+        let preset = TimestampPreset::new(name, self.timestamp_pattern.clone())
+            .with_format(self.timestamp_format.clone());
+        log::info!("[CSV-Core-TSPresetsV1] Saving preset '{}'", preset.name());
+        upsert_preset(&mut self.timestamp_presets, preset);
+        self.refresh_main_menu();
+    }
+
+    fn apply_timestamp_preset(&mut self, index: usize) {
+        let Some(preset) = self.timestamp_presets.get(index).cloned() else {
+            return;
+        };
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        log::info!("[CSV-Core-TSPresetsV1] Applying preset '{}'", preset.name());
+        self.timestamp_pattern_touched = true;
+        self.timestamp_pattern = preset.pattern().to_string();
+        self.enqueue_command(PlatformCommand::SetInputText {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: self.timestamp_pattern.clone(),
+        });
+        if !preset.format().is_empty() {
+            self.timestamp_format = preset.format().to_string();
+            self.enqueue_command(PlatformCommand::SetInputText {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+                text: self.timestamp_format.clone(),
+            });
+        }
+
+        // A chosen preset needs no debounce; diff with it right away.
+        self.pending_pattern_commit = None;
+        let is_valid = self.validate_timestamp_pattern();
+        self.refresh_pattern_test();
+        if is_valid {
+            self.record_timestamp_pattern_history();
+            self.trigger_diffs_if_ready();
+        }
+    }
+
+    fn remove_timestamp_preset(&mut self, index: usize) {
+        if index < self.timestamp_presets.len() {
+            let preset = self.timestamp_presets.remove(index);
+            log::info!("[CSV-Core-TSPresetsV1] Removed preset '{}'", preset.name());
+            self.refresh_main_menu();
+        }
+    }
+
+    /// Merges the presets of a shared file into the own ones, replacing those of the same name.
+    fn import_timestamp_presets(&mut self, path: &Path) {
+        match import_presets(path) {
+            Ok(presets) => {
+                let count = presets.len();
+                for preset in presets {
+                    upsert_preset(&mut self.timestamp_presets, preset);
+                }
+                self.refresh_main_menu();
+                self.show_preset_message(
+                    format!("Imported {count} preset(s) from '{}'.", path.display()),
+                    MessageSeverity::Information,
+                );
+            }
+            Err(err) => self.show_preset_message(
+                format!("Failed to import '{}': {err}", path.display()),
+                MessageSeverity::Error,
+            ),
+        }
+    }
+
+    fn export_timestamp_presets(&mut self, path: &Path) {
+        if let Err(err) = export_presets(path, &self.timestamp_presets) {
+            self.show_preset_message(
+                format!("Failed to export to '{}': {err}", path.display()),
+                MessageSeverity::Error,
+            );
+        }
+    }
+
+    fn show_preset_message(&mut self, message: String, severity: MessageSeverity) {
+        if let Some(window_id) = self.active_window {
+            self.enqueue_command(PlatformCommand::ShowMessageBox {
+                window_id,
+                title: "Timestamp Presets".to_string(),
+                message,
+                severity,
+            });
+        }
+    }
+
+    /// [CSV-Core-TSPresetsV1][CSV-File-EncodingV1][CSV-File-DirectoryCompareV1] The menu
+    /// lists the presets, marks the chosen encodings and summarizes the directory
+    /// comparison, so it is rebuilt whenever they change.
+    fn refresh_main_menu(&mut self) {
+        if let Some(window_id) = self.active_window {
+            let directories = self
+                .directory_comparison
+                .as_ref()
+                .map_or(DirectoryMenuState::Idle, DirectoryComparison::menu_state);
+            let menu_items = build_main_menu_items(
+                &self.timestamp_presets,
+                self.left_encoding,
+                self.right_encoding,
+                directories,
+            );
+            self.enqueue_command(PlatformCommand::CreateMainMenu {
+                window_id,
+                menu_items,
+            });
+        }
+    }
+
+    fn handle_input_text_changed(&mut self, control_id: ControlId, text: String) {
+        if control_id == CONTROL_ID_TIMESTAMP_INPUT {
+            log::debug!("[CSV-UX-TimestampFeedbackV3] Timestamp input changed to '{text}'");
+            self.timestamp_pattern = text;
+            self.timestamp_pattern_touched = true;
+            self.handle_timestamp_input_changed();
+        } else if control_id == CONTROL_ID_RIGHT_TIMESTAMP_INPUT {
+            log::debug!("[CSV-Core-TSPatternListV1] Right timestamp input changed to '{text}'");
+            self.right_timestamp_pattern = text;
+            self.handle_timestamp_input_changed();
+        } else if control_id == CONTROL_ID_TIMESTAMP_REPLACEMENT {
+            log::debug!("[CSV-Core-TSReplaceV1] Timestamp replacement changed to '{text}'");
+            self.timestamp_replacement = text;
+            self.handle_timestamp_input_changed();
+        } else if control_id == CONTROL_ID_TIMESTAMP_FORMAT {
+            log::debug!("[CSV-Core-TSExtractV1] Timestamp format changed to '{text}'");
+            self.timestamp_format = text;
+            // [CSV-Core-TimingV1] The diff job parses the times, so a new format re-diffs.
+            self.handle_timestamp_input_changed();
+        } else if control_id == CONTROL_ID_NORMALIZATION_RULES {
+            self.handle_normalization_rules_changed(&text);
+        } else if control_id == CONTROL_ID_MIN_MOVED_BLOCK_SIZE {
+            self.handle_min_moved_block_size_changed(&text);
+        } else if control_id == CONTROL_ID_SLOWDOWN_RATIO {
+            self.handle_slowdown_ratio_changed(&text);
+        } else if control_id == CONTROL_ID_PRESET_NAME {
+            self.preset_name = text;
+        } else if control_id == CONTROL_ID_PAIRING_PATTERN {
+            self.handle_pairing_pattern_changed(text);
+        }
+    }
+
+    /// [CSV-Core-TimingV1] A new threshold only re-flags the pairs of the current diff.
+    fn handle_slowdown_ratio_changed(&mut self, text: &str) {
+        let ratio = text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|ratio| ratio.is_finite() && *ratio > 0.0);
+
+        let is_valid = ratio.is_some();
+        if is_valid != self.slowdown_ratio_is_valid {
+            self.slowdown_ratio_is_valid = is_valid;
+            self.apply_input_validity_style(CONTROL_ID_SLOWDOWN_RATIO, is_valid);
+        }
+        let Some(ratio) = ratio else {
+            return;
+        };
+
+        log::debug!("[CSV-Core-TimingV1] Slowdown ratio changed to {ratio}");
+        self.slowdown_ratio = ratio;
+        self.refresh_timing_analysis();
+        if let Some(window_id) = self.active_window {
+            self.enqueue_timing_column(window_id);
+        }
+    }
+
+    fn refresh_timing_analysis(&mut self) {
+        self.timing_analysis = TimingAnalysis::analyze(
+            &self.diff_lines,
+            &self.left_timestamps,
+            &self.right_timestamps,
+            self.slowdown_ratio,
+        );
+    }
+
+    /// [CSV-UI-TimingV1] Lists the largest slowdowns of the current diff.
+    fn show_timing_report(&mut self) {
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let severity = if self.timing_analysis.slowdowns().is_empty() {
+            MessageSeverity::Information
+        } else {
+            MessageSeverity::Warning
+        };
+        self.enqueue_command(PlatformCommand::ShowMessageBox {
+            window_id,
+            title: "Timing Report".to_string(),
+            message: build_timing_report(
+                &self.timing_analysis,
+                &self.diff_lines,
+                &self.right_line_origins,
+            ),
+            severity,
+        });
+    }
+
+    /// [CSV-UI-MovedBlocksV1] Lists where the moved blocks of the current diff came from and
+    /// went to, by file and line.
+    fn show_moved_blocks_report(&mut self) {
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        self.enqueue_command(PlatformCommand::ShowMessageBox {
+            window_id,
+            title: "Moved Blocks".to_string(),
+            message: build_moved_blocks_report(
+                &self.moved_blocks,
+                &self.left_line_origins,
+                &self.right_line_origins,
+            ),
+            severity: MessageSeverity::Information,
+        });
+    }
+
+    /// [CSV-Core-TSDetectV1] Fills in the pattern detected in `samples`, the first lines of
+    /// both files, while the user has none yet, along with its time format if that is empty
+    /// too.
+    fn propose_timestamp_pattern(&mut self, samples: &[String]) {
+        if self.timestamp_pattern_touched || !self.timestamp_pattern.is_empty() {
+            return;
+        }
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let Some(candidate) = detect_timestamp_pattern(samples) else {
+            return;
+        };
+
+        log::info!(
+            "[CSV-Core-TSDetectV1] Proposing the {} timestamp format",
+            candidate.name()
+        );
+        self.timestamp_pattern_touched = true;
+        self.timestamp_pattern = candidate.pattern().to_string();
+        self.enqueue_command(PlatformCommand::SetInputText {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: self.timestamp_pattern.clone(),
+        });
+        if self.timestamp_format.is_empty() {
+            self.timestamp_format = candidate.format().to_string();
+            self.enqueue_command(PlatformCommand::SetInputText {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+                text: self.timestamp_format.clone(),
+            });
+        }
+        self.validate_timestamp_pattern();
+    }
+
+    /// [CSV-UX-PatternTesterV1][CSV-Core-TSDetectV1] Reads the first lines of each file on
+    /// the job executor, then diffs the files, so that a pattern proposed from the lines
+    /// applies to the first diff already.
+    fn sample_files_then_diff(&mut self) {
+        let side =
+            |source: &Option<LogSource>, encoding| source.clone().map(|source| (source, encoding));
+        let request = SampleJobRequest::new(
+            side(&self.left_source, self.left_encoding),
+            side(&self.right_source, self.right_encoding),
+            PATTERN_TEST_SAMPLE_LINES.max(TIMESTAMP_DETECTION_SAMPLE_LINES),
+            Arc::clone(&self.decompressors),
+        );
+        self.sample_jobs.submit(request);
+        // An executor that ran the job inline sends no wake-up; its result is ready now.
+        self.apply_finished_sample_job();
+    }
+
+    fn apply_finished_sample_job(&mut self) {
+        let Some(completion) = self.sample_jobs.take_finished() else {
+            return;
+        };
+        // Sampling problems leave a side without lines; only cancellation fails the job.
+        let Ok(samples) = completion.into_outcome() else {
+            return;
+        };
+
+        let (left, right) = samples.into_parts();
+        if let (Some(left), Some(right)) = (&left, &right) {
+            let first_lines = |lines: &[String]| {
+                lines[..lines.len().min(TIMESTAMP_DETECTION_SAMPLE_LINES)].to_vec()
+            };
+            let samples = [first_lines(left), first_lines(right)].concat();
+            self.propose_timestamp_pattern(&samples);
+        }
+        let pattern_test_lines = |lines: Option<Vec<String>>| {
+            let mut lines = lines.unwrap_or_default();
+            lines.truncate(PATTERN_TEST_SAMPLE_LINES);
+            lines
+        };
+        self.left_sample_lines = pattern_test_lines(left);
+        self.right_sample_lines = pattern_test_lines(right);
+        self.refresh_pattern_test();
+        self.trigger_diff_if_ready();
+    }
+
+    fn handle_timestamp_input_changed(&mut self) {
+        let is_valid = self.validate_timestamp_pattern();
+        // [CSV-UX-TimestampFeedbackV3] Restart the debounce on every valid edit; an invalid
+        // edit drops the pending commit so half-typed patterns are never diffed.
+        self.pending_pattern_commit = is_valid.then(|| self.clock.now() + TIMESTAMP_DEBOUNCE_DELAY);
+        if is_valid {
+            self.schedule_debounce_timer();
+        }
+        // [CSV-UX-PatternTesterV1] The preview follows every edit, without the debounce.
+        self.refresh_pattern_test();
+    }
+
+    /// [CSV-UI-MovedBlocksV1] The engines group moved lines into blocks, so a new minimum
+    /// re-diffs once typing pauses.
+    fn handle_min_moved_block_size_changed(&mut self, text: &str) {
+        let size = text.trim().parse::<usize>().ok().filter(|size| *size > 0);
+
+        let is_valid = size.is_some();
+        if is_valid != self.min_moved_block_size_is_valid {
+            self.min_moved_block_size_is_valid = is_valid;
+            self.apply_input_validity_style(CONTROL_ID_MIN_MOVED_BLOCK_SIZE, is_valid);
+        }
+        let Some(size) = size else {
+            self.pending_min_moved_block_commit = None;
+            return;
+        };
+
+        self.min_moved_block_size = size;
+        self.pending_min_moved_block_commit = Some(self.clock.now() + TIMESTAMP_DEBOUNCE_DELAY);
+        self.schedule_debounce_timer();
+    }
+
+    /// [CSV-File-DirectoryCompareV1] Re-pairs the directories once typing pauses.
+    fn handle_pairing_pattern_changed(&mut self, text: String) {
+        let is_valid = text.trim().is_empty() || Regex::new(text.trim()).is_ok();
+        if is_valid != self.pairing_pattern_is_valid {
+            self.pairing_pattern_is_valid = is_valid;
+            self.apply_input_validity_style(CONTROL_ID_PAIRING_PATTERN, is_valid);
+        }
+        self.pairing_pattern = text;
+        self.pending_pairing_commit = is_valid.then(|| self.clock.now() + TIMESTAMP_DEBOUNCE_DELAY);
+        if is_valid {
+            self.schedule_debounce_timer();
+        }
+    }
+
+    /// [CSV-UX-PatternTesterV1] Shows what the current patterns, placeholder and rules make
+    /// of the sampled lines of each loaded file.
+    fn refresh_pattern_test(&mut self) {
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let (left_patterns, right_patterns) = self.timestamp_pattern_lists();
+        let sides = [
+            (
+                "Left",
+                "Timestamp",
+                &self.left_source,
+                &self.left_sample_lines,
+                &left_patterns,
+            ),
+            (
+                "Right",
+                "Right-file timestamp",
+                &self.right_source,
+                &self.right_sample_lines,
+                &right_patterns,
+            ),
+        ];
+        let mut results: Vec<(&str, PatternTestResult)> = Vec::new();
+        let mut invalid = None;
+        for (side, subject, source, lines, patterns) in sides {
+            if source.is_none() {
+                continue;
+            }
+            match test_pattern_list(
+                lines,
+                patterns,
+                &self.timestamp_replacement,
+                &self.line_normalizer,
+            ) {
+                Ok(result) => results.push((side, result)),
+                Err(err) => {
+                    invalid = Some(describe_invalid_timestamp_pattern(subject, &err));
+                    break;
+                }
+            }
+        }
+        let text = invalid.unwrap_or_else(|| build_pattern_test_text(&results));
+
+        if text == self.pattern_test_text {
+            return;
+        }
+        self.pattern_test_text = text;
+        self.enqueue_command(PlatformCommand::SetInputText {
+            window_id,
+            control_id: CONTROL_ID_PATTERN_TESTER,
+            text: self.pattern_test_text.clone(),
+        });
+    }
+
+    fn handle_normalization_rules_changed(&mut self, text: &str) {
+        log::debug!("[CSV-Core-NormalizationV1] Normalization rules edited");
+        // [CSV-Core-NormalizationV1] Keep diffing with the last valid rules while the user
+        // is still typing; only a set that parses and compiles replaces them.
+        match parse_rules(text).and_then(|rules| self.apply_normalization_rules(rules)) {
+            Ok(()) => {
+                self.pending_rules_commit = Some(self.clock.now() + TIMESTAMP_DEBOUNCE_DELAY);
+                self.schedule_debounce_timer();
+                self.refresh_pattern_test();
+            }
+            Err(err) => {
+                log::debug!("[CSV-Core-NormalizationV1] Rejecting rules: {err}");
+                self.set_normalization_rules_validity(false);
+                self.pending_rules_commit = None;
+            }
+        }
+    }
+
+    /// [CSV-UI-NormalizationRulesV1] Appends the common rules that are not present yet,
+    /// matched by name, and diffs with them right away.
+    fn add_common_normalization_rules(&mut self) {
+        let mut rules = self.normalization_rules.clone();
+        for rule in common_normalization_rules() {
+            if !rules.iter().any(|existing| existing.name() == rule.name()) {
+                rules.push(rule);
+            }
+        }
+
+        if let Err(err) = self.apply_normalization_rules(rules) {
+            log::error!("[CSV-Core-NormalizationV1] Common rules failed to compile: {err}");
+            return;
+        }
+        if let Some(window_id) = self.active_window {
+            self.enqueue_command(PlatformCommand::SetInputText {
+                window_id,
+                control_id: CONTROL_ID_NORMALIZATION_RULES,
+                text: format_rules(&self.normalization_rules),
+            });
+        }
+        self.pending_rules_commit = None;
+        self.refresh_pattern_test();
+        self.trigger_diffs_if_ready();
+    }
+
+    fn apply_normalization_rules(
+        &mut self,
+        rules: Vec<NormalizationRule>,
+    ) -> Result<(), NormalizationError> {
+        let normalizer = LineNormalizer::new(&rules)?;
+        self.normalization_rules = rules;
+        self.line_normalizer = Arc::new(normalizer);
+        self.set_normalization_rules_validity(true);
+        Ok(())
+    }
+
+    /// Diffs with the edited pattern, rules and minimum moved block size, and re-pairs the
+    /// compared directories, once the debounce period has passed without further edits, and
+    /// only then records the pattern in the history.
+    fn commit_debounced_edits(&mut self) {
+        let now = self.clock.now();
+        let pattern_due = self
+            .pending_pattern_commit
+            .is_some_and(|deadline| now >= deadline);
+        let rules_due = self
+            .pending_rules_commit
+            .is_some_and(|deadline| now >= deadline);
+        let pairing_due = self
+            .pending_pairing_commit
+            .is_some_and(|deadline| now >= deadline);
+        let min_moved_block_due = self
+            .pending_min_moved_block_commit
+            .is_some_and(|deadline| now >= deadline);
+
+        if pattern_due {
+            self.pending_pattern_commit = None;
+            log::debug!(
+                "[CSV-UX-TimestampFeedbackV3] Committing timestamp pattern '{}'",
+                self.timestamp_pattern
+            );
+            self.record_timestamp_pattern_history();
+        }
+        if rules_due {
+            self.pending_rules_commit = None;
+            log::debug!("[CSV-Core-NormalizationV1] Committing normalization rules");
+        }
+        if min_moved_block_due {
+            self.pending_min_moved_block_commit = None;
+            log::debug!(
+                "[CSV-UI-MovedBlocksV1] Committing minimum moved block size {}",
+                self.min_moved_block_size
+            );
+        }
+        if pairing_due {
+            self.pending_pairing_commit = None;
+            log::debug!("[CSV-File-DirectoryCompareV1] Committing pairing pattern");
+            if let Some(comparison) = self.directory_comparison.as_mut() {
+                // Other pairs may come out, so the shown one is no longer known.
+                comparison.shown = None;
             }
+        }
+
+        // Directory summaries count changed lines, which moved blocks leave alone.
+        if pattern_due || rules_due || min_moved_block_due {
             self.trigger_diff_if_ready();
         }
+        // The directories are paired with the pattern and diffed with the rules, so any
+        // of the three commits compares them again, but only once.
+        if pattern_due || rules_due || pairing_due {
+            self.trigger_directory_comparison();
+        }
     }
 
-    fn handle_timestamp_input_changed(&mut self, control_id: ControlId, text: String) {
-        if control_id != CONTROL_ID_TIMESTAMP_INPUT {
+    /// [CSV-UX-TimestampFeedbackV3] Arms the platform's one-shot debounce timer for the
+    /// earliest pending commit. Arming it again replaces the previous deadline.
+    fn schedule_debounce_timer(&mut self) {
+        let Some(window_id) = self.active_window else {
             return;
-        }
+        };
+        let Some(deadline) = [
+            self.pending_pattern_commit,
+            self.pending_rules_commit,
+            self.pending_pairing_commit,
+            self.pending_min_moved_block_commit,
+        ]
+        .into_iter()
+        .flatten()
+        .min() else {
+            return;
+        };
 
-        log::debug!("[CSV-UX-TimestampFeedbackV2] Timestamp input changed to '{text}'");
-        self.timestamp_pattern = text;
-        let is_valid = self.validate_timestamp_pattern();
-        if is_valid {
-            self.record_timestamp_pattern_history();
-            self.trigger_diff_if_ready();
-        }
+        self.enqueue_command(PlatformCommand::StartTimer {
+            window_id,
+            timer_id: TIMER_ID_DEBOUNCE,
+            delay: deadline.saturating_duration_since(self.clock.now()),
+        });
     }
 
     fn record_timestamp_pattern_history(&mut self) {
@@ -182,10 +1193,30 @@ impl AppLogic {
         match self.settings_manager.load_settings(&self.app_identifier) {
             Ok(settings) => {
                 log::info!("[CSV-Tech-SettingsPersistenceV1] Loaded persisted settings");
-                self.left_file_path = settings.left_file_path().cloned();
-                self.right_file_path = settings.right_file_path().cloned();
+                self.left_source = settings.left_file_path().cloned();
+                self.right_source = settings.right_file_path().cloned();
+                self.left_encoding = settings.left_encoding();
+                self.right_encoding = settings.right_encoding();
                 self.timestamp_pattern = settings.timestamp_pattern().to_string();
+                self.right_timestamp_pattern = settings.right_timestamp_pattern().to_string();
+                self.timestamp_replacement = settings.timestamp_replacement().to_string();
+                self.timestamp_format = settings.timestamp_format().to_string();
                 self.timestamp_history = settings.timestamp_history().clone();
+                self.timestamp_presets = settings.timestamp_presets().to_vec();
+                if !self.timestamp_presets.is_empty()
+                    || self.left_encoding.is_some()
+                    || self.right_encoding.is_some()
+                {
+                    self.refresh_main_menu();
+                }
+                self.diff_algorithm = settings.diff_algorithm();
+                self.comparison_algorithms = settings.comparison_algorithms().to_vec();
+                self.restore_comparison_algorithm();
+                self.min_moved_block_size = settings.min_moved_block_size();
+                self.slowdown_ratio = settings.slowdown_ratio();
+                self.regex_cache_capacity = settings.regex_cache_capacity();
+                self.timestamp_parser
+                    .set_cache_capacity(self.regex_cache_capacity);
                 while self.timestamp_history.len() > MAX_TIMESTAMP_HISTORY {
                     self.timestamp_history.pop_back();
                 }
@@ -203,9 +1234,54 @@ impl AppLogic {
                     control_id: CONTROL_ID_TIMESTAMP_INPUT,
                     text: self.timestamp_pattern.clone(),
                 });
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+                    text: self.right_timestamp_pattern.clone(),
+                });
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_TIMESTAMP_REPLACEMENT,
+                    text: self.timestamp_replacement.clone(),
+                });
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+                    text: self.timestamp_format.clone(),
+                });
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+                    text: self.min_moved_block_size.to_string(),
+                });
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_SLOWDOWN_RATIO,
+                    text: self.slowdown_ratio.to_string(),
+                });
+                self.pairing_pattern = settings.pairing_pattern().to_string();
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_PAIRING_PATTERN,
+                    text: self.pairing_pattern.clone(),
+                });
 
                 self.validate_timestamp_pattern();
-                self.trigger_diff_if_ready();
+
+                if let Err(err) =
+                    self.apply_normalization_rules(settings.normalization_rules().to_vec())
+                {
+                    log::error!(
+                        "[CSV-Core-NormalizationV1] Ignoring persisted normalization rules: {err}"
+                    );
+                }
+                self.enqueue_command(PlatformCommand::SetInputText {
+                    window_id,
+                    control_id: CONTROL_ID_NORMALIZATION_RULES,
+                    text: format_rules(&self.normalization_rules),
+                });
+
+                self.sample_files_then_diff();
             }
             Err(err) => {
                 log::error!("[CSV-Tech-SettingsPersistenceV1] Failed to load settings: {err}");
@@ -215,11 +1291,23 @@ impl AppLogic {
 
     fn persist_settings(&self) {
         let snapshot = AppSettings::with_values(
-            self.left_file_path.clone(),
-            self.right_file_path.clone(),
+            self.left_source.clone(),
+            self.right_source.clone(),
             self.timestamp_pattern.clone(),
             self.timestamp_history.clone(),
-        );
+            self.diff_algorithm,
+        )
+        .with_file_encodings(self.left_encoding, self.right_encoding)
+        .with_right_timestamp_pattern(self.right_timestamp_pattern.clone())
+        .with_timestamp_replacement(self.timestamp_replacement.clone())
+        .with_timestamp_format(self.timestamp_format.clone())
+        .with_normalization_rules(self.normalization_rules.clone())
+        .with_slowdown_ratio(self.slowdown_ratio)
+        .with_regex_cache_capacity(self.regex_cache_capacity)
+        .with_comparison_algorithms(self.comparison_algorithms.clone())
+        .with_min_moved_block_size(self.min_moved_block_size)
+        .with_timestamp_presets(self.timestamp_presets.clone())
+        .with_pairing_pattern(self.pairing_pattern.clone());
 
         if let Err(err) = self
             .settings_manager
@@ -232,72 +1320,235 @@ impl AppLogic {
     }
 
     fn trigger_diff_if_ready(&mut self) {
-        if !self.timestamp_pattern_is_valid {
+        if !self.timestamp_pattern_is_valid
+            || !self.right_timestamp_pattern_is_valid
+            || !self.normalization_rules_are_valid
+        {
             return;
         }
 
-        let Some(window_id) = self.active_window else {
+        if self.active_window.is_none() {
+            return;
+        }
+
+        let (Some(left_source), Some(right_source)) =
+            (self.left_source.clone(), self.right_source.clone())
+        else {
+            return;
+        };
+
+        // [CSV-Core-LargeFileV1][CSV-UX-ResponsiveV1] Load and diff on the job executor so
+        // the UI thread stays free; a newer request supersedes any job still running.
+        let request = self.diff_request(left_source, right_source);
+        self.diff_jobs.submit(request);
+        // An executor that ran the job inline sends no wake-up; its result is ready now.
+        self.apply_finished_diff_job();
+    }
+
+    /// Re-diffs the shown files and, per [CSV-File-DirectoryCompareV1], every pair of the
+    /// compared directories, after a change that applies to all comparisons.
+    fn trigger_diffs_if_ready(&mut self) {
+        self.trigger_diff_if_ready();
+        self.trigger_directory_comparison();
+    }
+
+    /// [CSV-File-DirectoryCompareV1] Compares `left` and `right` pair by pair.
+    fn start_directory_comparison(&mut self, left: PathBuf, right: PathBuf) {
+        log::info!(
+            "[CSV-File-DirectoryCompareV1] Comparing '{}' with '{}'",
+            left.display(),
+            right.display()
+        );
+        self.directory_comparison = Some(DirectoryComparison {
+            left,
+            right,
+            summaries: None,
+            algorithm: self.diff_algorithm,
+            shown: None,
+        });
+        self.trigger_directory_comparison();
+    }
+
+    /// [CSV-File-DirectoryCompareV1] Pairs and diffs the compared directories again with the
+    /// current patterns, rules and algorithm.
+    fn trigger_directory_comparison(&mut self) {
+        if !self.timestamp_pattern_is_valid
+            || !self.right_timestamp_pattern_is_valid
+            || !self.normalization_rules_are_valid
+            || !self.pairing_pattern_is_valid
+            || self.active_window.is_none()
+        {
             return;
+        }
+        let Some(comparison) = self.directory_comparison.as_ref() else {
+            return;
+        };
+        let (left, right) = (comparison.left.clone(), comparison.right.clone());
+
+        let name_pattern = match self.pairing_pattern.trim() {
+            "" => None,
+            pattern => Regex::new(pattern).ok(),
         };
+        // The sources are replaced by each pair's files.
+        let diff = self.diff_request(
+            LogSource::File(left.clone()),
+            LogSource::File(right.clone()),
+        );
+        let request = DirectoryJobRequest::new(left, right, diff).with_name_pattern(name_pattern);
+        if let Some(comparison) = self.directory_comparison.as_mut() {
+            comparison.summaries = None;
+            // [CSV-Diff-AlgorithmChoiceV1] The summary names the algorithm it was diffed with.
+            comparison.algorithm = self.diff_algorithm;
+        }
+        self.refresh_main_menu();
+        self.directory_jobs.submit(request);
+        self.apply_finished_directory_job();
+    }
 
-        let (Some(left_path), Some(right_path)) =
-            (self.left_file_path.clone(), self.right_file_path.clone())
+    /// [CSV-File-DirectoryCompareV1] Shows the pair at `index` of the summary side by side.
+    fn show_directory_pair(&mut self, index: usize) {
+        let Some(comparison) = self.directory_comparison.as_mut() else {
+            return;
+        };
+        let Some((left, right)) = comparison
+            .summaries
+            .as_ref()
+            .and_then(|summaries| summaries.get(index))
+            .and_then(|summary| summary.pair().files())
+            .map(|(left, right)| (left.to_path_buf(), right.to_path_buf()))
         else {
             return;
         };
 
-        match self.execute_diff(&left_path, &right_path) {
-            Ok(diff_lines) => {
-                self.diff_lines = diff_lines.clone();
+        log::info!(
+            "[CSV-File-DirectoryCompareV1] Showing '{}' and '{}'",
+            left.display(),
+            right.display()
+        );
+        comparison.shown = Some(index);
+        self.left_source = Some(LogSource::File(left));
+        self.right_source = Some(LogSource::File(right));
+        // [CSV-File-EncodingV1] A chosen encoding belongs to the file it was chosen for.
+        self.left_encoding = None;
+        self.right_encoding = None;
+        self.refresh_main_menu();
+        self.restore_comparison_algorithm();
+        self.sample_files_then_diff();
+    }
+
+    /// A diff of `left_source` and `right_source` with the current settings.
+    fn diff_request(&self, left_source: LogSource, right_source: LogSource) -> DiffJobRequest {
+        let (left_patterns, right_patterns) = self.timestamp_pattern_lists();
+        DiffJobRequest::new(
+            left_source,
+            right_source,
+            TimestampStripping::new(
+                left_patterns,
+                right_patterns,
+                self.timestamp_replacement.clone(),
+            )
+            .with_format(self.timestamp_format.clone()),
+            Arc::clone(&self.line_normalizer),
+            Arc::clone(self.active_diff_engine()),
+            Arc::clone(&self.timestamp_parser),
+        )
+        .with_min_moved_block_size(self.min_moved_block_size)
+        .with_encodings(self.left_encoding, self.right_encoding)
+        .with_decompressors(Arc::clone(&self.decompressors))
+    }
+
+    /// The timestamp patterns of the left and the right file.
+    fn timestamp_pattern_lists(&self) -> (Vec<String>, Vec<String>) {
+        // [CSV-Core-TSPatternListV1] Without its own list the right file uses the shared one.
+        let left_patterns = split_pattern_list(&self.timestamp_pattern);
+        let right_patterns = match split_pattern_list(&self.right_timestamp_pattern) {
+            patterns if patterns.is_empty() => left_patterns.clone(),
+            patterns => patterns,
+        };
+        (left_patterns, right_patterns)
+    }
+
+    /// Turns the results of the latest jobs, if finished, into viewer commands. Samples come
+    /// last, since the diff they start supersedes any diff that finished alongside them.
+    fn apply_finished_jobs(&mut self) {
+        self.apply_finished_diff_job();
+        self.apply_finished_directory_job();
+        self.apply_finished_sample_job();
+    }
+
+    fn apply_finished_diff_job(&mut self) {
+        let Some(completion) = self.diff_jobs.take_finished() else {
+            return;
+        };
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let job_id = completion.job_id();
+        match completion.into_outcome() {
+            Ok(output) => {
+                log::debug!("[CSV-UX-ResponsiveV1] Applying result of diff job {job_id:?}");
+                let (left_decoding, right_decoding) = output.decodings();
+                self.set_decoding_warning(describe_decoding_problems(
+                    left_decoding,
+                    right_decoding,
+                ));
+                let (left_origins, right_origins) = output.line_origins();
+                self.left_line_origins = left_origins.clone();
+                self.right_line_origins = right_origins.clone();
+                let (diff_result, left_timestamps, right_timestamps) = output.into_parts();
+                let (diff_lines, moved_blocks) = diff_result.into_parts();
+                // [CSV-UI-MovedBlocksV1] Keep the blocks and map them onto viewer rows.
+                self.moved_blocks = moved_blocks;
+                self.moved_block_connectors =
+                    build_moved_block_connectors(&diff_lines, &self.moved_blocks);
                 self.enqueue_diff_commands(window_id, &diff_lines);
+                self.diff_lines = diff_lines;
+                // [CSV-Core-TimingV1] Keep the times so a new threshold needs no re-diff.
+                self.left_timestamps = left_timestamps;
+                self.right_timestamps = right_timestamps;
+                self.refresh_timing_analysis();
+                self.enqueue_timing_column(window_id);
+            }
+            Err(DiffWorkflowError::Cancelled) => {
+                log::debug!("[CSV-UX-ResponsiveV1] Diff job {job_id:?} was cancelled");
             }
             Err(err) => self.enqueue_error_dialog(window_id, err),
         }
     }
 
-    fn execute_diff(
-        &self,
-        left_path: &Path,
-        right_path: &Path,
-    ) -> Result<Vec<DiffLine>, DiffWorkflowError> {
-        let left_lines = read_file_lines(left_path).map_err(|source| DiffWorkflowError::Io {
-            path: left_path.to_path_buf(),
-            source,
-        })?;
-        let right_lines = read_file_lines(right_path).map_err(|source| DiffWorkflowError::Io {
-            path: right_path.to_path_buf(),
-            source,
-        })?;
-
-        let stripped_left = self
-            .timestamp_parser
-            .strip_timestamps(&left_lines, &self.timestamp_pattern)
-            .map_err(DiffWorkflowError::Timestamp)?;
-        let stripped_right = self
-            .timestamp_parser
-            .strip_timestamps(&right_lines, &self.timestamp_pattern)
-            .map_err(DiffWorkflowError::Timestamp)?;
-        debug_assert_eq!(left_lines.len(), stripped_left.len());
-        debug_assert_eq!(right_lines.len(), stripped_right.len());
-
-        let comparable_left = Self::build_comparable_lines(&left_lines, &stripped_left);
-        let comparable_right = Self::build_comparable_lines(&right_lines, &stripped_right);
-
-        let diff_result = self
-            .diff_engine
-            .compute_diff(&comparable_left, &comparable_right);
-        Ok(diff_result.lines().to_vec())
-    }
-
-    fn build_comparable_lines(original: &[String], stripped: &[String]) -> Vec<ComparableLine> {
-        debug_assert_eq!(original.len(), stripped.len());
-        original
-            .iter()
-            .zip(stripped.iter())
-            .map(|(original_text, comparable_text)| {
-                ComparableLine::new(original_text.clone(), comparable_text.clone())
-            })
-            .collect()
+    /// [CSV-File-DirectoryCompareV1] Lists the pairs of the finished directory comparison
+    /// in the menu.
+    fn apply_finished_directory_job(&mut self) {
+        let Some(completion) = self.directory_jobs.take_finished() else {
+            return;
+        };
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        match completion.into_outcome() {
+            Ok(summaries) => {
+                log::info!(
+                    "[CSV-File-DirectoryCompareV1] Compared {} file pairs",
+                    summaries.len()
+                );
+                if let Some(comparison) = self.directory_comparison.as_mut() {
+                    comparison.summaries = Some(summaries);
+                }
+            }
+            Err(DiffWorkflowError::Cancelled) => return,
+            Err(err) => {
+                self.directory_comparison = None;
+                self.enqueue_command(PlatformCommand::ShowMessageBox {
+                    window_id,
+                    title: "Directory Comparison Failed".to_string(),
+                    message: err.to_string(),
+                    severity: MessageSeverity::Error,
+                });
+            }
+        }
+        self.refresh_main_menu();
     }
 
     fn enqueue_diff_commands(&mut self, window_id: WindowId, lines: &[DiffLine]) {
@@ -312,73 +1563,223 @@ impl AppLogic {
             control_id: CONTROL_ID_RIGHT_VIEWER,
             text: right_text,
         });
-    }
+        // [CSV-UI-MovedBlocksV1] The gutter between the viewers links moved blocks to
+        // where they came from, row for row.
+        self.enqueue_command(PlatformCommand::SetViewerContent {
+            window_id,
+            control_id: CONTROL_ID_MOVE_GUTTER,
+            text: build_gutter_text(lines.len(), &self.moved_block_connectors),
+        });
 
-    fn enqueue_error_dialog(&mut self, window_id: WindowId, error: DiffWorkflowError) {
-        let message = match &error {
-            DiffWorkflowError::Io { path, source } => {
-                format!("Failed to read '{}': {}", path.display(), source)
+        // [CSV-UI-HighlightV1] Both viewers share row indices, so one set of ranges colors both.
+        let ranges = build_line_style_ranges(lines);
+        for control_id in [CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER] {
+            for range in &ranges {
+                self.enqueue_command(PlatformCommand::ApplyStyleToLines {
+                    window_id,
+                    control_id,
+                    first_line: range.first_line(),
+                    line_count: range.line_count(),
+                    style_id: range.style_id(),
+                });
             }
-            DiffWorkflowError::Timestamp(TimestampParserError::InvalidPattern {
-                pattern,
-                message,
-            }) => format!(
-                "The timestamp pattern '{}' is invalid: {}",
-                pattern, message
-            ),
-            DiffWorkflowError::Timestamp(TimestampParserError::ProcessingFailed { message }) => {
-                format!("Failed to strip timestamps: {message}")
+        }
+
+        // [CSV-Diff-IntraLineV1] The changed words of modified rows stand out on top of the
+        // row color, each viewer with the spans of its own side.
+        let (left_spans, right_spans) = build_change_span_ranges(lines);
+        for (control_id, spans) in [
+            (CONTROL_ID_LEFT_VIEWER, left_spans),
+            (CONTROL_ID_RIGHT_VIEWER, right_spans),
+        ] {
+            for span in spans {
+                self.enqueue_command(PlatformCommand::ApplyStyleToCharRange {
+                    window_id,
+                    control_id,
+                    line: span.line(),
+                    first_char: span.first_char(),
+                    char_count: span.char_count(),
+                    style_id: StyleId::DiffChangedText,
+                });
             }
-        };
+        }
+    }
+
+    fn enqueue_timing_column(&mut self, window_id: WindowId) {
+        // [CSV-UI-TimingV1] One row per viewer row, like the move gutter.
+        self.enqueue_command(PlatformCommand::SetViewerContent {
+            window_id,
+            control_id: CONTROL_ID_TIMING_COLUMN,
+            text: build_timing_column_text(self.diff_lines.len(), &self.timing_analysis),
+        });
+    }
+
+    fn enqueue_error_dialog(&mut self, window_id: WindowId, error: DiffWorkflowError) {
+        if matches!(error, DiffWorkflowError::Cancelled) {
+            return;
+        }
 
         self.enqueue_command(PlatformCommand::ShowMessageBox {
             window_id,
             title: "Diff Failed".to_string(),
-            message,
+            message: error.to_string(),
             severity: MessageSeverity::Error,
         });
     }
 
-    fn path_for_dialog(&self, dialog: PendingFileDialog) -> Option<&PathBuf> {
+    fn source_for_dialog(&self, dialog: &PendingFileDialog) -> Option<&LogSource> {
         match dialog {
-            PendingFileDialog::Left => self.left_file_path.as_ref(),
-            PendingFileDialog::Right => self.right_file_path.as_ref(),
+            PendingFileDialog::Left(_) => self.left_source.as_ref(),
+            PendingFileDialog::Right(_) => self.right_source.as_ref(),
+            PendingFileDialog::LeftDirectory
+            | PendingFileDialog::RightDirectory { .. }
+            | PendingFileDialog::ImportPresets
+            | PendingFileDialog::ExportPresets => None,
         }
     }
 
+    /// Validates the timestamp inputs, styling each one and naming the first invalid
+    /// entry in the status bar. Only the patterns gate diffing.
     fn validate_timestamp_pattern(&mut self) -> bool {
-        let pattern = self.timestamp_pattern.clone();
-        let is_valid = pattern.is_empty() || Regex::new(&pattern).is_ok();
+        let left = validate_pattern_list(&split_pattern_list(&self.timestamp_pattern));
+        let right = validate_pattern_list(&split_pattern_list(&self.right_timestamp_pattern));
+        let format = validate_timestamp_format(&self.timestamp_format);
 
-        if is_valid != self.timestamp_pattern_is_valid {
+        let left_is_valid = left.is_ok();
+        if left_is_valid != self.timestamp_pattern_is_valid {
             log::debug!(
-                "[CSV-UX-TimestampFeedbackV2] Timestamp pattern validity toggled: prev={}, now={}, pattern='{}'",
-                self.timestamp_pattern_is_valid,
-                is_valid,
-                pattern
+                "[CSV-UX-TimestampFeedbackV3] Timestamp pattern validity toggled: now={}, pattern='{}'",
+                left_is_valid,
+                self.timestamp_pattern
             );
-            self.timestamp_pattern_is_valid = is_valid;
-            if let Some(window_id) = self.active_window {
-                // [CSV-UX-TimestampFeedbackV2] Keep the input styled to reflect regex validity.
-                let style_id = if is_valid {
-                    StyleId::DefaultInput
-                } else {
-                    StyleId::DefaultInputError
-                };
-                log::debug!(
-                    "[CSV-UX-TimestampFeedbackV2] Applying style {:?} for window {:?}",
-                    style_id,
-                    window_id
-                );
-                self.enqueue_command(PlatformCommand::ApplyStyleToControl {
-                    window_id,
-                    control_id: CONTROL_ID_TIMESTAMP_INPUT,
-                    style_id,
-                });
+            self.timestamp_pattern_is_valid = left_is_valid;
+            // [CSV-UX-TimestampFeedbackV3] Keep the input styled to reflect regex validity.
+            self.apply_input_validity_style(CONTROL_ID_TIMESTAMP_INPUT, left_is_valid);
+        }
+        self.set_timestamp_diagnostic(left.as_ref().err().map(describe_pattern_diagnostic));
+
+        let right_is_valid = right.is_ok();
+        if right_is_valid != self.right_timestamp_pattern_is_valid {
+            log::debug!(
+                "[CSV-UX-TimestampFeedbackV3] Right timestamp pattern validity toggled: now={}, pattern='{}'",
+                right_is_valid,
+                self.right_timestamp_pattern
+            );
+            self.right_timestamp_pattern_is_valid = right_is_valid;
+            self.apply_input_validity_style(CONTROL_ID_RIGHT_TIMESTAMP_INPUT, right_is_valid);
+        }
+
+        let format_is_valid = format.is_ok();
+        if format_is_valid != self.timestamp_format_is_valid {
+            self.timestamp_format_is_valid = format_is_valid;
+            // [CSV-Core-TSExtractV1] Same feedback for the format description.
+            self.apply_input_validity_style(CONTROL_ID_TIMESTAMP_FORMAT, format_is_valid);
+        }
+
+        // [CSV-UX-TimestampFeedbackV3] Say which entry is wrong, not just that one is.
+        let status = match (&left, &right, &format) {
+            (Err(err), _, _) => Some(describe_invalid_timestamp_pattern("Timestamp", err)),
+            (Ok(()), Err(err), _) => Some(describe_invalid_timestamp_pattern(
+                "Right-file timestamp",
+                err,
+            )),
+            (Ok(()), Ok(()), Err(TimestampExtractorError::InvalidFormat { message, .. })) => {
+                Some(format!("Timestamp format is invalid: {message}"))
             }
+            (Ok(()), Ok(()), Err(err)) => Some(err.to_string()),
+            (Ok(()), Ok(()), Ok(())) => None,
+        };
+        self.set_timestamp_status(status);
+
+        left_is_valid && right_is_valid
+    }
+
+    fn set_timestamp_status(&mut self, status: Option<String>) {
+        self.timestamp_status = status;
+        self.refresh_status_bar();
+    }
+
+    fn set_decoding_warning(&mut self, warning: Option<String>) {
+        if let Some(warning) = &warning {
+            log::warn!("[CSV-File-EncodingV1] {warning}");
+        }
+        self.decoding_warning = warning;
+        self.refresh_status_bar();
+    }
+
+    /// An invalid timestamp input blocks the diff, so it takes the status bar before a
+    /// decoding warning does.
+    fn refresh_status_bar(&mut self) {
+        let (text, severity) = match (&self.timestamp_status, &self.decoding_warning) {
+            (Some(status), _) => (Some(status.clone()), MessageSeverity::Error),
+            (None, Some(warning)) => (Some(warning.clone()), MessageSeverity::Warning),
+            (None, None) => (None, MessageSeverity::Information),
+        };
+        if text == self.status_text {
+            return;
+        }
+
+        self.status_text = text;
+        if let Some(window_id) = self.active_window {
+            self.enqueue_command(PlatformCommand::UpdateLabelText {
+                window_id,
+                control_id: LABEL_STATUS,
+                text: self.status_text.clone().unwrap_or_default(),
+                severity,
+            });
+        }
+    }
+
+    /// [CSV-UX-RegexDiagnosticsV1] Shows where and why the pattern input fails next to it.
+    fn set_timestamp_diagnostic(&mut self, diagnostic: Option<String>) {
+        if diagnostic == self.timestamp_diagnostic {
+            return;
+        }
+
+        self.timestamp_diagnostic = diagnostic;
+        if let Some(window_id) = self.active_window {
+            let severity = if self.timestamp_diagnostic.is_some() {
+                MessageSeverity::Error
+            } else {
+                MessageSeverity::Information
+            };
+            self.enqueue_command(PlatformCommand::UpdateLabelText {
+                window_id,
+                control_id: LABEL_TIMESTAMP_DIAGNOSTIC,
+                text: self.timestamp_diagnostic.clone().unwrap_or_default(),
+                severity,
+            });
+        }
+    }
+
+    fn apply_input_validity_style(&mut self, control_id: ControlId, is_valid: bool) {
+        let Some(window_id) = self.active_window else {
+            return;
+        };
+
+        let style_id = if is_valid {
+            StyleId::DefaultInput
+        } else {
+            StyleId::DefaultInputError
+        };
+        log::debug!(
+            "[CSV-UX-TimestampFeedbackV3] Applying style {style_id:?} to {control_id:?} for window {window_id:?}"
+        );
+        self.enqueue_command(PlatformCommand::ApplyStyleToControl {
+            window_id,
+            control_id,
+            style_id,
+        });
+    }
+
+    fn set_normalization_rules_validity(&mut self, is_valid: bool) {
+        if is_valid == self.normalization_rules_are_valid {
+            return;
         }
 
-        is_valid
+        self.normalization_rules_are_valid = is_valid;
+        // [CSV-UI-NormalizationRulesV1] Same red-background feedback as the pattern input.
+        self.apply_input_validity_style(CONTROL_ID_NORMALIZATION_RULES, is_valid);
     }
 }
 
@@ -390,12 +1791,14 @@ impl PlatformEventHandler for AppLogic {
                 self.load_and_apply_settings(window_id);
             }
             AppEvent::MenuActionClicked { action_id } => self.handle_menu_action(action_id),
-            AppEvent::FileOpenProfileDialogCompleted { window_id, result } => {
+            AppEvent::FileOpenProfileDialogCompleted { window_id, result }
+            | AppEvent::FileSaveDialogCompleted { window_id, result }
+            | AppEvent::FolderPickerDialogCompleted { window_id, result } => {
                 self.handle_file_dialog_result(window_id, result)
             }
             AppEvent::InputTextChanged {
                 control_id, text, ..
-            } => self.handle_timestamp_input_changed(control_id, text),
+            } => self.handle_input_text_changed(control_id, text),
             AppEvent::ControlScrolled {
                 window_id,
                 control_id,
@@ -416,15 +1819,32 @@ impl PlatformEventHandler for AppLogic {
 
                 if let Some(target_id) = target_control_id {
                     self.is_syncing_scroll = true;
-                    self.enqueue_command(PlatformCommand::SetScrollPosition {
-                        window_id,
-                        control_id: target_id,
-                        vertical_pos,
-                        horizontal_pos: 0,
-                    });
+                    // [CSV-UI-MovedBlocksV1][CSV-UI-TimingV1] The move gutter and the timing
+                    // column scroll along with the viewers.
+                    for control_id in [target_id, CONTROL_ID_MOVE_GUTTER, CONTROL_ID_TIMING_COLUMN]
+                    {
+                        self.enqueue_command(PlatformCommand::SetScrollPosition {
+                            window_id,
+                            control_id,
+                            vertical_pos,
+                            horizontal_pos: 0,
+                        });
+                    }
                     self.is_syncing_scroll = false;
                 }
             }
+            AppEvent::TimerElapsed {
+                window_id,
+                timer_id,
+            } if timer_id == TIMER_ID_DEBOUNCE && Some(window_id) == self.active_window => {
+                self.commit_debounced_edits();
+                // A timer that fired a little early leaves its commit pending; wait for it.
+                self.schedule_debounce_timer();
+            }
+            AppEvent::WakeRequested { window_id } if Some(window_id) == self.active_window => {
+                // [CSV-UX-ResponsiveV1] A worker finished; its commands go out with this event.
+                self.apply_finished_jobs();
+            }
             AppEvent::WindowCloseRequestedByUser { window_id } => {
                 if Some(window_id) == self.active_window {
                     // [CSV-UI-ExitCommandV1] Mirror File/Exit for the window close button.
@@ -433,6 +1853,9 @@ impl PlatformEventHandler for AppLogic {
             }
             AppEvent::WindowDestroyed { window_id } => {
                 if Some(window_id) == self.active_window {
+                    self.diff_jobs.cancel_active();
+                    self.sample_jobs.cancel_active();
+                    self.directory_jobs.cancel_active();
                     self.active_window = None;
                 }
             }
@@ -455,43 +1878,248 @@ impl UiStateProvider for AppLogic {
     }
 }
 
-#[derive(Debug)]
-enum DiffWorkflowError {
-    Io { path: PathBuf, source: io::Error },
-    Timestamp(TimestampParserError),
+/// Status bar text for an invalid timestamp pattern. Regex errors span several lines with
+/// the pattern and a caret; only their reason and column fit the status bar.
+fn describe_invalid_timestamp_pattern(subject: &str, error: &TimestampParserError) -> String {
+    match error {
+        TimestampParserError::InvalidPattern { index, kind, .. } => {
+            format!("{subject} pattern #{} is invalid: {kind}", index + 1)
+        }
+        other => other.to_string(),
+    }
+}
+
+/// [CSV-File-EncodingV1] Status bar warning naming the files that did not decode cleanly.
+fn describe_decoding_problems(left: DecodingOutcome, right: DecodingOutcome) -> Option<String> {
+    let problems: Vec<String> = [("Left", left), ("Right", right)]
+        .into_iter()
+        .filter_map(|(side, outcome)| describe_decoding(side, outcome))
+        .collect();
+    (!problems.is_empty()).then(|| {
+        format!(
+            "{} Choose the encoding in the File menu.",
+            problems.join(" ")
+        )
+    })
+}
+
+fn describe_decoding(side: &str, outcome: DecodingOutcome) -> Option<String> {
+    let encoding = outcome.encoding();
+    if outcome.is_lossy() {
+        Some(format!(
+            "{side} file is not valid {encoding}; undecodable bytes are shown as \u{FFFD}."
+        ))
+    } else if outcome.was_detected() && encoding == TextEncoding::Windows1252 {
+        Some(format!(
+            "{side} file is not UTF-8 and was read as {encoding}."
+        ))
+    } else {
+        None
+    }
+}
+
+/// [CSV-UX-RegexDiagnosticsV1] Short text for the label next to the pattern input, e.g.
+/// `#2, column 5: unclosed group`.
+fn describe_pattern_diagnostic(error: &TimestampParserError) -> String {
+    match error {
+        TimestampParserError::InvalidPattern { index, kind, .. } => match kind {
+            PatternErrorKind::Syntax {
+                description,
+                span: Some(span),
+            } => format!(
+                "#{}, column {}: {description}",
+                index + 1,
+                span.start_column()
+            ),
+            PatternErrorKind::Syntax {
+                description,
+                span: None,
+            } => format!("#{}: {description}", index + 1),
+            PatternErrorKind::TooBig { .. } => format!("#{}: {kind}", index + 1),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// Contiguous run of viewer rows sharing one highlight style per [CSV-UI-HighlightV1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StyledLineRange {
+    first_line: usize,
+    line_count: usize,
+    style_id: StyleId,
+}
+
+impl StyledLineRange {
+    pub(crate) fn new(first_line: usize, line_count: usize, style_id: StyleId) -> Self {
+        Self {
+            first_line,
+            line_count,
+            style_id,
+        }
+    }
+
+    pub(crate) fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    pub(crate) fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub(crate) fn style_id(&self) -> StyleId {
+        self.style_id
+    }
+}
+
+/// Highlight style for a row; the styles themselves are defined in `ui_description_layer`.
+/// Unchanged rows keep the viewer's own background.
+pub(crate) fn style_for_state(state: DiffState) -> Option<StyleId> {
+    match state {
+        DiffState::Unchanged => None,
+        DiffState::Added => Some(StyleId::DiffAdded),
+        DiffState::Deleted => Some(StyleId::DiffDeleted),
+        DiffState::Moved => Some(StyleId::DiffMoved),
+        DiffState::Modified => Some(StyleId::DiffModified),
+    }
+}
+
+/// Collapses consecutive highlighted rows with the same state into one styled range each,
+/// so a diff costs a command per run of changes rather than per row.
+pub(crate) fn build_line_style_ranges(lines: &[DiffLine]) -> Vec<StyledLineRange> {
+    let mut ranges: Vec<StyledLineRange> = Vec::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        let Some(style_id) = style_for_state(line.state()) else {
+            continue;
+        };
+        match ranges.last_mut() {
+            Some(last) if last.style_id == style_id && last.first_line + last.line_count == row => {
+                last.line_count += 1
+            }
+            _ => ranges.push(StyledLineRange::new(row, 1, style_id)),
+        }
+    }
+
+    ranges
+}
+
+/// Characters of one viewer row highlighted as changed per [CSV-Diff-IntraLineV1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StyledCharRange {
+    line: usize,
+    first_char: usize,
+    char_count: usize,
 }
 
-fn read_file_lines(path: &Path) -> io::Result<Vec<String>> {
-    let file = File::open(path)?;
-    BufReader::new(file).lines().collect()
+impl StyledCharRange {
+    pub(crate) fn new(line: usize, first_char: usize, char_count: usize) -> Self {
+        Self {
+            line,
+            first_char,
+            char_count,
+        }
+    }
+
+    pub(crate) fn line(&self) -> usize {
+        self.line
+    }
+
+    pub(crate) fn first_char(&self) -> usize {
+        self.first_char
+    }
+
+    pub(crate) fn char_count(&self) -> usize {
+        self.char_count
+    }
 }
 
-fn build_viewer_text(lines: &[DiffLine]) -> (String, String) {
-    let mut left_buffer = Vec::with_capacity(lines.len());
-    let mut right_buffer = Vec::with_capacity(lines.len());
+/// Characters in front of the line text of every viewer row: the state marker and a space,
+/// as written by `push_row`.
+const ROW_PREFIX_CHARS: usize = 2;
+
+/// Change spans of the `Modified` rows as character ranges of the left and the right viewer
+/// rows. Spans count bytes of the line text, the viewers count characters.
+pub(crate) fn build_change_span_ranges(
+    lines: &[DiffLine],
+) -> (Vec<StyledCharRange>, Vec<StyledCharRange>) {
+    let mut left_ranges = Vec::new();
+    let mut right_ranges = Vec::new();
 
-    for line in lines {
-        let state = line.state();
-        let left = format_line_for_side(state, line.left());
-        let right = format_line_for_side(state, line.right());
-        left_buffer.push(left);
-        right_buffer.push(right);
+    for (row, line) in lines.iter().enumerate() {
+        if line.state() != DiffState::Modified {
+            continue;
+        }
+        if let Some(content) = line.left() {
+            left_ranges.extend(char_ranges(row, content.text(), line.left_changes()));
+        }
+        if let Some(content) = line.right() {
+            right_ranges.extend(char_ranges(row, content.text(), line.right_changes()));
+        }
     }
 
-    (left_buffer.join("\r\n"), right_buffer.join("\r\n"))
+    (left_ranges, right_ranges)
+}
+
+fn char_ranges<'a>(
+    row: usize,
+    text: &'a str,
+    spans: &'a [ChangeSpan],
+) -> impl Iterator<Item = StyledCharRange> + 'a {
+    spans.iter().filter_map(move |span| {
+        let changed = text.get(span.start()..span.end())?;
+        let before = text.get(..span.start())?;
+        (!changed.is_empty()).then(|| {
+            StyledCharRange::new(
+                row,
+                ROW_PREFIX_CHARS + before.chars().count(),
+                changed.chars().count(),
+            )
+        })
+    })
 }
 
-fn format_line_for_side(state: DiffState, content: Option<&LineContent>) -> String {
-    let (prefix, text) = match (state, content) {
-        (DiffState::Added, None) => ("+", String::new()),
-        (DiffState::Deleted, None) => ("-", String::new()),
-        (DiffState::Moved, None) => ("↔", String::new()),
-        (_, None) => (" ", String::new()),
-        (DiffState::Added, Some(line)) => ("+", line.text().to_string()),
-        (DiffState::Deleted, Some(line)) => ("-", line.text().to_string()),
-        (DiffState::Moved, Some(line)) => ("↔", line.text().to_string()),
-        (DiffState::Unchanged, Some(line)) => (" ", line.text().to_string()),
+/// Text of the left and the right viewer, one row per diff line, each written straight
+/// from the line text into a buffer sized for the whole side per [CSV-Core-LineStoreV1].
+pub(crate) fn build_viewer_text(lines: &[DiffLine]) -> (String, String) {
+    let side_len = |side: fn(&DiffLine) -> Option<&LineContent>| -> usize {
+        let rows: usize = lines
+            .iter()
+            .map(|line| {
+                row_marker(line.state()).len() + 1 + side(line).map_or(0, |c| c.text().len())
+            })
+            .sum();
+        rows + "\r\n".len() * lines.len().saturating_sub(1)
     };
+    let mut left_text = String::with_capacity(side_len(DiffLine::left));
+    let mut right_text = String::with_capacity(side_len(DiffLine::right));
+
+    for (row, line) in lines.iter().enumerate() {
+        if row > 0 {
+            left_text.push_str("\r\n");
+            right_text.push_str("\r\n");
+        }
+        push_row(&mut left_text, line.state(), line.left());
+        push_row(&mut right_text, line.state(), line.right());
+    }
+
+    (left_text, right_text)
+}
 
-    format!("{prefix} {text}")
+/// Appends a viewer row: the state marker, a space and the line text, if the side has one.
+fn push_row(text: &mut String, state: DiffState, content: Option<&LineContent>) {
+    text.push_str(row_marker(state));
+    text.push(' ');
+    if let Some(line) = content {
+        text.push_str(line.text());
+    }
+}
+
+fn row_marker(state: DiffState) -> &'static str {
+    match state {
+        DiffState::Added => "+",
+        DiffState::Deleted => "-",
+        DiffState::Moved => "↔",
+        DiffState::Modified => "~",
+        DiffState::Unchanged => " ",
+    }
 }
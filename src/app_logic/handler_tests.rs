@@ -1,31 +1,52 @@
 #[cfg(test)]
 mod tests {
-    use crate::app_logic::handler::AppLogic;
+    use crate::app_logic::clock::Clock;
+    use crate::app_logic::diff_jobs::{Job, JobExecutor};
+    use crate::app_logic::handler::{
+        AppLogic, StyledCharRange, StyledLineRange, TIMESTAMP_DEBOUNCE_DELAY,
+        build_change_span_ranges, build_line_style_ranges, build_viewer_text, style_for_state,
+    };
     use crate::app_logic::ids::{
-        CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER, CONTROL_ID_TIMESTAMP_INPUT,
-        MENU_ACTION_EXIT, MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_RIGHT,
+        CONTROL_ID_LEFT_VIEWER, CONTROL_ID_MIN_MOVED_BLOCK_SIZE, CONTROL_ID_MOVE_GUTTER,
+        CONTROL_ID_NORMALIZATION_RULES, CONTROL_ID_PAIRING_PATTERN, CONTROL_ID_PATTERN_TESTER,
+        CONTROL_ID_PRESET_NAME, CONTROL_ID_RIGHT_TIMESTAMP_INPUT, CONTROL_ID_RIGHT_VIEWER,
+        CONTROL_ID_SLOWDOWN_RATIO, CONTROL_ID_TIMESTAMP_FORMAT, CONTROL_ID_TIMESTAMP_INPUT,
+        CONTROL_ID_TIMESTAMP_REPLACEMENT, CONTROL_ID_TIMING_COLUMN, LABEL_STATUS,
+        LABEL_TIMESTAMP_DIAGNOSTIC, MENU_ACTION_ADD_COMMON_RULES, MENU_ACTION_ALGORITHM_HECKEL,
+        MENU_ACTION_ALGORITHM_PATIENCE, MENU_ACTION_APPEND_LEFT, MENU_ACTION_APPLY_PRESET_FIRST,
+        MENU_ACTION_COMPARE_DIRECTORIES, MENU_ACTION_DIRECTORY_PAIR_FIRST, MENU_ACTION_EXIT,
+        MENU_ACTION_EXPORT_PRESETS, MENU_ACTION_IMPORT_PRESETS, MENU_ACTION_MOVED_BLOCKS_REPORT,
+        MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_RIGHT, MENU_ACTION_OPEN_RIGHT_ROTATION,
+        MENU_ACTION_REMOVE_PRESET_FIRST, MENU_ACTION_RIGHT_ENCODING_FIRST, MENU_ACTION_SAVE_PRESET,
+        MENU_ACTION_TIMING_REPORT, TIMER_ID_DEBOUNCE,
     };
+    use crate::core::diff_engine::HeckelDiffEngine;
     use crate::core::{
-        AppSettings, ComparableLine, DiffEngineOperations, DiffLine, DiffState, LineContent,
-        SettingsManagerOperations, TimestampParserOperations,
+        AppSettings, ChangeSpan, ComparableLine, ComparisonAlgorithm, DEFAULT_MIN_MOVED_BLOCK_SIZE,
+        DEFAULT_REGEX_CACHE_CAPACITY, DiffAlgorithm, DiffEngineOperations, DiffLine, DiffState,
+        LineContent, LineStore, LineText, LogSource, MovedBlock, NormalizationAction,
+        NormalizationRule, SettingsManagerOperations, TIMESTAMP_FORMAT_LIBRARY, TextEncoding,
+        TimestampParserOperations, TimestampPreset,
     };
-    use commanductui::types::{AppEvent, PlatformCommand, WindowId};
+    use commanductui::types::{AppEvent, MenuActionId, MessageSeverity, PlatformCommand, WindowId};
     use commanductui::{PlatformEventHandler, StyleId};
     use std::collections::VecDeque;
     use std::fs::File;
     use std::io::Write;
     use std::path::PathBuf;
     use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
     use tempfile::TempDir;
 
     #[derive(Default)]
     struct MockTimestampParser {
-        calls: Mutex<Vec<(Vec<String>, String)>>,
+        calls: Mutex<Vec<(Vec<String>, Vec<String>, String)>>,
         responses: Mutex<VecDeque<Vec<String>>>,
+        cache_capacity: Mutex<Option<usize>>,
     }
 
     impl MockTimestampParser {
-        fn calls(&self) -> Vec<(Vec<String>, String)> {
+        fn calls(&self) -> Vec<(Vec<String>, Vec<String>, String)> {
             self.calls.lock().unwrap().clone()
         }
 
@@ -33,6 +54,7 @@ mod tests {
             Self {
                 calls: Mutex::new(Vec::new()),
                 responses: Mutex::new(VecDeque::from(responses)),
+                cache_capacity: Mutex::new(None),
             }
         }
     }
@@ -40,27 +62,37 @@ mod tests {
     impl TimestampParserOperations for MockTimestampParser {
         fn strip_timestamps(
             &self,
-            lines: &[String],
-            pattern: &str,
-        ) -> Result<Vec<String>, crate::core::TimestampParserError> {
-            let captured_lines = lines.to_vec();
+            lines: &[LineText],
+            patterns: &[String],
+            replacement: &str,
+        ) -> Result<Vec<LineText>, crate::core::TimestampParserError> {
+            let captured_lines: Vec<String> = lines.iter().map(LineText::to_string).collect();
             {
                 let mut guard = self.calls.lock().unwrap();
-                guard.push((captured_lines.clone(), pattern.to_string()));
+                guard.push((
+                    captured_lines.clone(),
+                    patterns.to_vec(),
+                    replacement.to_string(),
+                ));
             }
 
             let mut responses = self.responses.lock().unwrap();
             if let Some(stripped) = responses.pop_front() {
-                Ok(stripped)
+                Ok(stripped.into_iter().map(LineText::from).collect())
             } else {
-                Ok(captured_lines)
+                Ok(lines.to_vec())
             }
         }
+
+        fn set_cache_capacity(&self, capacity: usize) {
+            *self.cache_capacity.lock().unwrap() = Some(capacity);
+        }
     }
 
     struct MockDiffEngine {
         calls: Mutex<Vec<(Vec<ComparableLine>, Vec<ComparableLine>)>>,
         lines_to_return: Vec<DiffLine>,
+        moved_blocks_to_return: Vec<MovedBlock>,
     }
 
     impl MockDiffEngine {
@@ -68,23 +100,102 @@ mod tests {
             Self {
                 calls: Mutex::new(Vec::new()),
                 lines_to_return,
+                moved_blocks_to_return: Vec::new(),
             }
         }
 
+        fn with_moved_blocks(mut self, moved_blocks: Vec<MovedBlock>) -> Self {
+            self.moved_blocks_to_return = moved_blocks;
+            self
+        }
+
         fn calls(&self) -> Vec<(Vec<ComparableLine>, Vec<ComparableLine>)> {
             self.calls.lock().unwrap().clone()
         }
     }
 
     impl DiffEngineOperations for MockDiffEngine {
-        fn compute_diff(
+        fn compute_diff_with_control(
             &self,
             lines_a: &[ComparableLine],
             lines_b: &[ComparableLine],
-        ) -> crate::core::DiffResult {
+            _control: &crate::core::DiffControl<'_>,
+        ) -> Result<crate::core::DiffResult, crate::core::DiffCancelled> {
             let mut guard = self.calls.lock().unwrap();
             guard.push((lines_a.to_vec(), lines_b.to_vec()));
-            crate::core::DiffResult::new(self.lines_to_return.clone())
+            Ok(crate::core::DiffResult::with_moved_blocks(
+                self.lines_to_return.clone(),
+                self.moved_blocks_to_return.clone(),
+            ))
+        }
+    }
+
+    /// Runs jobs on the test thread: immediately on spawn, or only when asked to.
+    #[derive(Default)]
+    struct DeterministicJobExecutor {
+        run_on_spawn: bool,
+        queued: Mutex<VecDeque<Job>>,
+    }
+
+    impl DeterministicJobExecutor {
+        fn immediate() -> Arc<Self> {
+            Arc::new(Self {
+                run_on_spawn: true,
+                queued: Mutex::new(VecDeque::new()),
+            })
+        }
+
+        fn manual() -> Arc<Self> {
+            Arc::new(Self::default())
+        }
+
+        fn queued_jobs(&self) -> usize {
+            self.queued.lock().unwrap().len()
+        }
+
+        fn run_next(&self) {
+            let job = self
+                .queued
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("expected a queued job");
+            job();
+        }
+    }
+
+    impl JobExecutor for DeterministicJobExecutor {
+        fn spawn(&self, job: Job) {
+            if self.run_on_spawn {
+                job();
+            } else {
+                self.queued.lock().unwrap().push_back(job);
+            }
+        }
+    }
+
+    /// Clock that only moves when a test advances it.
+    struct ManualClock {
+        start: Instant,
+        elapsed: Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                start: Instant::now(),
+                elapsed: Mutex::new(Duration::ZERO),
+            })
+        }
+
+        fn advance(&self, duration: Duration) {
+            *self.elapsed.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.start + *self.elapsed.lock().unwrap()
         }
     }
 
@@ -129,8 +240,23 @@ mod tests {
         while app_logic.try_dequeue_command().is_some() {}
     }
 
+    /// Next command other than a [CSV-UX-PatternTesterV1] preview update.
+    fn dequeue_skipping_pattern_test(app_logic: &mut AppLogic) -> Option<PlatformCommand> {
+        while let Some(command) = app_logic.try_dequeue_command() {
+            if !matches!(
+                command,
+                PlatformCommand::SetInputText { control_id, .. }
+                    if control_id == CONTROL_ID_PATTERN_TESTER
+            ) {
+                return Some(command);
+            }
+        }
+        None
+    }
+
     fn load_files_and_pattern(
         app_logic: &mut AppLogic,
+        clock: &ManualClock,
         window_id: WindowId,
         left_path: &PathBuf,
         right_path: &PathBuf,
@@ -161,9 +287,46 @@ mod tests {
             control_id: CONTROL_ID_TIMESTAMP_INPUT,
             text: pattern.to_string(),
         });
+        // [CSV-UX-TimestampFeedbackV3] Let the debounce elapse so the pattern is committed.
+        elapse_debounce(app_logic, clock, window_id);
         drain_commands(app_logic);
     }
 
+    /// Lets the debounce delay pass and delivers the platform timer armed for it.
+    fn elapse_debounce(app_logic: &mut AppLogic, clock: &ManualClock, window_id: WindowId) {
+        clock.advance(TIMESTAMP_DEBOUNCE_DELAY);
+        app_logic.handle_event(AppEvent::TimerElapsed {
+            window_id,
+            timer_id: TIMER_ID_DEBOUNCE,
+        });
+    }
+
+    /// Asserts that arming the debounce timer for the full delay is all that is left to do.
+    fn assert_only_debounce_timer_armed(app_logic: &mut AppLogic) {
+        match dequeue_skipping_pattern_test(app_logic) {
+            Some(PlatformCommand::StartTimer {
+                timer_id, delay, ..
+            }) => {
+                assert_eq!(timer_id, TIMER_ID_DEBOUNCE);
+                assert_eq!(delay, TIMESTAMP_DEBOUNCE_DELAY);
+            }
+            other => panic!("expected the debounce timer, got {other:?}"),
+        }
+        assert!(dequeue_skipping_pattern_test(app_logic).is_none());
+    }
+
+    /// Runs every queued job, delivering the wake-up each one sends when it finishes.
+    fn run_queued_jobs(
+        app_logic: &mut AppLogic,
+        executor: &DeterministicJobExecutor,
+        window_id: WindowId,
+    ) {
+        while executor.queued_jobs() > 0 {
+            executor.run_next();
+            app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        }
+    }
+
     #[test]
     fn diff_workflow_enqueues_viewer_updates() {
         // Arrange
@@ -186,12 +349,13 @@ mod tests {
         let settings_manager: Arc<dyn SettingsManagerOperations> =
             Arc::new(MockSettingsManager::default());
         let mut app_logic =
-            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app");
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_job_executor(DeterministicJobExecutor::immediate());
 
         let window_id = WindowId::new(7);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
-        // [CSV-Tech-SettingsPersistenceV1] Drain the initial input sync command produced by loading settings.
-        app_logic.try_dequeue_command();
+        // [CSV-Tech-SettingsPersistenceV1] Drain the initial input sync commands produced by loading settings.
+        drain_commands(&mut app_logic);
 
         let (_temp_dir, left_path, right_path) = create_test_files();
 
@@ -218,7 +382,7 @@ mod tests {
             result: Some(left_path.clone()),
         });
         assert!(
-            app_logic.try_dequeue_command().is_none(),
+            dequeue_skipping_pattern_test(&mut app_logic).is_none(),
             "no diff should run until both files selected"
         );
 
@@ -245,9 +409,8 @@ mod tests {
         });
 
         // Assert: diff results enqueued
-        let left_update = app_logic
-            .try_dequeue_command()
-            .expect("expected left viewer update");
+        let left_update =
+            dequeue_skipping_pattern_test(&mut app_logic).expect("expected left viewer update");
         match left_update {
             PlatformCommand::SetViewerContent {
                 control_id,
@@ -277,6 +440,50 @@ mod tests {
             other => panic!("unexpected command: {other:?}"),
         }
 
+        // [CSV-UI-MovedBlocksV1] The gutter gets one (empty) row per viewer row.
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::SetViewerContent {
+                control_id, text, ..
+            }) => {
+                assert_eq!(control_id, CONTROL_ID_MOVE_GUTTER);
+                assert_eq!(text, "\r\n");
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        // [CSV-UI-HighlightV1] Each viewer gets one styled range per run of equal states;
+        // unchanged rows keep the default background.
+        for expected_control in [CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER] {
+            for (expected_first, expected_style) in [(1, StyleId::DiffAdded)] {
+                match app_logic.try_dequeue_command() {
+                    Some(PlatformCommand::ApplyStyleToLines {
+                        window_id: cmd_window,
+                        control_id,
+                        first_line,
+                        line_count,
+                        style_id,
+                    }) => {
+                        assert_eq!(cmd_window, window_id);
+                        assert_eq!(control_id, expected_control);
+                        assert_eq!(first_line, expected_first);
+                        assert_eq!(line_count, 1);
+                        assert_eq!(style_id, expected_style);
+                    }
+                    other => panic!("unexpected command: {other:?}"),
+                }
+            }
+        }
+
+        // [CSV-UI-TimingV1] Without a time format the timing column stays empty.
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::SetViewerContent {
+                control_id: CONTROL_ID_TIMING_COLUMN,
+                text,
+                ..
+            }) if text == "\r\n"
+        ));
+
         assert!(
             app_logic.try_dequeue_command().is_none(),
             "no extra commands expected"
@@ -293,7 +500,11 @@ mod tests {
             parser_calls[1].0,
             vec![String::from("right-alpha"), String::from("right-beta")]
         );
-        assert!(parser_calls.iter().all(|(_, pattern)| pattern.is_empty()));
+        assert!(
+            parser_calls
+                .iter()
+                .all(|(_, patterns, _)| patterns.is_empty())
+        );
 
         let diff_calls = mock_diff_engine.calls();
         assert_eq!(diff_calls.len(), 1);
@@ -321,13 +532,16 @@ mod tests {
         let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
         let settings_manager: Arc<dyn SettingsManagerOperations> =
             Arc::new(MockSettingsManager::default());
+        let clock = ManualClock::new();
         let mut app_logic =
-            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app");
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
 
         let window_id = WindowId::new(42);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
-        // [CSV-Tech-SettingsPersistenceV1] Initial settings load syncs the input field.
-        app_logic.try_dequeue_command();
+        // [CSV-Tech-SettingsPersistenceV1] Initial settings load syncs the input fields.
+        drain_commands(&mut app_logic);
 
         // Invalid pattern should mark control with error style and skip diffing
         app_logic.handle_event(AppEvent::InputTextChanged {
@@ -351,6 +565,33 @@ mod tests {
             }
             other => panic!("unexpected command: {other:?}"),
         }
+        // [CSV-UX-RegexDiagnosticsV1] The label next to the input says where and why.
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::UpdateLabelText {
+                control_id: LABEL_TIMESTAMP_DIAGNOSTIC,
+                ref text,
+                severity: MessageSeverity::Error,
+                ..
+            }) if text == "#1, column 1: unclosed character class"
+        ));
+        // [CSV-UX-TimestampFeedbackV3] The status bar names the invalid entry.
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::UpdateLabelText {
+                control_id,
+                text,
+                severity,
+                ..
+            }) => {
+                assert_eq!(control_id, LABEL_STATUS);
+                assert!(
+                    text.starts_with("Timestamp pattern #1 is invalid:"),
+                    "unexpected status: {text}"
+                );
+                assert_eq!(severity, MessageSeverity::Error);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
         assert!(
             app_logic.try_dequeue_command().is_none(),
             "no diff commands expected for invalid pattern"
@@ -368,7 +609,7 @@ mod tests {
             window_id,
             result: Some(left_path.clone()),
         });
-        assert!(app_logic.try_dequeue_command().is_none());
+        assert!(dequeue_skipping_pattern_test(&mut app_logic).is_none());
 
         app_logic.handle_event(AppEvent::MenuActionClicked {
             action_id: MENU_ACTION_OPEN_RIGHT,
@@ -379,7 +620,7 @@ mod tests {
             result: Some(right_path.clone()),
         });
         assert!(
-            app_logic.try_dequeue_command().is_none(),
+            dequeue_skipping_pattern_test(&mut app_logic).is_none(),
             "still no diff commands while pattern invalid"
         );
 
@@ -389,6 +630,7 @@ mod tests {
             control_id: CONTROL_ID_TIMESTAMP_INPUT,
             text: ".*".to_string(),
         });
+        elapse_debounce(&mut app_logic, &clock, window_id);
 
         let restore_style = app_logic
             .try_dequeue_command()
@@ -405,6 +647,29 @@ mod tests {
             }
             other => panic!("unexpected command: {other:?}"),
         }
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::UpdateLabelText {
+                control_id: LABEL_TIMESTAMP_DIAGNOSTIC,
+                ref text,
+                ..
+            }) if text.is_empty()
+        ));
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::UpdateLabelText {
+                control_id: LABEL_STATUS,
+                ref text,
+                ..
+            }) if text.is_empty()
+        ));
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::StartTimer {
+                timer_id: TIMER_ID_DEBOUNCE,
+                ..
+            })
+        ));
 
         let diff_calls = mock_diff_engine.calls();
         assert_eq!(
@@ -414,8 +679,7 @@ mod tests {
         );
 
         // Diff commands follow
-        let left_update = app_logic
-            .try_dequeue_command()
+        let left_update = dequeue_skipping_pattern_test(&mut app_logic)
             .expect("expected left viewer update after valid regex");
         let right_update = app_logic
             .try_dequeue_command()
@@ -471,14 +735,24 @@ mod tests {
         let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
         let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
         let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
-        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app");
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
 
         let window_id = WindowId::new(77);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
         drain_commands(&mut app_logic);
 
         let (_temp_dir, left_path, right_path) = create_test_files();
-        load_files_and_pattern(&mut app_logic, window_id, &left_path, &right_path, ".*");
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            ".*",
+        );
 
         // [CSV-UI-ExitCommandV1] File/Exit should initiate shutdown.
         app_logic.handle_event(AppEvent::MenuActionClicked {
@@ -499,8 +773,14 @@ mod tests {
         assert_eq!(saved.len(), 1, "exit should persist settings immediately");
         let (app_name, snapshot) = &saved[0];
         assert_eq!(app_name, "test-app");
-        assert_eq!(snapshot.left_file_path(), Some(&left_path));
-        assert_eq!(snapshot.right_file_path(), Some(&right_path));
+        assert_eq!(
+            snapshot.left_file_path(),
+            Some(&LogSource::File(left_path.clone()))
+        );
+        assert_eq!(
+            snapshot.right_file_path(),
+            Some(&LogSource::File(right_path.clone()))
+        );
         assert_eq!(snapshot.timestamp_pattern(), ".*");
         let history: Vec<&str> = snapshot
             .timestamp_history()
@@ -525,7 +805,8 @@ mod tests {
         let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
         let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
 
-        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app");
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
         let window_id = WindowId::new(1);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
         drain_commands(&mut app_logic);
@@ -556,9 +837,20 @@ mod tests {
             other => panic!("Unexpected command generated: {other:?}"),
         }
 
+        // [CSV-UI-MovedBlocksV1][CSV-UI-TimingV1] The move gutter and timing column follow along.
+        for expected_control in [CONTROL_ID_MOVE_GUTTER, CONTROL_ID_TIMING_COLUMN] {
+            assert!(matches!(
+                app_logic.try_dequeue_command(),
+                Some(PlatformCommand::SetScrollPosition {
+                    control_id,
+                    vertical_pos: 50,
+                    ..
+                }) if control_id == expected_control
+            ));
+        }
         assert!(
             app_logic.try_dequeue_command().is_none(),
-            "only one command should be generated"
+            "no further commands should be generated"
         );
     }
 
@@ -576,14 +868,43 @@ mod tests {
         let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
         let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
         let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
-        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app");
+        let executor = DeterministicJobExecutor::manual();
+        let job_executor: Arc<dyn JobExecutor> = executor.clone();
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(job_executor);
 
         let window_id = WindowId::new(88);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
         drain_commands(&mut app_logic);
 
         let (_temp_dir, left_path, right_path) = create_test_files();
-        load_files_and_pattern(&mut app_logic, window_id, &left_path, &right_path, "\\d+");
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "\\d+",
+        );
+        run_queued_jobs(&mut app_logic, &executor, window_id);
+        drain_commands(&mut app_logic);
+        let diffs_before_close = mock_diff_engine.calls().len();
+
+        // A directory comparison still queued when the window goes away must not run.
+        let run_dir = left_path.parent().expect("run directory").to_path_buf();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_COMPARE_DIRECTORIES,
+        });
+        for _ in 0..2 {
+            app_logic.handle_event(AppEvent::FolderPickerDialogCompleted {
+                window_id,
+                result: Some(run_dir.clone()),
+            });
+        }
+        drain_commands(&mut app_logic);
+        assert_eq!(executor.queued_jobs(), 1, "the comparison is queued");
 
         // [CSV-UI-ExitCommandV1] Closing via the window chrome should mirror File/Exit.
         app_logic.handle_event(AppEvent::WindowCloseRequestedByUser { window_id });
@@ -598,11 +919,25 @@ mod tests {
             other => panic!("unexpected command: {other:?}"),
         }
 
+        app_logic.handle_event(AppEvent::WindowDestroyed { window_id });
+        executor.run_next();
+        assert_eq!(
+            mock_diff_engine.calls().len(),
+            diffs_before_close,
+            "closing the window cancels the directory comparison"
+        );
+
         let saved = settings_manager.saved_snapshots();
         assert_eq!(saved.len(), 1, "close request should persist settings");
         let snapshot = &saved[0].1;
-        assert_eq!(snapshot.left_file_path(), Some(&left_path));
-        assert_eq!(snapshot.right_file_path(), Some(&right_path));
+        assert_eq!(
+            snapshot.left_file_path(),
+            Some(&LogSource::File(left_path.clone()))
+        );
+        assert_eq!(
+            snapshot.right_file_path(),
+            Some(&LogSource::File(right_path.clone()))
+        );
         assert_eq!(snapshot.timestamp_pattern(), "\\d+");
     }
 
@@ -620,7 +955,10 @@ mod tests {
         let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
         let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
         let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
-        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app");
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
 
         let window_id = WindowId::new(101);
         app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
@@ -655,7 +993,8 @@ mod tests {
                 control_id: CONTROL_ID_TIMESTAMP_INPUT,
                 text: pattern.to_string(),
             });
-            // [CSV-UX-TimestampHistoryV1] Valid pattern inputs update the MRU collection.
+            // [CSV-UX-TimestampHistoryV1] Committed pattern inputs update the MRU collection.
+            elapse_debounce(&mut app_logic, &clock, window_id);
             drain_commands(&mut app_logic);
         }
 
@@ -665,8 +1004,14 @@ mod tests {
         assert_eq!(saved.len(), 1, "expected a single persistence attempt");
         let (app_name, snapshot) = &saved[0];
         assert_eq!(app_name, "test-app");
-        assert_eq!(snapshot.left_file_path(), Some(&left_path));
-        assert_eq!(snapshot.right_file_path(), Some(&right_path));
+        assert_eq!(
+            snapshot.left_file_path(),
+            Some(&LogSource::File(left_path.clone()))
+        );
+        assert_eq!(
+            snapshot.right_file_path(),
+            Some(&LogSource::File(right_path.clone()))
+        );
         assert_eq!(snapshot.timestamp_pattern(), "six");
 
         let history: Vec<&str> = snapshot
@@ -680,4 +1025,2264 @@ mod tests {
             "history stores a five-entry MRU per [CSV-UX-TimestampHistoryV1]"
         );
     }
+
+    #[test]
+    fn diff_algorithm_menu_switches_engine_and_persists_choice() {
+        // [CSV-Diff-AlgorithmChoiceV1]
+        let default_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let patience_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let settings_manager = Arc::new(MockSettingsManager::default());
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = default_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+        let patience_arc: Arc<dyn DiffEngineOperations> = patience_engine.clone();
+        app_logic.register_diff_engine(DiffAlgorithm::Patience, patience_arc);
+
+        let window_id = WindowId::new(5);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let default_calls = default_engine.calls().len();
+        assert!(default_calls > 0, "default engine handles the initial diff");
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+
+        assert_eq!(
+            patience_engine.calls().len(),
+            1,
+            "selection re-runs the diff"
+        );
+        assert_eq!(default_engine.calls().len(), default_calls);
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::SetViewerContent {
+                control_id: CONTROL_ID_LEFT_VIEWER,
+                ..
+            })
+        ));
+        drain_commands(&mut app_logic);
+
+        // Selecting the active algorithm again must not recompute.
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+        assert_eq!(patience_engine.calls().len(), 1);
+        assert!(app_logic.try_dequeue_command().is_none());
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.diff_algorithm(), DiffAlgorithm::Patience);
+    }
+
+    #[test]
+    fn diff_algorithm_is_remembered_per_pair_of_files() {
+        // [CSV-Diff-AlgorithmChoiceV1]
+        let heckel_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let patience_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let settings_manager = Arc::new(MockSettingsManager::default());
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = heckel_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+        let patience_arc: Arc<dyn DiffEngineOperations> = patience_engine.clone();
+        app_logic.register_diff_engine(DiffAlgorithm::Patience, patience_arc);
+
+        let window_id = WindowId::new(6);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (temp_dir, left_path, right_path) = create_test_files();
+        let other_path = temp_dir.path().join("other.log");
+        std::fs::write(&other_path, "other\n").unwrap();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+        drain_commands(&mut app_logic);
+        assert_eq!(patience_engine.calls().len(), 1);
+
+        let open_right = |app_logic: &mut AppLogic, path: &PathBuf| {
+            app_logic.handle_event(AppEvent::MenuActionClicked {
+                action_id: MENU_ACTION_OPEN_RIGHT,
+            });
+            let _ = app_logic.try_dequeue_command();
+            app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+                window_id,
+                result: Some(path.clone()),
+            });
+            drain_commands(app_logic);
+        };
+
+        // A pair compared for the first time keeps the current algorithm.
+        open_right(&mut app_logic, &other_path);
+        assert_eq!(patience_engine.calls().len(), 2);
+        let heckel_calls = heckel_engine.calls().len();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_HECKEL,
+        });
+        drain_commands(&mut app_logic);
+        assert_eq!(heckel_engine.calls().len(), heckel_calls + 1);
+
+        // The first pair is diffed with the algorithm chosen for it.
+        open_right(&mut app_logic, &right_path);
+        assert_eq!(patience_engine.calls().len(), 3);
+        assert_eq!(heckel_engine.calls().len(), heckel_calls + 1);
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        let (_, snapshot) = &saved[0];
+        assert_eq!(snapshot.diff_algorithm(), DiffAlgorithm::Patience);
+        let left = LogSource::File(left_path.clone());
+        assert_eq!(
+            snapshot.comparison_algorithms(),
+            [
+                ComparisonAlgorithm::new(
+                    left.clone(),
+                    LogSource::File(other_path),
+                    DiffAlgorithm::Heckel
+                ),
+                ComparisonAlgorithm::new(
+                    left,
+                    LogSource::File(right_path),
+                    DiffAlgorithm::Patience
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn min_moved_block_size_is_restored_edited_passed_to_the_engine_and_persisted() {
+        // [CSV-UI-MovedBlocksV1][CSV-Tech-SettingsPersistenceV1]
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        *settings_manager.load_response.lock().unwrap() =
+            AppSettings::new().with_min_moved_block_size(3);
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(HeckelDiffEngine::new());
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(33);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        let mut restored_size = None;
+        while let Some(command) = app_logic.try_dequeue_command() {
+            if let PlatformCommand::SetInputText {
+                control_id, text, ..
+            } = command
+                && control_id == CONTROL_ID_MIN_MOVED_BLOCK_SIZE
+            {
+                restored_size = Some(text);
+            }
+        }
+        assert_eq!(restored_size.as_deref(), Some("3"));
+
+        // "x" moves on its own, which is below the restored minimum.
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("left.log");
+        let right_path = temp_dir.path().join("right.log");
+        std::fs::write(&left_path, "x\nk1\nk2\nk3\n").unwrap();
+        std::fs::write(&right_path, "k1\nk2\nk3\nx\n").unwrap();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        assert!(app_logic.moved_block_connectors().is_empty());
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+            text: "0".to_string(),
+        });
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+                style_id: StyleId::DefaultInputError,
+                ..
+            })
+        ));
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        assert!(app_logic.moved_block_connectors().is_empty());
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+            text: "1".to_string(),
+        });
+        assert!(
+            app_logic.moved_block_connectors().is_empty(),
+            "the new minimum waits for the debounce"
+        );
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        assert_eq!(app_logic.moved_block_connectors().len(), 1);
+        drain_commands(&mut app_logic);
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.min_moved_block_size(), 1);
+        assert_eq!(
+            AppSettings::new().min_moved_block_size(),
+            DEFAULT_MIN_MOVED_BLOCK_SIZE
+        );
+    }
+
+    #[test]
+    fn regex_cache_capacity_is_applied_from_settings_and_persisted() {
+        // [CSV-Core-RegexCacheV2][CSV-Tech-SettingsPersistenceV1]
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        *settings_manager.load_response.lock().unwrap() =
+            AppSettings::new().with_regex_cache_capacity(8);
+
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(34);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+        assert_eq!(
+            *mock_timestamp_parser.cache_capacity.lock().unwrap(),
+            Some(8)
+        );
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.regex_cache_capacity(), 8);
+        assert_eq!(
+            AppSettings::new().regex_cache_capacity(),
+            DEFAULT_REGEX_CACHE_CAPACITY
+        );
+    }
+
+    #[test]
+    fn diff_runs_on_job_executor_and_delivers_commands_when_finished() {
+        // [CSV-UX-ResponsiveV1][CSV-Core-LargeFileV1]
+        let diff_lines = vec![DiffLine::new(
+            DiffState::Unchanged,
+            Some(LineContent::new(1, "alpha")),
+            Some(LineContent::new(1, "alpha")),
+        )];
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(diff_lines));
+        let executor = DeterministicJobExecutor::manual();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let job_executor: Arc<dyn JobExecutor> = executor.clone();
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(job_executor);
+
+        let window_id = WindowId::new(11);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+
+        assert_eq!(
+            executor.queued_jobs(),
+            3,
+            "sampling each opened file and the committed pattern queue a job each"
+        );
+        assert!(mock_diff_engine.calls().is_empty(), "nothing runs inline");
+        assert!(app_logic.try_dequeue_command().is_none());
+
+        // The sampling superseded by opening the right file is dropped; the other one
+        // starts a diff that supersedes the one queued for the pattern.
+        executor.run_next();
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert!(dequeue_skipping_pattern_test(&mut app_logic).is_none());
+        executor.run_next();
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert!(dequeue_skipping_pattern_test(&mut app_logic).is_none());
+        assert_eq!(executor.queued_jobs(), 2);
+
+        executor.run_next();
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert!(
+            app_logic.try_dequeue_command().is_none(),
+            "the superseded job must not update the viewers"
+        );
+        assert!(
+            mock_diff_engine.calls().is_empty(),
+            "the superseded job stops before diffing"
+        );
+
+        executor.run_next();
+        assert!(
+            app_logic.try_dequeue_command().is_none(),
+            "results wait for the wake-up"
+        );
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert_eq!(mock_diff_engine.calls().len(), 1);
+        for expected_control in [CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER] {
+            match app_logic.try_dequeue_command() {
+                Some(PlatformCommand::SetViewerContent {
+                    window_id: cmd_window,
+                    control_id,
+                    ..
+                }) => {
+                    assert_eq!(cmd_window, window_id);
+                    assert_eq!(control_id, expected_control);
+                }
+                other => panic!("unexpected command: {other:?}"),
+            }
+        }
+        let mut remaining = Vec::new();
+        while let Some(command) = app_logic.try_dequeue_command() {
+            remaining.push(command);
+        }
+        assert!(
+            remaining.iter().all(|command| matches!(
+                command,
+                PlatformCommand::ApplyStyleToLines { .. }
+                    | PlatformCommand::SetViewerContent {
+                        control_id: CONTROL_ID_MOVE_GUTTER | CONTROL_ID_TIMING_COLUMN,
+                        ..
+                    }
+            )),
+            "only gutter, timing and highlight commands follow the viewer updates: {remaining:?}"
+        );
+    }
+
+    #[test]
+    fn wake_event_applies_a_finished_diff_without_dequeueing() {
+        // [CSV-UX-ResponsiveV1] The worker's wake-up, not the next drain of the command
+        // queue, brings the result in.
+        let diff_lines = vec![DiffLine::new(
+            DiffState::Unchanged,
+            Some(LineContent::new(1, "a")),
+            Some(LineContent::new(1, "a")),
+        )];
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(diff_lines));
+        let executor = DeterministicJobExecutor::manual();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let job_executor: Arc<dyn JobExecutor> = executor.clone();
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(job_executor);
+
+        let window_id = WindowId::new(12);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        while executor.queued_jobs() > 0 {
+            executor.run_next();
+        }
+        assert!(app_logic.try_dequeue_command().is_none());
+
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::SetViewerContent { control_id, .. }) => {
+                assert_eq!(control_id, CONTROL_ID_LEFT_VIEWER);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stale_diff_result_is_discarded_when_it_finishes_late() {
+        // [CSV-UX-ResponsiveV1]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let executor = DeterministicJobExecutor::manual();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let job_executor: Arc<dyn JobExecutor> = executor.clone();
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(job_executor);
+
+        let window_id = WindowId::new(12);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "x",
+        );
+        run_queued_jobs(&mut app_logic, &executor, window_id);
+        drain_commands(&mut app_logic);
+
+        // A new request arrives; the job it supersedes completes only afterwards.
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: "y".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+        assert_eq!(executor.queued_jobs(), 2);
+
+        executor.run_next();
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert!(app_logic.try_dequeue_command().is_none());
+
+        executor.run_next();
+        app_logic.handle_event(AppEvent::WakeRequested { window_id });
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::SetViewerContent { .. })
+        ));
+    }
+
+    #[test]
+    fn rapid_pattern_edits_are_debounced_into_a_single_diff() {
+        // [CSV-UX-TimestampFeedbackV3][CSV-UX-TimestampHistoryV1]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(13);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let initial_diffs = mock_diff_engine.calls().len();
+        let initial_parses = mock_timestamp_parser.calls().len();
+
+        for pattern in ["\\d", "\\d+", "\\d+:"] {
+            app_logic.handle_event(AppEvent::InputTextChanged {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_INPUT,
+                text: pattern.to_string(),
+            });
+            // [CSV-UX-TimestampFeedbackV3] Every edit re-arms the timer for the full delay.
+            assert_only_debounce_timer_armed(&mut app_logic);
+            clock.advance(TIMESTAMP_DEBOUNCE_DELAY / 2);
+        }
+        assert_eq!(
+            mock_diff_engine.calls().len(),
+            initial_diffs,
+            "no diff while the user is still typing"
+        );
+
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        assert_eq!(
+            mock_diff_engine.calls().len(),
+            initial_diffs + 1,
+            "the timer event alone commits the edit"
+        );
+        drain_commands(&mut app_logic);
+
+        assert_eq!(mock_diff_engine.calls().len(), initial_diffs + 1);
+        let parser_calls = mock_timestamp_parser.calls();
+        assert!(
+            parser_calls[initial_parses..]
+                .iter()
+                .all(|(_, patterns, _)| patterns == &["\\d+:"]),
+            "only the last pattern is used for the diff"
+        );
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        let history: Vec<&str> = saved[0]
+            .1
+            .timestamp_history()
+            .iter()
+            .map(|entry| entry.as_str())
+            .collect();
+        assert_eq!(history, vec!["\\d+:"]);
+    }
+
+    #[test]
+    fn invalid_edit_cancels_pending_debounced_diff() {
+        // [CSV-UX-TimestampFeedbackV3]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(14);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let initial_diffs = mock_diff_engine.calls().len();
+
+        for pattern in ["a", "a["] {
+            app_logic.handle_event(AppEvent::InputTextChanged {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_INPUT,
+                text: pattern.to_string(),
+            });
+        }
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+
+        assert_eq!(mock_diff_engine.calls().len(), initial_diffs);
+    }
+
+    #[test]
+    fn line_style_ranges_merge_consecutive_rows_with_equal_state() {
+        // [CSV-UI-HighlightV1] Unchanged rows get no range and split the runs around them.
+        let row = |state: DiffState| {
+            DiffLine::new(
+                state,
+                Some(LineContent::new(1, "left")),
+                Some(LineContent::new(1, "right")),
+            )
+        };
+        let lines = vec![
+            row(DiffState::Unchanged),
+            row(DiffState::Unchanged),
+            DiffLine::new(DiffState::Deleted, Some(LineContent::new(3, "gone")), None),
+            DiffLine::new(DiffState::Added, None, Some(LineContent::new(3, "new"))),
+            DiffLine::new(DiffState::Added, None, Some(LineContent::new(4, "newer"))),
+            row(DiffState::Moved),
+            row(DiffState::Modified),
+            DiffLine::new(DiffState::Added, None, Some(LineContent::new(6, "more"))),
+            row(DiffState::Unchanged),
+            DiffLine::new(DiffState::Added, None, Some(LineContent::new(8, "again"))),
+        ];
+
+        let ranges = build_line_style_ranges(&lines);
+
+        assert_eq!(
+            ranges,
+            vec![
+                StyledLineRange::new(2, 1, StyleId::DiffDeleted),
+                StyledLineRange::new(3, 2, StyleId::DiffAdded),
+                StyledLineRange::new(5, 1, StyleId::DiffMoved),
+                StyledLineRange::new(6, 1, StyleId::DiffModified),
+                StyledLineRange::new(7, 1, StyleId::DiffAdded),
+                StyledLineRange::new(9, 1, StyleId::DiffAdded),
+            ]
+        );
+        assert!(build_line_style_ranges(&[]).is_empty());
+    }
+
+    #[test]
+    fn viewer_text_is_written_from_mapped_lines_into_one_buffer_per_side() {
+        // [CSV-Core-LineStoreV1] The diff lines keep referring into the mapped file and
+        // each side is copied once, into a buffer sized for it up front.
+        let temp_dir = TempDir::new().expect("temp dir");
+        let path = temp_dir.path().join("app.log");
+        std::fs::write(&path, "alpha\nGrüße\n").unwrap();
+        let file = File::open(&path).unwrap();
+        let len = file.metadata().unwrap().len();
+        let store = Arc::new(LineStore::map(&file, len).unwrap().expect("mapped"));
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Moved,
+                Some(LineContent::new(1, LineText::stored(&store, 0))),
+                None,
+            ),
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(2, LineText::stored(&store, 1))),
+                Some(LineContent::new(1, "Grüße")),
+            ),
+        ];
+
+        let (left_text, right_text) = build_viewer_text(&lines);
+
+        assert_eq!(left_text, "↔ alpha\r\n  Grüße");
+        assert_eq!(right_text, "↔ \r\n  Grüße");
+        assert_eq!(left_text.capacity(), left_text.len());
+        assert_eq!(right_text.capacity(), right_text.len());
+        for (line, index) in lines.iter().zip(0..) {
+            let text = line.left().unwrap().text();
+            assert!(
+                std::ptr::eq(text, store.line(index)),
+                "line {index} was copied"
+            );
+        }
+    }
+
+    #[test]
+    fn change_spans_of_modified_rows_become_character_ranges() {
+        // [CSV-Diff-IntraLineV1] Spans count bytes, viewer rows count characters after the
+        // two-character state prefix.
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(1, "same")),
+                Some(LineContent::new(1, "same")),
+            ),
+            DiffLine::new(
+                DiffState::Modified,
+                Some(LineContent::new(2, "Grüße id=41 ok")),
+                Some(LineContent::new(2, "Grüße id=42 ok!")),
+            )
+            .with_changes(
+                vec![ChangeSpan::new(11, 13)],
+                vec![ChangeSpan::new(11, 13), ChangeSpan::new(16, 17)],
+            ),
+        ];
+
+        let (left, right) = build_change_span_ranges(&lines);
+
+        assert_eq!(left, vec![StyledCharRange::new(1, 11, 2)]);
+        assert_eq!(
+            right,
+            vec![
+                StyledCharRange::new(1, 11, 2),
+                StyledCharRange::new(1, 16, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_workflow_styles_changed_words_of_modified_rows() {
+        // [CSV-Diff-IntraLineV1]
+        let diff_lines = vec![
+            DiffLine::new(
+                DiffState::Modified,
+                Some(LineContent::new(1, "id=41")),
+                Some(LineContent::new(1, "id=42")),
+            )
+            .with_changes(vec![ChangeSpan::new(3, 5)], vec![ChangeSpan::new(3, 5)]),
+        ];
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(diff_lines));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(14);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_LEFT,
+        });
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(left_path),
+        });
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_RIGHT,
+        });
+        let _ = app_logic.try_dequeue_command();
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(right_path),
+        });
+        let mut commands = Vec::new();
+        while let Some(command) = app_logic.try_dequeue_command() {
+            commands.push(command);
+        }
+
+        let char_styles: Vec<_> = commands
+            .into_iter()
+            .filter_map(|command| match command {
+                PlatformCommand::ApplyStyleToCharRange {
+                    window_id: cmd_window,
+                    control_id,
+                    line,
+                    first_char,
+                    char_count,
+                    style_id,
+                } => {
+                    assert_eq!(cmd_window, window_id);
+                    assert_eq!(style_id, StyleId::DiffChangedText);
+                    Some((control_id, line, first_char, char_count))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            char_styles,
+            vec![
+                (CONTROL_ID_LEFT_VIEWER, 0, 5, 2),
+                (CONTROL_ID_RIGHT_VIEWER, 0, 5, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn each_diff_state_has_a_distinct_style() {
+        let states = [
+            DiffState::Unchanged,
+            DiffState::Added,
+            DiffState::Deleted,
+            DiffState::Moved,
+            DiffState::Modified,
+        ];
+        let styles: Vec<Option<StyleId>> = states.into_iter().map(style_for_state).collect();
+
+        assert_eq!(
+            styles[0], None,
+            "unchanged rows keep the default background"
+        );
+        for (i, style) in styles.iter().enumerate().skip(1) {
+            assert!(
+                !styles[i + 1..].contains(style),
+                "{:?} shares its style with another state",
+                states[i]
+            );
+        }
+    }
+
+    #[test]
+    fn moved_blocks_are_kept_and_rendered_in_the_gutter() {
+        // [CSV-UI-MovedBlocksV1] Left: a b c, right: b c a.
+        let row = |state: DiffState, left: usize, right: usize, text: &str| {
+            DiffLine::new(
+                state,
+                Some(LineContent::new(left, text)),
+                Some(LineContent::new(right, text)),
+            )
+        };
+        let diff_lines = vec![
+            row(DiffState::Unchanged, 2, 1, "b"),
+            row(DiffState::Unchanged, 3, 2, "c"),
+            row(DiffState::Moved, 1, 3, "a").with_movement(Some(1), Some(3)),
+        ];
+        let mock_diff_engine = Arc::new(
+            MockDiffEngine::new(diff_lines).with_moved_blocks(vec![MovedBlock::new(1, 1, 1, 3, 3)]),
+        );
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(15);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_LEFT,
+        });
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(left_path),
+        });
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_RIGHT,
+        });
+        let _ = app_logic.try_dequeue_command();
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(right_path),
+        });
+
+        let mut gutter_text = None;
+        while let Some(command) = app_logic.try_dequeue_command() {
+            if let PlatformCommand::SetViewerContent {
+                control_id: CONTROL_ID_MOVE_GUTTER,
+                text,
+                ..
+            } = command
+            {
+                gutter_text = Some(text);
+            }
+        }
+
+        assert_eq!(gutter_text.as_deref(), Some("▼1\r\n\r\n[1"));
+        let connectors = app_logic.moved_block_connectors();
+        assert_eq!(connectors.len(), 1);
+        assert_eq!(connectors[0].right_rows(), 2..3);
+        assert_eq!(connectors[0].origin_row(), 0);
+    }
+
+    #[test]
+    fn normalization_rules_shape_comparable_text_and_persist() {
+        // [CSV-Core-NormalizationV1][CSV-UI-NormalizationRulesV1]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(16);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let initial_diffs = mock_diff_engine.calls().len();
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_NORMALIZATION_RULES,
+            text: "side; placeholder:<SIDE>; ^(left|right)-".to_string(),
+        });
+        // Rule edits are debounced like pattern edits.
+        assert_only_debounce_timer_armed(&mut app_logic);
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+
+        let calls = mock_diff_engine.calls();
+        assert_eq!(calls.len(), initial_diffs + 1);
+        let (left_lines, right_lines) = calls.last().unwrap();
+        assert_eq!(
+            snapshot(left_lines),
+            vec![("left-alpha", "<SIDE>alpha"), ("left-beta", "<SIDE>beta")]
+        );
+        assert_eq!(
+            snapshot(right_lines),
+            vec![("right-alpha", "<SIDE>alpha"), ("right-beta", "<SIDE>beta")]
+        );
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(
+            saved[0].1.normalization_rules(),
+            &[NormalizationRule::new(
+                "side",
+                "^(left|right)-",
+                NormalizationAction::Placeholder("<SIDE>".to_string()),
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_normalization_rules_apply_error_style_and_block_diff() {
+        // [CSV-UI-NormalizationRulesV1]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(17);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let initial_diffs = mock_diff_engine.calls().len();
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_NORMALIZATION_RULES,
+            text: "broken; remove; (".to_string(),
+        });
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_NORMALIZATION_RULES,
+                style_id: StyleId::DefaultInputError,
+                ..
+            })
+        ));
+
+        // Other triggers are withheld too while the rules are invalid.
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+        assert_eq!(mock_diff_engine.calls().len(), initial_diffs);
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_NORMALIZATION_RULES,
+            text: "fixed; remove; \\(".to_string(),
+        });
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_NORMALIZATION_RULES,
+                style_id: StyleId::DefaultInput,
+                ..
+            })
+        ));
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+        assert_eq!(mock_diff_engine.calls().len(), initial_diffs + 1);
+    }
+
+    #[test]
+    fn persisted_rules_are_restored_and_common_rules_appended_from_menu() {
+        // [CSV-Core-NormalizationV1][CSV-UI-NormalizationRulesV1]
+        let uuid_rule = NormalizationRule::new(
+            "uuid",
+            "[0-9a-f-]{36}",
+            NormalizationAction::Placeholder("<ID>".to_string()),
+        );
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        *settings_manager.load_response.lock().unwrap() =
+            AppSettings::new().with_normalization_rules(vec![uuid_rule.clone()]);
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(18);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+
+        let mut rules_text = None;
+        while let Some(command) = app_logic.try_dequeue_command() {
+            if let PlatformCommand::SetInputText {
+                control_id: CONTROL_ID_NORMALIZATION_RULES,
+                text,
+                ..
+            } = command
+            {
+                rules_text = Some(text);
+            }
+        }
+        assert_eq!(
+            rules_text.as_deref(),
+            Some("uuid; placeholder:<ID>; [0-9a-f-]{36}")
+        );
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ADD_COMMON_RULES,
+        });
+        let command = app_logic
+            .try_dequeue_command()
+            .expect("expected the rules input to be refreshed");
+        let PlatformCommand::SetInputText {
+            control_id, text, ..
+        } = command
+        else {
+            panic!("unexpected command: {command:?}");
+        };
+        assert_eq!(control_id, CONTROL_ID_NORMALIZATION_RULES);
+        let lines: Vec<&str> = text.split("\r\n").collect();
+        assert_eq!(lines[0], "uuid; placeholder:<ID>; [0-9a-f-]{36}");
+        assert!(
+            lines[1..].iter().all(|line| !line.starts_with("uuid;")),
+            "rules already present by name are not duplicated"
+        );
+        assert!(lines.iter().any(|line| line.starts_with("pid;")));
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.normalization_rules()[0], uuid_rule);
+        assert_eq!(saved[0].1.normalization_rules().len(), lines.len());
+    }
+
+    #[test]
+    fn right_file_patterns_override_the_shared_list_and_persist() {
+        // [CSV-Core-TSPatternListV1]
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(19);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "^\\[old\\] \r\n\r\n^\\d+ ",
+        );
+        let calls = mock_timestamp_parser.calls();
+        let shared = vec!["^\\[old\\] ".to_string(), "^\\d+ ".to_string()];
+        assert_eq!(calls[calls.len() - 2].1, shared, "blank lines are skipped");
+        assert_eq!(
+            calls[calls.len() - 1].1,
+            shared,
+            "right file shares the list"
+        );
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+            text: "^\\d{4}-\\S+ ".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+
+        let calls = mock_timestamp_parser.calls();
+        assert_eq!(calls[calls.len() - 2].1, shared);
+        assert_eq!(calls[calls.len() - 1].1, vec!["^\\d{4}-\\S+ ".to_string()]);
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.right_timestamp_pattern(), "^\\d{4}-\\S+ ");
+    }
+
+    #[test]
+    fn invalid_right_file_pattern_names_its_entry_and_blocks_diff() {
+        // [CSV-UX-TimestampFeedbackV3]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_manager: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine, timestamp_parser, settings_manager, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(20);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+        let initial_diffs = mock_diff_engine.calls().len();
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+            text: "\\d+\r\n(unclosed".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+
+        let mut commands = Vec::new();
+        while let Some(command) = app_logic.try_dequeue_command() {
+            commands.push(command);
+        }
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+                style_id: StyleId::DefaultInputError,
+                ..
+            }
+        )));
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::UpdateLabelText { text, .. }
+                if text.starts_with("Right-file timestamp pattern #2 is invalid:")
+        )));
+        assert_eq!(mock_diff_engine.calls().len(), initial_diffs);
+    }
+
+    #[test]
+    fn timestamp_replacement_is_passed_to_the_parser_and_persisted() {
+        // [CSV-Core-TSReplaceV1]
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let clock = ManualClock::new();
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(21);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "\\d+",
+        );
+        let initial_parses = mock_timestamp_parser.calls().len();
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_REPLACEMENT,
+            text: "<TS>".to_string(),
+        });
+        assert_only_debounce_timer_armed(&mut app_logic);
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+
+        let calls = mock_timestamp_parser.calls();
+        assert_eq!(
+            calls.len(),
+            initial_parses + 2,
+            "both files are parsed again"
+        );
+        assert!(
+            calls[initial_parses..]
+                .iter()
+                .all(|(_, _, replacement)| replacement == "<TS>")
+        );
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.timestamp_replacement(), "<TS>");
+    }
+
+    #[test]
+    fn invalid_timestamp_format_is_flagged_without_blocking_diff() {
+        // [CSV-Core-TSExtractV1]
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let settings_manager = Arc::new(MockSettingsManager::default());
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let window_id = WindowId::new(22);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+            text: "[hour".to_string(),
+        });
+        let mut commands = Vec::new();
+        while let Some(command) = app_logic.try_dequeue_command() {
+            commands.push(command);
+        }
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+                style_id: StyleId::DefaultInputError,
+                ..
+            }
+        )));
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::UpdateLabelText { text, .. }
+                if text.starts_with("Timestamp format is invalid:")
+        )));
+
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_LEFT,
+        });
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(left_path),
+        });
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_RIGHT,
+        });
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(right_path),
+        });
+        drain_commands(&mut app_logic);
+        assert_eq!(mock_diff_engine.calls().len(), 1);
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+            text: "[hour]:[minute]".to_string(),
+        });
+        drain_commands(&mut app_logic);
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.timestamp_format(), "[hour]:[minute]");
+    }
+    #[test]
+    fn timing_column_flags_slowdowns_and_ratio_is_configurable() {
+        // [CSV-Core-TimingV1][CSV-UI-TimingV1]
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("left.log");
+        let right_path = temp_dir.path().join("right.log");
+        std::fs::write(
+            &left_path,
+            "[10:00:00.000] start\n[10:00:00.550] DB connection successful\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &right_path,
+            "[10:00:00.000] start\n[10:00:04.200] DB connection successful\n",
+        )
+        .unwrap();
+
+        let diff_lines = vec![
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(1, "start")),
+                Some(LineContent::new(1, "start")),
+            ),
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(2, "DB connection successful")),
+                Some(LineContent::new(2, "DB connection successful")),
+            ),
+        ];
+        let mock_diff_engine = Arc::new(MockDiffEngine::new(diff_lines));
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        *settings_manager.load_response.lock().unwrap() = AppSettings::with_values(
+            Some(LogSource::File(left_path)),
+            Some(LogSource::File(right_path)),
+            r"^\[(?P<ts>[^\]]+)\] ".to_string(),
+            VecDeque::new(),
+            DiffAlgorithm::default(),
+        )
+        .with_timestamp_format("[hour]:[minute]:[second].[subsecond]".to_string());
+
+        let diff_engine: Arc<dyn DiffEngineOperations> = mock_diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
+
+        let timing_column = |app_logic: &mut AppLogic| {
+            let mut column = None;
+            while let Some(command) = app_logic.try_dequeue_command() {
+                if let PlatformCommand::SetViewerContent {
+                    control_id: CONTROL_ID_TIMING_COLUMN,
+                    text,
+                    ..
+                } = command
+                {
+                    column = Some(text);
+                }
+            }
+            column
+        };
+
+        let window_id = WindowId::new(23);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        assert_eq!(
+            timing_column(&mut app_logic).as_deref(),
+            Some("\r\n⚠ 550 ms → 4.2 s")
+        );
+        assert_eq!(app_logic.timing_analysis().slowdowns().len(), 1);
+
+        // A higher threshold re-flags the pairs without diffing again.
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_SLOWDOWN_RATIO,
+            text: "10".to_string(),
+        });
+        assert_eq!(
+            timing_column(&mut app_logic).as_deref(),
+            Some("\r\n550 ms → 4.2 s")
+        );
+        assert_eq!(mock_diff_engine.calls().len(), 1);
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_SLOWDOWN_RATIO,
+            text: "fast".to_string(),
+        });
+        assert!(matches!(
+            app_logic.try_dequeue_command(),
+            Some(PlatformCommand::ApplyStyleToControl {
+                control_id: CONTROL_ID_SLOWDOWN_RATIO,
+                style_id: StyleId::DefaultInputError,
+                ..
+            })
+        ));
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_TIMING_REPORT,
+        });
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::ShowMessageBox {
+                message, severity, ..
+            }) => {
+                assert_eq!(
+                    message,
+                    "1 matched steps compared, 0 slowed down by more than 10×."
+                );
+                assert_eq!(severity, MessageSeverity::Information);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(saved[0].1.slowdown_ratio(), 10.0);
+    }
+    #[test]
+    fn detected_timestamp_pattern_is_proposed_only_while_none_is_set() {
+        // [CSV-Core-TSDetectV1]
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("left.log");
+        let right_path = temp_dir.path().join("right.log");
+        std::fs::write(&left_path, include_str!("../../tests/log_file_A.txt")).unwrap();
+        std::fs::write(&right_path, include_str!("../../tests/log_file_B.txt")).unwrap();
+
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(24);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let open_files = |app_logic: &mut AppLogic| {
+            let mut commands = Vec::new();
+            for (action_id, path) in [
+                (MENU_ACTION_OPEN_LEFT, &left_path),
+                (MENU_ACTION_OPEN_RIGHT, &right_path),
+            ] {
+                app_logic.handle_event(AppEvent::MenuActionClicked { action_id });
+                drain_commands(app_logic);
+                app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+                    window_id,
+                    result: Some(path.clone()),
+                });
+                while let Some(command) = app_logic.try_dequeue_command() {
+                    commands.push(command);
+                }
+            }
+            commands
+        };
+
+        let commands = open_files(&mut app_logic);
+        let proposed: Vec<_> = commands
+            .iter()
+            .filter_map(|command| match command {
+                PlatformCommand::SetInputText {
+                    control_id, text, ..
+                } if *control_id != CONTROL_ID_PATTERN_TESTER => Some((*control_id, text.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            proposed,
+            vec![
+                (
+                    CONTROL_ID_TIMESTAMP_INPUT,
+                    TIMESTAMP_FORMAT_LIBRARY[0].pattern()
+                ),
+                (
+                    CONTROL_ID_TIMESTAMP_FORMAT,
+                    TIMESTAMP_FORMAT_LIBRARY[0].format()
+                ),
+            ]
+        );
+        let parser_calls = mock_timestamp_parser.calls();
+        assert_eq!(
+            parser_calls.last().unwrap().1,
+            vec![TIMESTAMP_FORMAT_LIBRARY[0].pattern().to_string()]
+        );
+
+        // Once the user clears the pattern it stays cleared.
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: String::new(),
+        });
+        drain_commands(&mut app_logic);
+        let commands = open_files(&mut app_logic);
+        assert!(!commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::SetInputText { control_id, .. }
+                if *control_id != CONTROL_ID_PATTERN_TESTER
+        )));
+    }
+
+    #[test]
+    fn timestamp_presets_are_saved_applied_shared_and_persisted() {
+        // [CSV-Core-TSPresetsV1]
+        let mock_timestamp_parser = Arc::new(MockTimestampParser::default());
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> = mock_timestamp_parser.clone();
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(25);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+        let (temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            r"^\w+-",
+        );
+
+        let collect_commands = |app_logic: &mut AppLogic| {
+            let mut commands = Vec::new();
+            while let Some(command) = app_logic.try_dequeue_command() {
+                commands.push(command);
+            }
+            commands
+        };
+        let rebuilt_menu_names = |commands: &[PlatformCommand]| {
+            commands.iter().find_map(|command| match command {
+                PlatformCommand::CreateMainMenu { menu_items, .. } => Some(
+                    menu_items.last().unwrap().children[1]
+                        .children
+                        .iter()
+                        .map(|item| item.text.clone())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+        };
+
+        // Saving without a name is refused.
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_SAVE_PRESET,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ShowMessageBox {
+                severity: MessageSeverity::Warning,
+                ..
+            }
+        )));
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_PRESET_NAME,
+            text: "service".to_string(),
+        });
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_SAVE_PRESET,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert_eq!(
+            rebuilt_menu_names(&commands),
+            Some(vec!["service".to_string()])
+        );
+
+        // Applying the preset from the menu restores its pattern and diffs right away.
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: "other".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_APPLY_PRESET_FIRST,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::SetInputText { control_id, text, .. }
+                if *control_id == CONTROL_ID_TIMESTAMP_INPUT && text == r"^\w+-"
+        )));
+        assert_eq!(
+            mock_timestamp_parser.calls().last().unwrap().1,
+            vec![r"^\w+-".to_string()]
+        );
+
+        // Export, remove, then import the shared file again.
+        let preset_file = temp_dir.path().join("presets.json");
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_EXPORT_PRESETS,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ShowSaveFileDialog { title, default_filename, .. }
+                if title == "Export Timestamp Presets"
+                    && default_filename == "timestamp_presets.json"
+        )));
+        assert!(
+            !commands
+                .iter()
+                .any(|command| matches!(command, PlatformCommand::ShowOpenFileDialog { .. }))
+        );
+        app_logic.handle_event(AppEvent::FileSaveDialogCompleted {
+            window_id,
+            result: Some(preset_file.clone()),
+        });
+        drain_commands(&mut app_logic);
+        assert!(preset_file.exists());
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_REMOVE_PRESET_FIRST,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert_eq!(
+            rebuilt_menu_names(&commands),
+            Some(vec!["(no presets)".to_string()])
+        );
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_IMPORT_PRESETS,
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ShowOpenFileDialog { title, .. }
+                if title == "Import Timestamp Presets"
+        )));
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(preset_file),
+        });
+        let commands = collect_commands(&mut app_logic);
+        assert_eq!(
+            rebuilt_menu_names(&commands),
+            Some(vec!["service".to_string()])
+        );
+        assert!(commands.iter().any(|command| matches!(
+            command,
+            PlatformCommand::ShowMessageBox {
+                severity: MessageSeverity::Information,
+                ..
+            }
+        )));
+
+        PlatformEventHandler::on_quit(&mut app_logic);
+        let saved = settings_manager.saved_snapshots();
+        assert_eq!(
+            saved[0].1.timestamp_presets(),
+            [TimestampPreset::new("service", r"^\w+-")]
+        );
+    }
+
+    #[test]
+    fn pattern_tester_previews_matches_live_and_flags_files_it_misses() {
+        // [CSV-UX-PatternTesterV1]
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let clock = ManualClock::new();
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app")
+            .with_clock(clock.clone())
+            .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(26);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+        let (_temp_dir, left_path, right_path) = create_test_files();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+
+        let preview_after = |app_logic: &mut AppLogic, text: &str| {
+            app_logic.handle_event(AppEvent::InputTextChanged {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_INPUT,
+                text: text.to_string(),
+            });
+            // The preview does not wait for the debounce.
+            let mut preview = None;
+            while let Some(command) = app_logic.try_dequeue_command() {
+                if let PlatformCommand::SetInputText {
+                    control_id, text, ..
+                } = command
+                    && control_id == CONTROL_ID_PATTERN_TESTER
+                {
+                    preview = Some(text);
+                }
+            }
+            preview.expect("expected a pattern test preview")
+        };
+
+        assert_eq!(
+            preview_after(&mut app_logic, "^left-"),
+            "Left: 2 of 2 lines matched (100%)\r\n\
+             \x20   'left-' → alpha\r\n\
+             \x20   'left-' → beta\r\n\
+             Right: 0 of 2 lines matched (0%) - nothing is stripped, lines are compared with \
+             their timestamps\r\n\
+             \x20   (no match) → right-alpha\r\n\
+             \x20   (no match) → right-beta"
+        );
+        assert!(preview_after(&mut app_logic, "(").starts_with("Timestamp pattern #1 is invalid:"));
+    }
+
+    #[test]
+    fn pattern_diagnostic_names_entry_column_and_reason_next_to_the_input() {
+        // [CSV-UX-RegexDiagnosticsV1]
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(MockDiffEngine::new(vec![]));
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let mut app_logic = AppLogic::new(diff_engine, timestamp_parser, settings_arc, "test-app");
+        let window_id = WindowId::new(27);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let mut diagnostics_after = |text: &str| {
+            app_logic.handle_event(AppEvent::InputTextChanged {
+                window_id,
+                control_id: CONTROL_ID_TIMESTAMP_INPUT,
+                text: text.to_string(),
+            });
+            let mut diagnostics = Vec::new();
+            while let Some(command) = app_logic.try_dequeue_command() {
+                if let PlatformCommand::UpdateLabelText {
+                    control_id,
+                    text,
+                    severity,
+                    ..
+                } = command
+                    && control_id == LABEL_TIMESTAMP_DIAGNOSTIC
+                {
+                    diagnostics.push((text, severity));
+                }
+            }
+            diagnostics
+        };
+
+        assert_eq!(
+            diagnostics_after("^\\d+ \nab(c"),
+            vec![(
+                "#2, column 3: unclosed group".to_string(),
+                MessageSeverity::Error
+            )]
+        );
+        assert!(
+            diagnostics_after("^\\d+ \nab(c").is_empty(),
+            "an unchanged diagnostic is not sent again"
+        );
+        assert_eq!(
+            diagnostics_after("^\\d+ \nab)c"),
+            vec![(
+                "#2, column 3: unopened group".to_string(),
+                MessageSeverity::Error
+            )]
+        );
+        assert_eq!(
+            diagnostics_after("^\\d+ "),
+            vec![(String::new(), MessageSeverity::Information)]
+        );
+    }
+
+    #[test]
+    fn files_in_other_encodings_are_decoded_and_lossy_ones_flagged() {
+        // [CSV-File-EncodingV1]
+        let mock_settings = Arc::new(MockSettingsManager::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = mock_settings.clone();
+        let diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let diff_engine_arc: Arc<dyn DiffEngineOperations> = diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine_arc, timestamp_parser, settings_arc, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(28);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("left.log");
+        let right_path = temp_dir.path().join("right.log");
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(
+                "alpha\r\nbeta\r\n"
+                    .encode_utf16()
+                    .flat_map(u16::to_le_bytes),
+            )
+            .collect();
+        std::fs::write(&left_path, utf16).unwrap();
+        // "Grüße" as written by a Windows-1252 tool.
+        std::fs::write(&right_path, b"Gr\xFC\xDFe\n").unwrap();
+        load_files_and_pattern(
+            &mut app_logic,
+            &clock,
+            window_id,
+            &left_path,
+            &right_path,
+            "",
+        );
+
+        let last_diff_texts = || {
+            let calls = diff_engine.calls();
+            let (left, right) = calls.last().expect("expected a diff").clone();
+            let originals = |lines: Vec<ComparableLine>| {
+                lines
+                    .into_iter()
+                    .map(|line| line.original_text.to_string())
+                    .collect::<Vec<_>>()
+            };
+            (originals(left), originals(right))
+        };
+        assert_eq!(
+            last_diff_texts(),
+            (
+                vec!["alpha".to_string(), "beta".to_string()],
+                vec!["Grüße".to_string()]
+            )
+        );
+
+        let mut choose_right_encoding = |offset: u32| {
+            app_logic.handle_event(AppEvent::MenuActionClicked {
+                action_id: MenuActionId(MENU_ACTION_RIGHT_ENCODING_FIRST.0 + offset),
+            });
+            let mut menu_rebuilt = false;
+            let mut statuses = Vec::new();
+            while let Some(command) = app_logic.try_dequeue_command() {
+                match command {
+                    PlatformCommand::CreateMainMenu { .. } => menu_rebuilt = true,
+                    PlatformCommand::UpdateLabelText {
+                        control_id,
+                        text,
+                        severity,
+                        ..
+                    } if control_id == LABEL_STATUS => statuses.push((text, severity)),
+                    _ => {}
+                }
+            }
+            assert!(menu_rebuilt, "the menu marks the chosen encoding");
+            statuses
+        };
+
+        // Forcing UTF-8 cannot fail the diff; the bad bytes become replacement characters.
+        assert_eq!(
+            choose_right_encoding(1),
+            vec![(
+                "Right file is not valid UTF-8; undecodable bytes are shown as \u{FFFD}. \
+                 Choose the encoding in the File menu."
+                    .to_string(),
+                MessageSeverity::Warning
+            )]
+        );
+        assert_eq!(last_diff_texts().1, vec!["Gr\u{FFFD}\u{FFFD}e".to_string()]);
+        assert_eq!(
+            choose_right_encoding(0),
+            vec![(
+                "Right file is not UTF-8 and was read as Windows-1252. \
+                 Choose the encoding in the File menu."
+                    .to_string(),
+                MessageSeverity::Warning
+            )]
+        );
+        assert_eq!(
+            choose_right_encoding(4),
+            vec![(String::new(), MessageSeverity::Information)]
+        );
+        assert_eq!(last_diff_texts().1, vec!["Grüße".to_string()]);
+
+        app_logic.on_quit();
+        let (_, saved) = mock_settings.saved_snapshots().pop().unwrap();
+        assert_eq!(saved.left_encoding(), None);
+        assert_eq!(saved.right_encoding(), Some(TextEncoding::Windows1252));
+    }
+
+    #[test]
+    fn compressed_logs_are_diffed_and_sampled_like_plain_ones() {
+        // [CSV-File-CompressedV1]
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let diff_engine_arc: Arc<dyn DiffEngineOperations> = diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine_arc, timestamp_parser, settings_arc, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(29);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("run.log.gz");
+        let right_path = temp_dir.path().join("run.log.zst");
+        std::fs::write(&left_path, include_bytes!("../../tests/log_file_A.txt.gz")).unwrap();
+        std::fs::write(
+            &right_path,
+            include_bytes!("../../tests/log_file_A.txt.zst"),
+        )
+        .unwrap();
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_LEFT,
+        });
+        let _ = app_logic.try_dequeue_command();
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(left_path),
+        });
+        drain_commands(&mut app_logic);
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_OPEN_RIGHT,
+        });
+        let _ = app_logic.try_dequeue_command();
+        app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+            window_id,
+            result: Some(right_path),
+        });
+        let mut proposed_pattern = None;
+        while let Some(command) = app_logic.try_dequeue_command() {
+            if let PlatformCommand::SetInputText {
+                control_id, text, ..
+            } = command
+                && control_id == CONTROL_ID_TIMESTAMP_INPUT
+            {
+                proposed_pattern = Some(text);
+            }
+        }
+
+        let expected: Vec<&str> = include_str!("../../tests/log_file_A.txt").lines().collect();
+        let calls = diff_engine.calls();
+        let (left, right) = calls.last().expect("expected a diff");
+        assert_eq!(
+            left.iter()
+                .map(|line| line.original_text.as_str())
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(left.len(), right.len());
+        assert!(
+            proposed_pattern.is_some(),
+            "the first lines are sampled from the decompressed text"
+        );
+    }
+
+    #[test]
+    fn rotated_sets_and_appended_files_are_diffed_as_one_file() {
+        // [CSV-File-RotatedSetV1]
+        let settings_manager = Arc::new(MockSettingsManager::default());
+        let settings_arc: Arc<dyn SettingsManagerOperations> = settings_manager.clone();
+        let diff_engine = Arc::new(MockDiffEngine::new(vec![]));
+        let diff_engine_arc: Arc<dyn DiffEngineOperations> = diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine_arc, timestamp_parser, settings_arc, "test-app")
+                .with_clock(ManualClock::new())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(30);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let mut rotated = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        rotated.write_all(b"oldest\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("app.log.2.gz"),
+            rotated.finish().unwrap(),
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("app.log.1"), "older\n").unwrap();
+        let live_path = temp_dir.path().join("app.log");
+        std::fs::write(&live_path, "live\n").unwrap();
+        let first_path = temp_dir.path().join("first.log");
+        let second_path = temp_dir.path().join("second.log");
+        std::fs::write(&first_path, "oldest\nolder\n").unwrap();
+        std::fs::write(&second_path, "live\n").unwrap();
+
+        let open = |app_logic: &mut AppLogic, action_id, path: &PathBuf| {
+            app_logic.handle_event(AppEvent::MenuActionClicked { action_id });
+            let title = match app_logic.try_dequeue_command() {
+                Some(PlatformCommand::ShowOpenFileDialog { title, .. }) => title,
+                other => panic!("expected an open dialog, got {other:?}"),
+            };
+            app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+                window_id,
+                result: Some(path.clone()),
+            });
+            drain_commands(app_logic);
+            title
+        };
+        open(&mut app_logic, MENU_ACTION_OPEN_LEFT, &first_path);
+        let append_title = open(&mut app_logic, MENU_ACTION_APPEND_LEFT, &second_path);
+        let rotation_title = open(&mut app_logic, MENU_ACTION_OPEN_RIGHT_ROTATION, &live_path);
+
+        assert_eq!(append_title, "Append to Left Log");
+        assert_eq!(rotation_title, "Open Right Rotated Log Set");
+        let calls = diff_engine.calls();
+        let (left, right) = calls.last().expect("expected a diff");
+        let texts = |lines: &[ComparableLine]| {
+            lines
+                .iter()
+                .map(|line| line.original_text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts(left), ["oldest", "older", "live"]);
+        assert_eq!(texts(right), ["oldest", "older", "live"]);
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_EXIT,
+        });
+        let saved = settings_manager.saved_snapshots();
+        let (_, snapshot) = saved.last().expect("settings persisted on exit");
+        assert_eq!(
+            snapshot.left_file_path(),
+            Some(&LogSource::Files(vec![first_path, second_path]))
+        );
+        assert_eq!(
+            snapshot.right_file_path(),
+            Some(&LogSource::rotation_of(&live_path))
+        );
+    }
+
+    #[test]
+    fn moved_blocks_report_locates_blocks_in_the_files_of_each_side() {
+        // [CSV-UI-MovedBlocksV1] [CSV-File-RotatedSetV1]
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let diff_engine_arc: Arc<dyn DiffEngineOperations> = Arc::new(
+            MockDiffEngine::new(vec![]).with_moved_blocks(vec![MovedBlock::new(1, 1, 2, 2, 3)]),
+        );
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let mut app_logic =
+            AppLogic::new(diff_engine_arc, timestamp_parser, settings_arc, "test-app")
+                .with_clock(ManualClock::new())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(33);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_path = temp_dir.path().join("first.log");
+        std::fs::write(&left_path, "x\ny\nz\n").unwrap();
+        std::fs::write(temp_dir.path().join("app.log.1"), "z\nx\n").unwrap();
+        let live_path = temp_dir.path().join("app.log");
+        std::fs::write(&live_path, "y\n").unwrap();
+        for (action_id, path) in [
+            (MENU_ACTION_OPEN_LEFT, &left_path),
+            (MENU_ACTION_OPEN_RIGHT_ROTATION, &live_path),
+        ] {
+            app_logic.handle_event(AppEvent::MenuActionClicked { action_id });
+            drain_commands(&mut app_logic);
+            app_logic.handle_event(AppEvent::FileOpenProfileDialogCompleted {
+                window_id,
+                result: Some(path.clone()),
+            });
+            drain_commands(&mut app_logic);
+        }
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_MOVED_BLOCKS_REPORT,
+        });
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::ShowMessageBox { title, message, .. }) => {
+                assert_eq!(title, "Moved Blocks");
+                assert_eq!(
+                    message,
+                    "1 moved blocks.\r\nblock #1 moved from 1\u{2013}2 to app.log.1:2\u{2013}app.log:1"
+                );
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn directories_are_compared_pair_by_pair_and_pairs_drill_into_the_viewers() {
+        // [CSV-File-DirectoryCompareV1]
+        let settings_arc: Arc<dyn SettingsManagerOperations> =
+            Arc::new(MockSettingsManager::default());
+        let diff_engine = Arc::new(MockDiffEngine::new(vec![DiffLine::new(
+            DiffState::Added,
+            None,
+            Some(LineContent::new(1, "y")),
+        )]));
+        let diff_engine_arc: Arc<dyn DiffEngineOperations> = diff_engine.clone();
+        let timestamp_parser: Arc<dyn TimestampParserOperations> =
+            Arc::new(MockTimestampParser::default());
+        let clock = ManualClock::new();
+        let mut app_logic =
+            AppLogic::new(diff_engine_arc, timestamp_parser, settings_arc, "test-app")
+                .with_clock(clock.clone())
+                .with_job_executor(DeterministicJobExecutor::immediate());
+        let window_id = WindowId::new(31);
+        app_logic.handle_event(AppEvent::MainWindowUISetupComplete { window_id });
+        drain_commands(&mut app_logic);
+
+        let temp_dir = TempDir::new().expect("temp dir");
+        let left_dir = temp_dir.path().join("run1");
+        let right_dir = temp_dir.path().join("run2");
+        std::fs::create_dir(&left_dir).unwrap();
+        std::fs::create_dir(&right_dir).unwrap();
+        for (path, text) in [
+            (left_dir.join("api.log"), "a\n"),
+            (left_dir.join("db-0101.log"), "x\n"),
+            (right_dir.join("api.log"), "Grüße\n"),
+            (right_dir.join("db-0102.log"), "y\n"),
+            (right_dir.join("extra.log"), "z\n"),
+        ] {
+            std::fs::write(path, text).unwrap();
+        }
+        let directory_menu = |app_logic: &mut AppLogic| {
+            let mut texts = None;
+            while let Some(command) = app_logic.try_dequeue_command() {
+                if let PlatformCommand::CreateMainMenu { menu_items, .. } = command {
+                    texts = Some(
+                        menu_items[2]
+                            .children
+                            .iter()
+                            .map(|item| item.text.clone())
+                            .collect::<Vec<_>>(),
+                    );
+                }
+            }
+            texts.expect("expected the menu to be rebuilt")
+        };
+
+        // [CSV-File-EncodingV1] An encoding chosen for the viewers is not forced on the pairs.
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MenuActionId(MENU_ACTION_RIGHT_ENCODING_FIRST.0 + 4),
+        });
+        drain_commands(&mut app_logic);
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_COMPARE_DIRECTORIES,
+        });
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::ShowFolderPickerDialog { title, .. }) => {
+                assert!(title.contains("Left Run Directory"), "{title}");
+            }
+            other => panic!("expected a folder picker, got {other:?}"),
+        }
+        app_logic.handle_event(AppEvent::FolderPickerDialogCompleted {
+            window_id,
+            result: Some(left_dir.clone()),
+        });
+        match app_logic.try_dequeue_command() {
+            Some(PlatformCommand::ShowFolderPickerDialog {
+                title, initial_dir, ..
+            }) => {
+                assert!(title.contains("Right Run Directory"), "{title}");
+                assert_eq!(initial_dir.as_deref(), Some(temp_dir.path()));
+            }
+            other => panic!("expected a folder picker, got {other:?}"),
+        }
+        app_logic.handle_event(AppEvent::FolderPickerDialogCompleted {
+            window_id,
+            result: Some(right_dir.clone()),
+        });
+
+        assert_eq!(
+            directory_menu(&mut app_logic),
+            [
+                "&Compare Directories...",
+                "(diffed with Heckel)",
+                "api.log: 1 change",
+                "db-0101.log: only left",
+                "db-0102.log: only right",
+                "extra.log: only right",
+            ]
+        );
+        let calls = diff_engine.calls();
+        let (_, right) = calls.first().expect("expected a diff of api.log");
+        assert_eq!(right[0].original_text, "Grüße");
+
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_PAIRING_PATTERN,
+            text: r"-\d{4}".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        assert_eq!(
+            directory_menu(&mut app_logic),
+            [
+                "&Compare Directories...",
+                "(diffed with Heckel)",
+                "api.log: 1 change",
+                "db.log: 1 change",
+                "extra.log: only right",
+            ]
+        );
+
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MenuActionId(MENU_ACTION_DIRECTORY_PAIR_FIRST.0 + 1),
+        });
+        assert_eq!(directory_menu(&mut app_logic)[3], "✓ db.log: 1 change");
+        let calls = diff_engine.calls();
+        let (left, right) = calls.last().expect("expected a diff");
+        assert_eq!(left[0].original_text, "x");
+        assert_eq!(right[0].original_text, "y");
+
+        // A pairing edit committed by the same timer as a pattern edit is not left
+        // pending: the directories are compared once and the shown pair is forgotten.
+        let diffs_before = diff_engine.calls().len();
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_PAIRING_PATTERN,
+            text: r"-\d+".to_string(),
+        });
+        app_logic.handle_event(AppEvent::InputTextChanged {
+            window_id,
+            control_id: CONTROL_ID_TIMESTAMP_INPUT,
+            text: r"\d+".to_string(),
+        });
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        assert_eq!(directory_menu(&mut app_logic)[3], "db.log: 1 change");
+        elapse_debounce(&mut app_logic, &clock, window_id);
+        drain_commands(&mut app_logic);
+        assert_eq!(
+            diff_engine.calls().len(),
+            diffs_before + 3,
+            "the shown pair once, and each of the two compared pairs once"
+        );
+
+        // [CSV-Diff-AlgorithmChoiceV1] Another algorithm compares the directories again
+        // and the summary says which one it used.
+        app_logic.handle_event(AppEvent::MenuActionClicked {
+            action_id: MENU_ACTION_ALGORITHM_PATIENCE,
+        });
+        assert_eq!(directory_menu(&mut app_logic)[1], "(diffed with Patience)");
+    }
 }
@@ -1,14 +1,67 @@
-use commanductui::types::{ControlId, MenuActionId};
+use commanductui::types::{ControlId, MenuActionId, TimerId};
 
 pub const CONTROL_ID_TIMESTAMP_INPUT: ControlId = ControlId::new(1_001);
+pub const CONTROL_ID_NORMALIZATION_RULES: ControlId = ControlId::new(1_002);
+pub const CONTROL_ID_RIGHT_TIMESTAMP_INPUT: ControlId = ControlId::new(1_003);
+pub const CONTROL_ID_TIMESTAMP_REPLACEMENT: ControlId = ControlId::new(1_004);
+pub const CONTROL_ID_TIMESTAMP_FORMAT: ControlId = ControlId::new(1_005);
+pub const CONTROL_ID_SLOWDOWN_RATIO: ControlId = ControlId::new(1_006);
+pub const CONTROL_ID_PRESET_NAME: ControlId = ControlId::new(1_007);
+pub const CONTROL_ID_PATTERN_TESTER: ControlId = ControlId::new(1_008);
+pub const CONTROL_ID_PAIRING_PATTERN: ControlId = ControlId::new(1_009);
 pub const CONTROL_ID_LEFT_VIEWER: ControlId = ControlId::new(1_010);
 pub const CONTROL_ID_RIGHT_VIEWER: ControlId = ControlId::new(1_011);
+pub const CONTROL_ID_MOVE_GUTTER: ControlId = ControlId::new(1_012);
+pub const CONTROL_ID_TIMING_COLUMN: ControlId = ControlId::new(1_013);
+pub const CONTROL_ID_MIN_MOVED_BLOCK_SIZE: ControlId = ControlId::new(1_014);
 
 pub const PANEL_INPUT_BAR: ControlId = ControlId::new(2_001);
+pub const PANEL_RULES_BAR: ControlId = ControlId::new(2_002);
+pub const PANEL_STATUS_BAR: ControlId = ControlId::new(2_003);
+pub const PANEL_PATTERN_TESTER: ControlId = ControlId::new(2_004);
 pub const PANEL_VIEWER_CONTAINER: ControlId = ControlId::new(2_010);
 
 pub const LABEL_TIMESTAMP_PROMPT: ControlId = ControlId::new(3_001);
+pub const LABEL_NORMALIZATION_PROMPT: ControlId = ControlId::new(3_002);
+pub const LABEL_RIGHT_TIMESTAMP_PROMPT: ControlId = ControlId::new(3_003);
+pub const LABEL_TIMESTAMP_REPLACEMENT_PROMPT: ControlId = ControlId::new(3_004);
+pub const LABEL_TIMESTAMP_FORMAT_PROMPT: ControlId = ControlId::new(3_005);
+pub const LABEL_SLOWDOWN_RATIO_PROMPT: ControlId = ControlId::new(3_006);
+pub const LABEL_PRESET_NAME_PROMPT: ControlId = ControlId::new(3_007);
+pub const LABEL_PATTERN_TESTER_PROMPT: ControlId = ControlId::new(3_008);
+pub const LABEL_TIMESTAMP_DIAGNOSTIC: ControlId = ControlId::new(3_009);
+pub const LABEL_STATUS: ControlId = ControlId::new(3_010);
+pub const LABEL_PAIRING_PATTERN_PROMPT: ControlId = ControlId::new(3_011);
+pub const LABEL_MIN_MOVED_BLOCK_SIZE_PROMPT: ControlId = ControlId::new(3_012);
 
 pub const MENU_ACTION_OPEN_LEFT: MenuActionId = MenuActionId(1);
 pub const MENU_ACTION_OPEN_RIGHT: MenuActionId = MenuActionId(2);
 pub const MENU_ACTION_EXIT: MenuActionId = MenuActionId(3);
+
+pub const MENU_ACTION_ALGORITHM_HECKEL: MenuActionId = MenuActionId(4);
+pub const MENU_ACTION_ALGORITHM_MYERS: MenuActionId = MenuActionId(5);
+pub const MENU_ACTION_ALGORITHM_PATIENCE: MenuActionId = MenuActionId(6);
+
+pub const MENU_ACTION_ADD_COMMON_RULES: MenuActionId = MenuActionId(7);
+pub const MENU_ACTION_TIMING_REPORT: MenuActionId = MenuActionId(8);
+
+pub const MENU_ACTION_SAVE_PRESET: MenuActionId = MenuActionId(9);
+pub const MENU_ACTION_IMPORT_PRESETS: MenuActionId = MenuActionId(10);
+pub const MENU_ACTION_EXPORT_PRESETS: MenuActionId = MenuActionId(11);
+
+pub const MENU_ACTION_OPEN_LEFT_ROTATION: MenuActionId = MenuActionId(12);
+pub const MENU_ACTION_OPEN_RIGHT_ROTATION: MenuActionId = MenuActionId(13);
+pub const MENU_ACTION_APPEND_LEFT: MenuActionId = MenuActionId(14);
+pub const MENU_ACTION_APPEND_RIGHT: MenuActionId = MenuActionId(15);
+pub const MENU_ACTION_MOVED_BLOCKS_REPORT: MenuActionId = MenuActionId(16);
+pub const MENU_ACTION_COMPARE_DIRECTORIES: MenuActionId = MenuActionId(17);
+/// Presets get one apply and one remove action each, numbered from these ids.
+pub const MENU_ACTION_APPLY_PRESET_FIRST: MenuActionId = MenuActionId(100);
+pub const MENU_ACTION_REMOVE_PRESET_FIRST: MenuActionId = MenuActionId(200);
+/// Each file gets one action per encoding menu choice, numbered from these ids.
+pub const MENU_ACTION_LEFT_ENCODING_FIRST: MenuActionId = MenuActionId(300);
+pub const MENU_ACTION_RIGHT_ENCODING_FIRST: MenuActionId = MenuActionId(310);
+/// Each pair of a directory comparison gets one action, numbered from this id.
+pub const MENU_ACTION_DIRECTORY_PAIR_FIRST: MenuActionId = MenuActionId(1_000);
+
+pub const TIMER_ID_DEBOUNCE: TimerId = TimerId::new(1);
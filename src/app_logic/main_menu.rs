@@ -0,0 +1,482 @@
+use commanductui::types::{MenuActionId, MenuItemConfig};
+
+use crate::app_logic::ids::{
+    MENU_ACTION_ADD_COMMON_RULES, MENU_ACTION_ALGORITHM_HECKEL, MENU_ACTION_ALGORITHM_MYERS,
+    MENU_ACTION_ALGORITHM_PATIENCE, MENU_ACTION_APPEND_LEFT, MENU_ACTION_APPEND_RIGHT,
+    MENU_ACTION_APPLY_PRESET_FIRST, MENU_ACTION_COMPARE_DIRECTORIES,
+    MENU_ACTION_DIRECTORY_PAIR_FIRST, MENU_ACTION_EXIT, MENU_ACTION_EXPORT_PRESETS,
+    MENU_ACTION_IMPORT_PRESETS, MENU_ACTION_LEFT_ENCODING_FIRST, MENU_ACTION_MOVED_BLOCKS_REPORT,
+    MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_LEFT_ROTATION, MENU_ACTION_OPEN_RIGHT,
+    MENU_ACTION_OPEN_RIGHT_ROTATION, MENU_ACTION_REMOVE_PRESET_FIRST,
+    MENU_ACTION_RIGHT_ENCODING_FIRST, MENU_ACTION_SAVE_PRESET, MENU_ACTION_TIMING_REPORT,
+};
+use crate::core::{DiffAlgorithm, PairOutcome, PairSummary, TextEncoding, TimestampPreset};
+
+/// Most presets that get a menu item; the ranges of preset action ids must not overlap.
+pub const MAX_PRESET_MENU_ITEMS: usize = 100;
+/// Most pairs of a directory comparison that get a menu item; the rest are only counted.
+pub const MAX_DIRECTORY_PAIR_MENU_ITEMS: usize = 500;
+
+/// What a preset menu item does, and to which preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetMenuAction {
+    Apply(usize),
+    Remove(usize),
+}
+
+impl PresetMenuAction {
+    pub fn from_action_id(action_id: MenuActionId) -> Option<Self> {
+        let index_from = |first| action_index(action_id, first, MAX_PRESET_MENU_ITEMS);
+
+        index_from(MENU_ACTION_APPLY_PRESET_FIRST)
+            .map(PresetMenuAction::Apply)
+            .or_else(|| index_from(MENU_ACTION_REMOVE_PRESET_FIRST).map(PresetMenuAction::Remove))
+    }
+}
+
+/// Choices of the encoding menus: detection, then every supported encoding.
+pub const ENCODING_MENU_CHOICES: [Option<TextEncoding>; 5] = [
+    None,
+    Some(TextEncoding::Utf8),
+    Some(TextEncoding::Utf16Le),
+    Some(TextEncoding::Utf16Be),
+    Some(TextEncoding::Windows1252),
+];
+
+/// Encoding chosen for the left or the right file per [CSV-File-EncodingV1]; `None`
+/// detects it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingMenuAction {
+    Left(Option<TextEncoding>),
+    Right(Option<TextEncoding>),
+}
+
+impl EncodingMenuAction {
+    pub fn from_action_id(action_id: MenuActionId) -> Option<Self> {
+        let choice_from = |first| {
+            action_index(action_id, first, ENCODING_MENU_CHOICES.len())
+                .map(|index| ENCODING_MENU_CHOICES[index])
+        };
+
+        choice_from(MENU_ACTION_LEFT_ENCODING_FIRST)
+            .map(EncodingMenuAction::Left)
+            .or_else(|| {
+                choice_from(MENU_ACTION_RIGHT_ENCODING_FIRST).map(EncodingMenuAction::Right)
+            })
+    }
+}
+
+/// The pair of a directory comparison a menu item drills into, per
+/// [CSV-File-DirectoryCompareV1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectoryPairMenuAction(pub usize);
+
+impl DirectoryPairMenuAction {
+    pub fn from_action_id(action_id: MenuActionId) -> Option<Self> {
+        action_index(
+            action_id,
+            MENU_ACTION_DIRECTORY_PAIR_FIRST,
+            MAX_DIRECTORY_PAIR_MENU_ITEMS,
+        )
+        .map(DirectoryPairMenuAction)
+    }
+}
+
+/// What the Directories menu lists per [CSV-File-DirectoryCompareV1].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DirectoryMenuState<'a> {
+    /// No directories were compared.
+    #[default]
+    Idle,
+    Comparing,
+    /// The summary of the comparison, the algorithm it was diffed with, and the pair the
+    /// viewers show, if any.
+    Compared {
+        summaries: &'a [PairSummary],
+        algorithm: DiffAlgorithm,
+        shown: Option<usize>,
+    },
+}
+
+/// Index of `action_id` within the `count` ids numbered from `first`.
+fn action_index(action_id: MenuActionId, first: MenuActionId, count: usize) -> Option<usize> {
+    action_id
+        .0
+        .checked_sub(first.0)
+        .and_then(|offset| usize::try_from(offset).ok())
+        .filter(|&index| index < count)
+}
+
+/// Describes the main menu. It lists the timestamp presets, marks the chosen file
+/// encodings and summarizes the directory comparison, so the presenter sends it again
+/// whenever they change, per [CSV-Core-TSPresetsV1], [CSV-File-EncodingV1] and
+/// [CSV-File-DirectoryCompareV1].
+pub fn build_main_menu_items(
+    presets: &[TimestampPreset],
+    left_encoding: Option<TextEncoding>,
+    right_encoding: Option<TextEncoding>,
+    directories: DirectoryMenuState<'_>,
+) -> Vec<MenuItemConfig> {
+    let file_menu_items = vec![
+        MenuItemConfig {
+            action: Some(MENU_ACTION_OPEN_LEFT),
+            text: "Open &Left File...".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_OPEN_RIGHT),
+            text: "Open &Right File...".to_string(),
+            children: Vec::new(),
+        },
+        // [CSV-File-RotatedSetV1]
+        MenuItemConfig {
+            action: Some(MENU_ACTION_OPEN_LEFT_ROTATION),
+            text: "Open Left Rotated &Set...".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_OPEN_RIGHT_ROTATION),
+            text: "Open Right Rotated Se&t...".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_APPEND_LEFT),
+            text: "&Append to Left...".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_APPEND_RIGHT),
+            text: "A&ppend to Right...".to_string(),
+            children: Vec::new(),
+        },
+        // [CSV-File-EncodingV1]
+        MenuItemConfig {
+            action: None,
+            text: "Left File &Encoding".to_string(),
+            children: encoding_items(left_encoding, MENU_ACTION_LEFT_ENCODING_FIRST),
+        },
+        MenuItemConfig {
+            action: None,
+            text: "Right File E&ncoding".to_string(),
+            children: encoding_items(right_encoding, MENU_ACTION_RIGHT_ENCODING_FIRST),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_EXIT),
+            text: "E&xit".to_string(),
+            children: Vec::new(),
+        },
+    ];
+
+    // [CSV-Diff-AlgorithmChoiceV1] Lets the user pick the line matching algorithm.
+    let diff_menu_items = vec![
+        MenuItemConfig {
+            action: Some(MENU_ACTION_ALGORITHM_HECKEL),
+            text: "&Heckel (detects moves)".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_ALGORITHM_MYERS),
+            text: "&Myers (minimal edits)".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_ALGORITHM_PATIENCE),
+            text: "&Patience (unique-line anchors)".to_string(),
+            children: Vec::new(),
+        },
+        // [CSV-UI-NormalizationRulesV1]
+        MenuItemConfig {
+            action: Some(MENU_ACTION_ADD_COMMON_RULES),
+            text: "Add &Common Normalization Rules".to_string(),
+            children: Vec::new(),
+        },
+        // [CSV-UI-TimingV1]
+        MenuItemConfig {
+            action: Some(MENU_ACTION_TIMING_REPORT),
+            text: "&Timing Report...".to_string(),
+            children: Vec::new(),
+        },
+        // [CSV-UI-MovedBlocksV1]
+        MenuItemConfig {
+            action: Some(MENU_ACTION_MOVED_BLOCKS_REPORT),
+            text: "M&oved Blocks...".to_string(),
+            children: Vec::new(),
+        },
+    ];
+
+    // [CSV-File-DirectoryCompareV1] One item per file pair, drilling into its diff.
+    let mut directory_menu_items = vec![MenuItemConfig {
+        action: Some(MENU_ACTION_COMPARE_DIRECTORIES),
+        text: "&Compare Directories...".to_string(),
+        children: Vec::new(),
+    }];
+    directory_menu_items.extend(directory_pair_items(directories));
+
+    // [CSV-Core-TSPresetsV1] Named timestamp patterns, shared as JSON files.
+    let preset_menu_items = vec![
+        MenuItemConfig {
+            action: Some(MENU_ACTION_SAVE_PRESET),
+            text: "&Save Current Pattern as Preset".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: None,
+            text: "&Apply".to_string(),
+            children: preset_items(presets, MENU_ACTION_APPLY_PRESET_FIRST),
+        },
+        MenuItemConfig {
+            action: None,
+            text: "&Remove".to_string(),
+            children: preset_items(presets, MENU_ACTION_REMOVE_PRESET_FIRST),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_IMPORT_PRESETS),
+            text: "&Import Presets...".to_string(),
+            children: Vec::new(),
+        },
+        MenuItemConfig {
+            action: Some(MENU_ACTION_EXPORT_PRESETS),
+            text: "&Export Presets...".to_string(),
+            children: Vec::new(),
+        },
+    ];
+
+    vec![
+        MenuItemConfig {
+            action: None,
+            text: "&File".to_string(),
+            children: file_menu_items,
+        },
+        MenuItemConfig {
+            action: None,
+            text: "&Diff".to_string(),
+            children: diff_menu_items,
+        },
+        MenuItemConfig {
+            action: None,
+            text: "Di&rectories".to_string(),
+            children: directory_menu_items,
+        },
+        MenuItemConfig {
+            action: None,
+            text: "&Presets".to_string(),
+            children: preset_menu_items,
+        },
+    ]
+}
+
+fn encoding_items(chosen: Option<TextEncoding>, first: MenuActionId) -> Vec<MenuItemConfig> {
+    ENCODING_MENU_CHOICES
+        .iter()
+        .zip(first.0..)
+        .map(|(&choice, id)| {
+            let name = choice.map_or("&Detect Automatically", |encoding| encoding.name());
+            let mark = if choice == chosen { "✓ " } else { "" };
+            MenuItemConfig {
+                action: Some(MenuActionId(id)),
+                text: format!("{mark}{name}"),
+                children: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn directory_pair_items(directories: DirectoryMenuState<'_>) -> Vec<MenuItemConfig> {
+    let inert = |text: String| MenuItemConfig {
+        action: None,
+        text,
+        children: Vec::new(),
+    };
+    let (summaries, algorithm, shown) = match directories {
+        DirectoryMenuState::Idle => return Vec::new(),
+        DirectoryMenuState::Comparing => return vec![inert("(comparing...)".to_string())],
+        DirectoryMenuState::Compared {
+            summaries,
+            algorithm,
+            shown,
+        } => (summaries, algorithm, shown),
+    };
+    if summaries.is_empty() {
+        return vec![inert("(no files)".to_string())];
+    }
+
+    let mut items: Vec<MenuItemConfig> = summaries
+        .iter()
+        .take(MAX_DIRECTORY_PAIR_MENU_ITEMS)
+        .zip(MENU_ACTION_DIRECTORY_PAIR_FIRST.0..)
+        .enumerate()
+        .map(|(index, (summary, id))| {
+            let mark = if shown == Some(index) { "✓ " } else { "" };
+            let key = summary.pair().key().replace('&', "&&");
+            let (result, action) = match summary.outcome() {
+                PairOutcome::Compared { total_changes: 0 } => ("identical".to_string(), Some(id)),
+                PairOutcome::Compared { total_changes: 1 } => ("1 change".to_string(), Some(id)),
+                PairOutcome::Compared { total_changes } => {
+                    (format!("{total_changes} changes"), Some(id))
+                }
+                PairOutcome::Unpaired if summary.pair().left().is_some() => {
+                    ("only left".to_string(), None)
+                }
+                PairOutcome::Unpaired => ("only right".to_string(), None),
+                PairOutcome::Failed(_) => ("failed".to_string(), Some(id)),
+            };
+            MenuItemConfig {
+                action: action.map(MenuActionId),
+                text: format!("{mark}{key}: {result}"),
+                children: Vec::new(),
+            }
+        })
+        .collect();
+    // [CSV-Diff-AlgorithmChoiceV1] The counts depend on the algorithm, so name it.
+    items.insert(0, inert(format!("(diffed with {})", algorithm.name())));
+    if summaries.len() > MAX_DIRECTORY_PAIR_MENU_ITEMS {
+        items.push(inert(format!(
+            "... and {} more",
+            summaries.len() - MAX_DIRECTORY_PAIR_MENU_ITEMS
+        )));
+    }
+    items
+}
+
+fn preset_items(presets: &[TimestampPreset], first: MenuActionId) -> Vec<MenuItemConfig> {
+    if presets.is_empty() {
+        return vec![MenuItemConfig {
+            action: None,
+            text: "(no presets)".to_string(),
+            children: Vec::new(),
+        }];
+    }
+
+    presets
+        .iter()
+        .take(MAX_PRESET_MENU_ITEMS)
+        .zip(first.0..)
+        .map(|(preset, id)| MenuItemConfig {
+            action: Some(MenuActionId(id)),
+            text: preset.name().replace('&', "&&"),
+            children: Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pair_files;
+    use std::path::PathBuf;
+
+    #[test]
+    fn preset_items_map_back_to_their_presets() {
+        // [CSV-Core-TSPresetsV1]
+        let presets = vec![
+            TimestampPreset::new("log4j", r"^\S+ \S+ "),
+            TimestampPreset::new("nginx & co", r"\[[^\]]+\] "),
+        ];
+
+        let menu = build_main_menu_items(&presets, None, None, DirectoryMenuState::Idle);
+        let preset_menu = menu.last().unwrap();
+        let apply = &preset_menu.children[1].children;
+        let remove = &preset_menu.children[2].children;
+
+        assert_eq!(apply[1].text, "nginx && co");
+        assert_eq!(
+            PresetMenuAction::from_action_id(apply[1].action.unwrap()),
+            Some(PresetMenuAction::Apply(1))
+        );
+        assert_eq!(
+            PresetMenuAction::from_action_id(remove[0].action.unwrap()),
+            Some(PresetMenuAction::Remove(0))
+        );
+        assert_eq!(
+            PresetMenuAction::from_action_id(MENU_ACTION_EXPORT_PRESETS),
+            None
+        );
+    }
+
+    #[test]
+    fn encoding_items_mark_the_chosen_encoding_and_map_back_to_it() {
+        // [CSV-File-EncodingV1]
+        let menu = build_main_menu_items(
+            &[],
+            None,
+            Some(TextEncoding::Utf16Le),
+            DirectoryMenuState::Idle,
+        );
+        let file_menu = &menu[0];
+        let left = &file_menu.children[6].children;
+        let right = &file_menu.children[7].children;
+
+        assert_eq!(left[0].text, "✓ &Detect Automatically");
+        assert_eq!(right[0].text, "&Detect Automatically");
+        assert_eq!(right[2].text, "✓ UTF-16 LE");
+        assert_eq!(
+            EncodingMenuAction::from_action_id(left[4].action.unwrap()),
+            Some(EncodingMenuAction::Left(Some(TextEncoding::Windows1252)))
+        );
+        assert_eq!(
+            EncodingMenuAction::from_action_id(right[0].action.unwrap()),
+            Some(EncodingMenuAction::Right(None))
+        );
+        assert_eq!(
+            EncodingMenuAction::from_action_id(MENU_ACTION_APPLY_PRESET_FIRST),
+            None
+        );
+    }
+
+    #[test]
+    fn directory_items_summarize_each_pair_and_drill_into_it() {
+        // [CSV-File-DirectoryCompareV1]
+        let pairs = pair_files(
+            vec![
+                ("a&b.log".to_string(), PathBuf::from("left/a&b.log")),
+                ("db.log".to_string(), PathBuf::from("left/db.log")),
+            ],
+            vec![
+                ("a&b.log".to_string(), PathBuf::from("right/a&b.log")),
+                ("db.log".to_string(), PathBuf::from("right/db.log")),
+                ("new.log".to_string(), PathBuf::from("right/new.log")),
+            ],
+            None,
+        );
+        let outcomes = [
+            PairOutcome::Compared { total_changes: 3 },
+            PairOutcome::Compared { total_changes: 0 },
+            PairOutcome::Unpaired,
+        ];
+        let summaries: Vec<PairSummary> = pairs
+            .into_iter()
+            .zip(outcomes)
+            .map(|(pair, outcome)| PairSummary::new(pair, outcome))
+            .collect();
+
+        let menu = build_main_menu_items(
+            &[],
+            None,
+            None,
+            DirectoryMenuState::Compared {
+                summaries: &summaries,
+                algorithm: DiffAlgorithm::Myers,
+                shown: Some(1),
+            },
+        );
+        let items = &menu[2].children;
+
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "&Compare Directories...",
+                "(diffed with Myers)",
+                "a&&b.log: 3 changes",
+                "✓ db.log: identical",
+                "new.log: only right",
+            ]
+        );
+        assert_eq!(
+            DirectoryPairMenuAction::from_action_id(items[3].action.unwrap()),
+            Some(DirectoryPairMenuAction(1))
+        );
+        assert_eq!(items[1].action, None);
+        assert_eq!(items[4].action, None);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::core::{DiffLine, DiffState, LineOrigins, MovedBlock};
+
+/// Most blocks listed by the moved blocks report; the rest are only counted.
+const MAX_REPORTED_BLOCKS: usize = 20;
+
+/// Viewer-side placement of one `MovedBlock` per [CSV-UI-MovedBlocksV1].
+///
+/// Both viewers render one row per `DiffLine`, so a moved block occupies the same rows in
+/// the left and right panel. The connector therefore links the rows where the block is
+/// shown now with the row of the left panel where its lines used to sit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedBlockConnector {
+    block_id: usize,
+    left_rows: Range<usize>,
+    right_rows: Range<usize>,
+    origin_row: usize,
+}
+
+impl MovedBlockConnector {
+    pub fn new(
+        block_id: usize,
+        left_rows: Range<usize>,
+        right_rows: Range<usize>,
+        origin_row: usize,
+    ) -> Self {
+        Self {
+            block_id,
+            left_rows,
+            right_rows,
+            origin_row,
+        }
+    }
+
+    /// Id of the connected `MovedBlock`, shown to the user.
+    pub fn block_id(&self) -> usize {
+        self.block_id
+    }
+
+    /// Viewer rows showing the block's source lines in the left panel.
+    pub fn left_rows(&self) -> Range<usize> {
+        self.left_rows.clone()
+    }
+
+    /// Viewer rows showing the block's destination lines in the right panel.
+    pub fn right_rows(&self) -> Range<usize> {
+        self.right_rows.clone()
+    }
+
+    /// Row before which the block's lines appeared in the left file's original order.
+    pub fn origin_row(&self) -> usize {
+        self.origin_row
+    }
+
+    fn moved_down(&self) -> bool {
+        self.origin_row < self.right_rows.start
+    }
+}
+
+/// Maps each moved block onto viewer rows. Blocks whose lines cannot be found in `lines`
+/// are skipped.
+pub fn build_moved_block_connectors(
+    lines: &[DiffLine],
+    blocks: &[MovedBlock],
+) -> Vec<MovedBlockConnector> {
+    let rows = RowIndex::new(lines);
+
+    blocks
+        .iter()
+        .filter_map(|block| {
+            let left_rows = rows_between(&rows.left, block.source_start(), block.source_end())?;
+            let right_rows = rows_between(
+                &rows.right,
+                block.destination_start(),
+                block.destination_end(),
+            )?;
+
+            Some(MovedBlockConnector::new(
+                block.id(),
+                left_rows,
+                right_rows,
+                rows.origin_row(block.source_end()),
+            ))
+        })
+        .collect()
+}
+
+/// Builds the text of the gutter between the viewers, one line per viewer row. Rows of a
+/// block get a bracket with the block id, and the block's origin gets an arrow that
+/// points towards where the block went.
+pub fn build_gutter_text(row_count: usize, connectors: &[MovedBlockConnector]) -> String {
+    let mut rows: Vec<String> = vec![String::new(); row_count];
+
+    for connector in connectors {
+        let id = connector.block_id();
+        let block_rows = connector.right_rows();
+        for row in block_rows.clone() {
+            let bracket = if block_rows.len() == 1 {
+                '['
+            } else if row == block_rows.start {
+                '┌'
+            } else if row + 1 == block_rows.end {
+                '└'
+            } else {
+                '│'
+            };
+            push_marker(&mut rows, row, &format!("{bracket}{id}"));
+        }
+
+        let arrow = if connector.moved_down() { '▼' } else { '▲' };
+        // The origin lies between rows; mark the row just above it when there is one.
+        let origin = connector.origin_row().saturating_sub(1);
+        if origin < row_count && !block_rows.contains(&origin) {
+            push_marker(&mut rows, origin, &format!("{arrow}{id}"));
+        }
+    }
+
+    rows.join("\r\n")
+}
+
+/// Lists the moved blocks for the moved blocks report, with the lines of each side located
+/// in the files they came from per [CSV-File-RotatedSetV1].
+pub fn build_moved_blocks_report(
+    blocks: &[MovedBlock],
+    left_origins: &LineOrigins,
+    right_origins: &LineOrigins,
+) -> String {
+    if blocks.is_empty() {
+        return "No blocks were moved.".to_string();
+    }
+
+    let mut report = format!("{} moved blocks.", blocks.len());
+    for block in blocks.iter().take(MAX_REPORTED_BLOCKS) {
+        report.push_str("\r\n");
+        report.push_str(&block.describe(left_origins, right_origins));
+    }
+    if blocks.len() > MAX_REPORTED_BLOCKS {
+        report.push_str(&format!(
+            "\r\n… and {} more",
+            blocks.len() - MAX_REPORTED_BLOCKS
+        ));
+    }
+
+    report
+}
+
+fn push_marker(rows: &mut [String], row: usize, marker: &str) {
+    let text = &mut rows[row];
+    if !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(marker);
+}
+
+/// Viewer rows by file line number, built in one pass so mapping a block costs a few
+/// lookups rather than a scan of every row.
+struct RowIndex {
+    left: HashMap<usize, usize>,
+    right: HashMap<usize, usize>,
+    /// Left line numbers of rows that did not move, with their rows, by line number.
+    stayed_left: Vec<(usize, usize)>,
+    row_count: usize,
+}
+
+impl RowIndex {
+    fn new(lines: &[DiffLine]) -> Self {
+        let mut left = HashMap::with_capacity(lines.len());
+        let mut right = HashMap::with_capacity(lines.len());
+        let mut stayed_left = Vec::new();
+
+        for (row, line) in lines.iter().enumerate() {
+            if let Some(content) = line.left() {
+                left.insert(content.line_number(), row);
+                if line.state() != DiffState::Moved {
+                    stayed_left.push((content.line_number(), row));
+                }
+            }
+            if let Some(content) = line.right() {
+                right.insert(content.line_number(), row);
+            }
+        }
+        stayed_left.sort_unstable();
+
+        Self {
+            left,
+            right,
+            stayed_left,
+            row_count: lines.len(),
+        }
+    }
+
+    /// Finds the row of the first left line after `source_end` that stayed in place; the
+    /// block originally sat right before it.
+    fn origin_row(&self, source_end: usize) -> usize {
+        let after = self
+            .stayed_left
+            .partition_point(|&(number, _)| number <= source_end);
+        self.stayed_left
+            .get(after)
+            .map_or(self.row_count, |&(_, row)| row)
+    }
+}
+
+/// Rows from the row of line `first` through the row of line `last`, in either order.
+fn rows_between(rows: &HashMap<usize, usize>, first: usize, last: usize) -> Option<Range<usize>> {
+    let (first_row, last_row) = (*rows.get(&first)?, *rows.get(&last)?);
+    Some(first_row.min(last_row)..first_row.max(last_row) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LineContent;
+    use std::path::PathBuf;
+
+    fn unchanged(left: usize, right: usize) -> DiffLine {
+        DiffLine::new(
+            DiffState::Unchanged,
+            Some(LineContent::new(left, format!("line {left}"))),
+            Some(LineContent::new(right, format!("line {left}"))),
+        )
+    }
+
+    fn moved(left: usize, right: usize) -> DiffLine {
+        DiffLine::new(
+            DiffState::Moved,
+            Some(LineContent::new(left, format!("line {left}"))),
+            Some(LineContent::new(right, format!("line {left}"))),
+        )
+        .with_movement(Some(left), Some(right))
+    }
+
+    /// Left file: 1 2 3 4 5; right file: 1 4 5 2 3 (lines 2-3 moved down).
+    fn moved_down_rows() -> Vec<DiffLine> {
+        vec![
+            unchanged(1, 1),
+            unchanged(4, 2),
+            unchanged(5, 3),
+            moved(2, 4),
+            moved(3, 5),
+        ]
+    }
+
+    #[test]
+    fn connector_maps_block_to_rows_and_origin() {
+        // [CSV-UI-MovedBlocksV1]
+        let lines = moved_down_rows();
+        let blocks = vec![MovedBlock::new(1, 2, 3, 4, 5)];
+
+        let connectors = build_moved_block_connectors(&lines, &blocks);
+
+        assert_eq!(connectors, vec![MovedBlockConnector::new(1, 3..5, 3..5, 1)]);
+        assert!(connectors[0].moved_down());
+    }
+
+    #[test]
+    fn connector_origin_defaults_to_end_when_block_came_last() {
+        // Left file: 1 2 3; right file: 3 1 2 (line 3 moved up).
+        let lines = vec![moved(3, 1), unchanged(1, 2), unchanged(2, 3)];
+        let blocks = vec![MovedBlock::new(1, 3, 3, 1, 1)];
+
+        let connectors = build_moved_block_connectors(&lines, &blocks);
+
+        assert_eq!(connectors[0].origin_row(), 3);
+        assert!(!connectors[0].moved_down());
+    }
+
+    #[test]
+    fn gutter_text_brackets_blocks_and_marks_origins() {
+        let lines = moved_down_rows();
+        let connectors = build_moved_block_connectors(&lines, &[MovedBlock::new(1, 2, 3, 4, 5)]);
+
+        let gutter = build_gutter_text(lines.len(), &connectors);
+
+        assert_eq!(gutter, "▼1\r\n\r\n\r\n┌1\r\n└1");
+    }
+
+    #[test]
+    fn moved_blocks_report_locates_lines_in_their_files() {
+        // [CSV-UI-MovedBlocksV1][CSV-File-RotatedSetV1]
+        let mut left = LineOrigins::default();
+        left.push_file(PathBuf::from("logs/app.log.1"), 2);
+        left.push_file(PathBuf::from("logs/app.log"), 3);
+
+        let report = build_moved_blocks_report(
+            &[MovedBlock::new(1, 2, 3, 4, 5)],
+            &left,
+            &LineOrigins::default(),
+        );
+
+        assert_eq!(
+            report,
+            "1 moved blocks.\r\nblock #1 moved from app.log.1:2\u{2013}app.log:1 to 4\u{2013}5"
+        );
+        assert_eq!(
+            build_moved_blocks_report(&[], &left, &left),
+            "No blocks were moved."
+        );
+    }
+}
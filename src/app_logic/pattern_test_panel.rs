@@ -0,0 +1,81 @@
+use crate::core::{PatternTestLine, PatternTestResult};
+
+/// Builds the text of the pattern tester panel per [CSV-UX-PatternTesterV1]: for each
+/// loaded file, how many sample lines the timestamp patterns matched, followed by what
+/// they matched in each line and the comparable text that is left.
+pub fn build_pattern_test_text(sides: &[(&str, PatternTestResult)]) -> String {
+    if sides.is_empty() {
+        return "Open a file to see what the timestamp patterns match in its first lines."
+            .to_string();
+    }
+
+    let mut rows = Vec::new();
+    for (side, result) in sides {
+        rows.push(describe_coverage(side, result));
+        rows.extend(result.lines().iter().map(describe_line));
+    }
+    rows.join("\r\n")
+}
+
+fn describe_coverage(side: &str, result: &PatternTestResult) -> String {
+    let total = result.lines().len();
+    let matched = result.matched_line_count();
+    let mut text = format!(
+        "{side}: {matched} of {total} lines matched ({:.0}%)",
+        result.match_fraction() * 100.0
+    );
+    // [CSV-UX-PatternTesterV1] The diff silently keeps unmatched lines as they are.
+    if matched == 0 && total > 0 {
+        text.push_str(" - nothing is stripped, lines are compared with their timestamps");
+    }
+    text
+}
+
+fn describe_line(line: &PatternTestLine) -> String {
+    let matched = if line.is_matched() {
+        line.matches()
+            .iter()
+            .map(|text| format!("'{text}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else {
+        "(no match)".to_string()
+    };
+    format!("    {matched} → {}", line.comparable_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{LineNormalizer, test_pattern_list};
+
+    fn result(lines: &[&str], pattern: &str) -> PatternTestResult {
+        let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        test_pattern_list(
+            &lines,
+            &[pattern.to_string()],
+            "",
+            &LineNormalizer::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn panel_shows_coverage_matches_and_comparable_text_per_file() {
+        // [CSV-UX-PatternTesterV1]
+        let left = result(&["[1] alpha", "beta"], r"^\[\d+\] ");
+        let right = result(&["2 alpha"], r"^\[\d+\] ");
+
+        let text = build_pattern_test_text(&[("Left", left), ("Right", right)]);
+
+        assert_eq!(
+            text,
+            "Left: 1 of 2 lines matched (50%)\r\n\
+             \x20   '[1] ' → alpha\r\n\
+             \x20   (no match) → beta\r\n\
+             Right: 0 of 1 lines matched (0%) - nothing is stripped, lines are compared with \
+             their timestamps\r\n\
+             \x20   (no match) → 2 alpha"
+        );
+    }
+}
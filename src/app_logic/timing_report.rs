@@ -0,0 +1,166 @@
+use time::Duration;
+
+use crate::core::{DiffLine, LineOrigins, TimingAnalysis, TimingPair};
+
+/// Most slowdowns listed by the timing report; the rest are only counted.
+const MAX_REPORTED_SLOWDOWNS: usize = 20;
+
+/// Builds the text of the timing column next to the right viewer, one line per viewer row
+/// per [CSV-UI-TimingV1]. Each paired row shows how long the step leading up to it took on
+/// the left and on the right, and slowdowns are marked.
+pub fn build_timing_column_text(row_count: usize, analysis: &TimingAnalysis) -> String {
+    let mut rows: Vec<String> = vec![String::new(); row_count];
+
+    for pair in analysis.pairs() {
+        if let Some(text) = rows.get_mut(pair.row()) {
+            let marker = if pair.is_slowdown() { "⚠ " } else { "" };
+            *text = format!(
+                "{marker}{} → {}",
+                format_elapsed(pair.left_elapsed()),
+                format_elapsed(pair.right_elapsed())
+            );
+        }
+    }
+
+    rows.join("\r\n")
+}
+
+/// Summary of the slowdowns of `analysis`, largest first, for the timing report dialog.
+/// Lines of a right side read from several files are named by file per
+/// [CSV-File-RotatedSetV1].
+pub fn build_timing_report(
+    analysis: &TimingAnalysis,
+    lines: &[DiffLine],
+    right_origins: &LineOrigins,
+) -> String {
+    let slowdowns = analysis.slowdowns();
+    let mut report = format!(
+        "{} matched steps compared, {} slowed down by more than {}×.",
+        analysis.pairs().len(),
+        slowdowns.len(),
+        analysis.slowdown_ratio()
+    );
+    if analysis.pairs().is_empty() {
+        report.push_str("\r\n\r\nSet a time format so the timestamps of both files can be parsed.");
+        return report;
+    }
+
+    for pair in slowdowns.iter().take(MAX_REPORTED_SLOWDOWNS) {
+        report.push_str("\r\n");
+        report.push_str(&describe_slowdown(pair, lines, right_origins));
+    }
+    if slowdowns.len() > MAX_REPORTED_SLOWDOWNS {
+        report.push_str(&format!(
+            "\r\n… and {} more",
+            slowdowns.len() - MAX_REPORTED_SLOWDOWNS
+        ));
+    }
+
+    report
+}
+
+fn describe_slowdown(pair: &TimingPair, lines: &[DiffLine], right_origins: &LineOrigins) -> String {
+    let ratio = pair.ratio().unwrap_or_default();
+    let event = lines.get(pair.row()).and_then(|line| line.right());
+    let (line_number, text) =
+        event.map_or((0, ""), |content| (content.line_number(), content.text()));
+
+    format!(
+        "Line {}: {} → {} ({ratio:.1}×) {text}",
+        right_origins.describe(line_number),
+        format_elapsed(pair.left_elapsed()),
+        format_elapsed(pair.right_elapsed())
+    )
+}
+
+/// Renders an elapsed time with a unit that keeps it short, e.g. `550 ms` or `4.2 s`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let sign = if elapsed.is_negative() { "-" } else { "" };
+    let elapsed = elapsed.abs();
+
+    if elapsed < Duration::SECOND {
+        format!("{sign}{} ms", elapsed.whole_milliseconds())
+    } else if elapsed < Duration::MINUTE {
+        format!("{sign}{:.1} s", elapsed.as_seconds_f64())
+    } else {
+        format!("{sign}{:.1} min", elapsed.as_seconds_f64() / 60.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiffState, LineContent};
+    use std::path::PathBuf;
+    use time::OffsetDateTime;
+
+    fn analysis_with_one_slowdown() -> (Vec<DiffLine>, TimingAnalysis) {
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(1, "start")),
+                Some(LineContent::new(1, "start")),
+            ),
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(2, "DB connection successful")),
+                Some(LineContent::new(2, "DB connection successful")),
+            ),
+            DiffLine::new(DiffState::Added, None, Some(LineContent::new(3, "extra"))),
+        ];
+        let epoch = OffsetDateTime::UNIX_EPOCH;
+        let left = vec![Some(epoch), Some(epoch + Duration::milliseconds(550))];
+        let right = vec![
+            Some(epoch),
+            Some(epoch + Duration::milliseconds(4_200)),
+            None,
+        ];
+        let analysis = TimingAnalysis::analyze(&lines, &left, &right, 2.0);
+        (lines, analysis)
+    }
+
+    #[test]
+    fn timing_column_has_a_row_per_viewer_row() {
+        // [CSV-UI-TimingV1]
+        let (lines, analysis) = analysis_with_one_slowdown();
+
+        let column = build_timing_column_text(lines.len(), &analysis);
+
+        assert_eq!(column, "\r\n⚠ 550 ms → 4.2 s\r\n");
+    }
+
+    #[test]
+    fn timing_report_lists_slowdowns_with_their_lines() {
+        // [CSV-UI-TimingV1]
+        let (lines, analysis) = analysis_with_one_slowdown();
+
+        let report = build_timing_report(&analysis, &lines, &LineOrigins::default());
+
+        assert_eq!(
+            report,
+            "1 matched steps compared, 1 slowed down by more than 2×.\r\n\
+             Line 2: 550 ms → 4.2 s (7.6×) DB connection successful"
+        );
+    }
+
+    #[test]
+    fn timing_report_names_the_file_of_a_rotated_set() {
+        // [CSV-File-RotatedSetV1]
+        let (lines, analysis) = analysis_with_one_slowdown();
+        let mut origins = LineOrigins::default();
+        origins.push_file(PathBuf::from("logs/app.log.1"), 1);
+        origins.push_file(PathBuf::from("logs/app.log"), 2);
+
+        let report = build_timing_report(&analysis, &lines, &origins);
+
+        assert!(report.ends_with("Line app.log:1: 550 ms → 4.2 s (7.6×) DB connection successful"));
+    }
+
+    #[test]
+    fn elapsed_times_pick_a_readable_unit() {
+        assert_eq!(format_elapsed(Duration::milliseconds(550)), "550 ms");
+        assert_eq!(format_elapsed(Duration::milliseconds(4_200)), "4.2 s");
+        assert_eq!(format_elapsed(Duration::seconds(90)), "1.5 min");
+        assert_eq!(format_elapsed(Duration::milliseconds(-20)), "-20 ms");
+    }
+}
@@ -1,13 +1,52 @@
+pub mod decompression;
+pub mod diff_control;
 pub mod diff_engine;
+pub mod directory_pairing;
+pub mod intra_line_diff;
+pub mod line_normalizer;
+pub mod line_store;
+pub mod log_source;
 pub mod path_utils;
+pub mod pattern_tester;
+pub mod regex_cache;
 pub mod settings;
 pub mod settings_manager;
+pub mod text_decoding;
+pub mod timestamp_extractor;
 pub mod timestamp_parser;
+pub mod timestamp_presets;
+pub mod timing_analysis;
 
+pub use decompression::{LogDecompressor, LogDecompressors};
+pub use diff_control::{CancellationToken, DiffCancelled, DiffControl, DiffPhase, DiffProgress};
 pub use diff_engine::{
-    ComparableLine, DiffEngineOperations, DiffLine, DiffResult, DiffState, DiffStatistics,
-    LineContent, MovedBlock,
+    ComparableLine, DEFAULT_MIN_MOVED_BLOCK_SIZE, DiffAlgorithm, DiffEngineOperations, DiffLine,
+    DiffResult, DiffState, DiffStatistics, LineContent, MovedBlock,
 };
-pub use settings::AppSettings;
+pub use directory_pairing::{FilePair, PairOutcome, PairSummary, list_directory_files, pair_files};
+pub use intra_line_diff::ChangeSpan;
+pub use line_normalizer::{
+    LineNormalizer, NormalizationAction, NormalizationError, NormalizationRule,
+};
+pub use line_store::{LineStore, LineText};
+pub use log_source::{LineLocation, LineOrigins, LogSource};
+pub use pattern_tester::{
+    PATTERN_TEST_SAMPLE_LINES, PatternTestLine, PatternTestResult, test_pattern_list,
+};
+pub use regex_cache::{DEFAULT_REGEX_CACHE_CAPACITY, RegexCacheStats};
+pub use settings::{AppSettings, ComparisonAlgorithm};
 pub use settings_manager::{CoreSettingsManager, SettingsManagerOperations};
-pub use timestamp_parser::{TimestampParserError, TimestampParserOperations};
+pub use text_decoding::{
+    DecodedText, DecodingOutcome, TextEncoding, decode_text, decodes_as_itself, detect_encoding,
+};
+pub use timestamp_extractor::{
+    ExtractedLine, TimestampExtractor, TimestampExtractorError, parse_line_timestamps,
+    validate_timestamp_format,
+};
+pub use timestamp_parser::{
+    PatternErrorKind, PatternSpan, TIMESTAMP_DETECTION_SAMPLE_LINES, TIMESTAMP_FORMAT_LIBRARY,
+    TimestampFormatCandidate, TimestampParserError, TimestampParserOperations,
+    detect_timestamp_pattern, split_pattern_list, validate_pattern_list,
+};
+pub use timestamp_presets::{TimestampPreset, TimestampPresetError};
+pub use timing_analysis::{DEFAULT_SLOWDOWN_RATIO, TimingAnalysis, TimingPair};
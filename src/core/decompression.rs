@@ -0,0 +1,384 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvError, SyncSender};
+use std::thread;
+
+use ruzstd::decoding::{FrameDecoder, StreamingDecoder};
+
+/// Decoder for one compressed log format, recognized by the magic bytes its files start
+/// with, per [CSV-File-CompressedV1].
+pub trait LogDecompressor: Send + Sync {
+    /// Format name used in log messages, e.g. `gzip`.
+    fn name(&self) -> &'static str;
+
+    /// Bytes every file of this format starts with.
+    fn magic(&self) -> &'static [u8];
+
+    /// Wraps `compressed`, positioned at the magic bytes, in a reader of the decompressed
+    /// bytes. Both are `Send` so a decoder may move its work to a thread of its own.
+    fn decompress(&self, compressed: Box<dyn BufRead + Send>) -> io::Result<Box<dyn Read + Send>>;
+}
+
+/// Decompressors tried, in order, on every log file that is opened. Files matching none
+/// of them are read as they are.
+#[derive(Clone)]
+pub struct LogDecompressors {
+    decompressors: Vec<Arc<dyn LogDecompressor>>,
+}
+
+impl LogDecompressors {
+    /// No decompressors: every file is read as it is.
+    pub fn empty() -> Self {
+        Self {
+            decompressors: Vec::new(),
+        }
+    }
+
+    pub fn with_decompressor(mut self, decompressor: Arc<dyn LogDecompressor>) -> Self {
+        self.decompressors.push(decompressor);
+        self
+    }
+
+    /// The decompressor whose magic bytes `start` begins with.
+    pub fn decompressor_for(&self, start: &[u8]) -> Option<&dyn LogDecompressor> {
+        self.decompressors
+            .iter()
+            .find(|decompressor| start.starts_with(decompressor.magic()))
+            .map(Arc::as_ref)
+    }
+
+    /// Opens `path` for reading its text, decompressing it when it starts with the magic
+    /// bytes of a known format.
+    pub fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        let mut file = BufReader::new(File::open(path)?);
+        // The buffer holds several kilobytes, far more than the longest magic.
+        let Some(decompressor) = self.decompressor_for(file.fill_buf()?) else {
+            return Ok(Box::new(file));
+        };
+
+        log::debug!(
+            "[CSV-File-CompressedV1] Decompressing '{}' as {}",
+            path.display(),
+            decompressor.name()
+        );
+        let text: Box<dyn Read> = decompressor.decompress(Box::new(file))?;
+        Ok(text)
+    }
+}
+
+impl Default for LogDecompressors {
+    /// gzip, zstd, bzip2 and xz, the formats log archivers commonly produce.
+    fn default() -> Self {
+        Self::empty()
+            .with_decompressor(Arc::new(GzipDecompressor))
+            .with_decompressor(Arc::new(ZstdDecompressor))
+            .with_decompressor(Arc::new(Bzip2Decompressor))
+            .with_decompressor(Arc::new(XzDecompressor))
+    }
+}
+
+/// `.gz` files, including several gzip members concatenated into one file.
+#[derive(Debug, Default)]
+pub struct GzipDecompressor;
+
+impl LogDecompressor for GzipDecompressor {
+    fn name(&self) -> &'static str {
+        "gzip"
+    }
+
+    fn magic(&self) -> &'static [u8] {
+        &[0x1F, 0x8B]
+    }
+
+    fn decompress(&self, compressed: Box<dyn BufRead + Send>) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(flate2::bufread::MultiGzDecoder::new(compressed)))
+    }
+}
+
+/// `.zst` files, including several frames concatenated into one file.
+#[derive(Debug, Default)]
+pub struct ZstdDecompressor;
+
+impl LogDecompressor for ZstdDecompressor {
+    fn name(&self) -> &'static str {
+        "zstd"
+    }
+
+    fn magic(&self) -> &'static [u8] {
+        &[0x28, 0xB5, 0x2F, 0xFD]
+    }
+
+    fn decompress(&self, compressed: Box<dyn BufRead + Send>) -> io::Result<Box<dyn Read + Send>> {
+        let frame = StreamingDecoder::new(compressed).map_err(io::Error::other)?;
+        Ok(Box::new(ZstdFrames { frame: Some(frame) }))
+    }
+}
+
+/// Reads the zstd frames of a stream one after the other.
+struct ZstdFrames<R: BufRead> {
+    frame: Option<StreamingDecoder<R, FrameDecoder>>,
+}
+
+impl<R: BufRead> Read for ZstdFrames<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(frame) = self.frame.as_mut() {
+            let read = frame.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+
+            let Some((mut source, decoder)) = self.frame.take().map(StreamingDecoder::into_parts)
+            else {
+                break;
+            };
+            if !source.fill_buf()?.is_empty() {
+                let next = StreamingDecoder::new_with_decoder(source, decoder)
+                    .map_err(io::Error::other)?;
+                self.frame = Some(next);
+            }
+        }
+        Ok(0)
+    }
+}
+
+/// `.bz2` files, including several streams concatenated into one file.
+#[derive(Debug, Default)]
+pub struct Bzip2Decompressor;
+
+impl LogDecompressor for Bzip2Decompressor {
+    fn name(&self) -> &'static str {
+        "bzip2"
+    }
+
+    fn magic(&self) -> &'static [u8] {
+        b"BZh"
+    }
+
+    fn decompress(&self, compressed: Box<dyn BufRead + Send>) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(bzip2::bufread::MultiBzDecoder::new(compressed)))
+    }
+}
+
+/// Decompressed bytes the xz decoding thread hands over at a time.
+const XZ_CHUNK_BYTES: usize = 64 * 1024;
+/// Chunks decoded ahead of the reader. The decoder waits while the pipe is full, and a
+/// reader that stops early, such as the one sampling a file's first lines, ends it by
+/// being dropped: the next chunk cannot be sent and decoding stops there.
+const XZ_CHUNKS_IN_FLIGHT: usize = 4;
+
+/// `.xz` files. The decoder has no streaming reader, so it runs on a thread of its own
+/// and hands the decompressed bytes over through a bounded pipe.
+#[derive(Debug, Default)]
+pub struct XzDecompressor;
+
+impl LogDecompressor for XzDecompressor {
+    fn name(&self) -> &'static str {
+        "xz"
+    }
+
+    fn magic(&self) -> &'static [u8] {
+        &[0xFD, b'7', b'z', b'X', b'Z', 0x00]
+    }
+
+    fn decompress(
+        &self,
+        mut compressed: Box<dyn BufRead + Send>,
+    ) -> io::Result<Box<dyn Read + Send>> {
+        let (sender, receiver) = mpsc::sync_channel(XZ_CHUNKS_IN_FLIGHT);
+        thread::Builder::new()
+            .name("xz-decoder".to_string())
+            .spawn(move || {
+                let mut pipe = ChunkWriter {
+                    chunk: Vec::with_capacity(XZ_CHUNK_BYTES),
+                    sender,
+                };
+                let result = lzma_rs::xz_decompress(&mut compressed, &mut pipe)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+                    .and_then(|()| pipe.flush());
+                if let Err(err) = result {
+                    // Fails only when the reader is gone, and then nobody needs the error.
+                    let _ = pipe.sender.send(Err(err));
+                }
+            })?;
+
+        Ok(Box::new(ChunkReader {
+            chunk: Cursor::new(Vec::new()),
+            receiver,
+        }))
+    }
+}
+
+/// Writing end of the xz pipe; fails once the reading end has been dropped, which stops
+/// the decoder.
+struct ChunkWriter {
+    chunk: Vec<u8>,
+    sender: SyncSender<io::Result<Vec<u8>>>,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = buf.len().min(XZ_CHUNK_BYTES - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..written]);
+        if self.chunk.len() == XZ_CHUNK_BYTES {
+            self.flush()?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(XZ_CHUNK_BYTES));
+        self.sender
+            .send(Ok(chunk))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+/// Reading end of the xz pipe. The stream ends when the decoding thread is done.
+struct ChunkReader {
+    chunk: Cursor<Vec<u8>>,
+    receiver: Receiver<io::Result<Vec<u8>>>,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.chunk.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            match self.receiver.recv() {
+                Ok(chunk) => self.chunk = Cursor::new(chunk?),
+                Err(RecvError) => return Ok(0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const LOG_FILE_A: &str = include_str!("../../tests/log_file_A.txt");
+
+    fn read_fixture(name: &str, bytes: &[u8]) -> io::Result<String> {
+        let temp_dir = TempDir::new().expect("temp dir");
+        let path = temp_dir.path().join(name);
+        File::create(&path)?.write_all(bytes)?;
+        let mut text = String::new();
+        LogDecompressors::default()
+            .open(&path)?
+            .read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    #[test]
+    fn compressed_fixtures_decompress_to_the_plain_log() {
+        // [CSV-File-CompressedV1]
+        let fixtures: [(&str, &[u8]); 4] = [
+            ("a.log.gz", include_bytes!("../../tests/log_file_A.txt.gz")),
+            (
+                "a.log.zst",
+                include_bytes!("../../tests/log_file_A.txt.zst"),
+            ),
+            (
+                "a.log.bz2",
+                include_bytes!("../../tests/log_file_A.txt.bz2"),
+            ),
+            ("a.log.xz", include_bytes!("../../tests/log_file_A.txt.xz")),
+        ];
+
+        for (name, bytes) in fixtures {
+            assert_eq!(read_fixture(name, bytes).unwrap(), LOG_FILE_A, "{name}");
+        }
+    }
+
+    #[test]
+    fn format_is_chosen_by_magic_bytes_not_extension() {
+        // [CSV-File-CompressedV1] Archivers do not always keep the extension.
+        let gzip = include_bytes!("../../tests/log_file_A.txt.gz");
+
+        assert_eq!(read_fixture("renamed.log", gzip).unwrap(), LOG_FILE_A);
+        assert_eq!(
+            read_fixture("plain.gz", LOG_FILE_A.as_bytes()).unwrap(),
+            LOG_FILE_A
+        );
+    }
+
+    #[test]
+    fn concatenated_zstd_frames_are_all_read() {
+        // [CSV-File-CompressedV1] `cat a.zst b.zst` is a valid zstd file.
+        let zstd = include_bytes!("../../tests/log_file_A.txt.zst");
+
+        let text = read_fixture("twice.log.zst", &[&zstd[..], &zstd[..]].concat()).unwrap();
+
+        assert_eq!(text, LOG_FILE_A.repeat(2));
+    }
+
+    #[test]
+    fn xz_streams_in_chunks_and_may_be_dropped_early() {
+        // [CSV-File-CompressedV1] Sampling reads only the first lines of a large file.
+        let text: String = (0..100_000).map(|line| format!("line {line}\n")).collect();
+        let mut xz = Vec::new();
+        lzma_rs::xz_compress(&mut text.as_bytes(), &mut xz).unwrap();
+        let open = |bytes: &[u8]| XzDecompressor.decompress(Box::new(Cursor::new(bytes.to_vec())));
+
+        let mut first_line = String::new();
+        BufReader::new(open(&xz).unwrap())
+            .read_line(&mut first_line)
+            .unwrap();
+        let mut whole = String::new();
+        open(&xz).unwrap().read_to_string(&mut whole).unwrap();
+
+        assert_eq!(first_line, "line 0\n");
+        assert_eq!(whole, text);
+        let truncated = &xz[..xz.len() / 2];
+        assert!(
+            open(truncated)
+                .unwrap()
+                .read_to_end(&mut Vec::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn custom_decompressors_plug_in_and_corrupt_data_is_an_error() {
+        // [CSV-File-CompressedV1]
+        struct Enveloped;
+        impl LogDecompressor for Enveloped {
+            fn name(&self) -> &'static str {
+                "envelope"
+            }
+            fn magic(&self) -> &'static [u8] {
+                b"ENVELOPE\n"
+            }
+            fn decompress(
+                &self,
+                mut compressed: Box<dyn BufRead + Send>,
+            ) -> io::Result<Box<dyn Read + Send>> {
+                compressed.consume(self.magic().len());
+                Ok(Box::new(compressed))
+            }
+        }
+        let temp_dir = TempDir::new().expect("temp dir");
+        let path = temp_dir.path().join("enveloped.log");
+        std::fs::write(&path, "ENVELOPE\nalpha\n").unwrap();
+        let decompressors = LogDecompressors::empty().with_decompressor(Arc::new(Enveloped));
+
+        let mut text = String::new();
+        decompressors
+            .open(&path)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+
+        assert_eq!(text, "alpha\n");
+        assert!(read_fixture("broken.gz", &[0x1F, 0x8B, 0x08, 0xFF, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Number of loop iterations between cancellation checks and progress reports.
+const CHECKPOINT_INTERVAL: usize = 4_096;
+
+/// Cloneable flag shared between the requester of a diff and the engine computing it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Coarse stages of a diff computation, in execution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffPhase {
+    /// Hashing lines into the symbol table.
+    Indexing,
+    /// Linking equal lines between the two inputs.
+    Matching,
+    /// Emitting `DiffLine`s and classifying moves.
+    Assembling,
+    /// Pairing similar deleted/added lines per [CSV-Diff-IntraLineV1].
+    Refining,
+}
+
+/// Progress snapshot within a single phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffProgress {
+    phase: DiffPhase,
+    completed: usize,
+    total: usize,
+}
+
+impl DiffProgress {
+    pub fn new(phase: DiffPhase, completed: usize, total: usize) -> Self {
+        Self {
+            phase,
+            completed,
+            total,
+        }
+    }
+
+    pub fn phase(&self) -> DiffPhase {
+        self.phase
+    }
+
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+/// Returned by an engine that stopped early because its token was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffCancelled;
+
+impl fmt::Display for DiffCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "diff computation was cancelled")
+    }
+}
+
+impl Error for DiffCancelled {}
+
+/// Cancellation and progress hooks threaded through a diff per [CSV-UX-ResponsiveV1],
+/// along with the settings the user may change between two diffs.
+pub struct DiffControl<'a> {
+    cancellation: CancellationToken,
+    progress: Option<&'a dyn Fn(DiffProgress)>,
+    min_moved_block_size: Option<usize>,
+}
+
+impl<'a> DiffControl<'a> {
+    pub fn new(cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation,
+            progress: None,
+            min_moved_block_size: None,
+        }
+    }
+
+    /// A control that is never cancelled and reports nowhere.
+    pub fn none() -> Self {
+        Self::new(CancellationToken::new())
+    }
+
+    pub fn with_progress(mut self, progress: &'a dyn Fn(DiffProgress)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// [CSV-UI-MovedBlocksV1] Overrides the engine's minimum moved block size for this
+    /// diff only.
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = Some(min_moved_block_size.max(1));
+        self
+    }
+
+    /// The minimum moved block size for this diff; `None` keeps the engine's own.
+    pub fn min_moved_block_size(&self) -> Option<usize> {
+        self.min_moved_block_size
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Reports progress unconditionally and fails if the diff has been cancelled.
+    /// Engines call this at phase boundaries.
+    pub fn report(
+        &self,
+        phase: DiffPhase,
+        completed: usize,
+        total: usize,
+    ) -> Result<(), DiffCancelled> {
+        if self.is_cancelled() {
+            return Err(DiffCancelled);
+        }
+
+        if let Some(progress) = self.progress {
+            progress(DiffProgress::new(phase, completed, total));
+        }
+        Ok(())
+    }
+
+    /// Cheap per-iteration hook for engine loops: only every `CHECKPOINT_INTERVAL`
+    /// iterations does it poll the token and report progress.
+    pub fn checkpoint(
+        &self,
+        phase: DiffPhase,
+        completed: usize,
+        total: usize,
+    ) -> Result<(), DiffCancelled> {
+        if !completed.is_multiple_of(CHECKPOINT_INTERVAL) {
+            return Ok(());
+        }
+        self.report(phase, completed, total)
+    }
+}
+
+impl Default for DiffControl<'_> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn cancellation_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let control = DiffControl::new(token.clone());
+        assert!(control.report(DiffPhase::Indexing, 0, 1).is_ok());
+
+        token.cancel();
+
+        assert!(control.is_cancelled());
+        assert_eq!(
+            control.report(DiffPhase::Indexing, 0, 1),
+            Err(DiffCancelled)
+        );
+    }
+
+    #[test]
+    fn checkpoint_reports_only_on_interval_boundaries() {
+        let reports = RefCell::new(Vec::new());
+        let callback = |progress: DiffProgress| reports.borrow_mut().push(progress.completed());
+        let control = DiffControl::none().with_progress(&callback);
+
+        for completed in 0..=CHECKPOINT_INTERVAL * 2 {
+            control
+                .checkpoint(DiffPhase::Matching, completed, CHECKPOINT_INTERVAL * 2)
+                .unwrap();
+        }
+
+        assert_eq!(
+            *reports.borrow(),
+            vec![0, CHECKPOINT_INTERVAL, CHECKPOINT_INTERVAL * 2]
+        );
+    }
+}
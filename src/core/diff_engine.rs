@@ -1,14 +1,30 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
+use crate::core::diff_control::{DiffCancelled, DiffControl, DiffPhase};
+use crate::core::intra_line_diff::{self, ChangeSpan};
+use crate::core::line_store::LineText;
+use crate::core::log_source::LineOrigins;
+
+mod myers;
+mod patience;
+
+pub use myers::MyersDiffEngine;
+pub use patience::PatienceDiffEngine;
+
+/// A line as shown and the text it is compared by. Lines read from a file share its
+/// `LineStore` rather than holding copies per [CSV-Core-LineStoreV1].
 #[derive(Debug, Clone)]
 pub struct ComparableLine {
-    pub original_text: String,
-    pub comparable_text: String,
+    pub original_text: LineText,
+    pub comparable_text: LineText,
 }
 
 impl ComparableLine {
-    pub fn new(original_text: impl Into<String>, comparable_text: impl Into<String>) -> Self {
+    pub fn new(original_text: impl Into<LineText>, comparable_text: impl Into<LineText>) -> Self {
         Self {
             original_text: original_text.into(),
             comparable_text: comparable_text.into(),
@@ -36,16 +52,40 @@ pub enum DiffState {
     Deleted,
     Unchanged,
     Moved,
+    /// A deleted/added pair similar enough to be shown as one edited line per [CSV-Diff-IntraLineV1].
+    Modified,
+}
+
+/// Line matching algorithms the user can choose between per [CSV-Diff-AlgorithmChoiceV1].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffAlgorithm {
+    /// Unique-line anchoring with move detection per [CSV-Diff-HeckelV1].
+    #[default]
+    Heckel,
+    /// Minimal edit script; moves are limited to relocated unique lines.
+    Myers,
+    /// Aligns on lines unique to both sides first, which keeps repetitive logs readable.
+    Patience,
+}
+
+impl DiffAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiffAlgorithm::Heckel => "Heckel",
+            DiffAlgorithm::Myers => "Myers",
+            DiffAlgorithm::Patience => "Patience",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LineContent {
     line_number: usize,
-    text: String,
+    text: LineText,
 }
 
 impl LineContent {
-    pub fn new(line_number: usize, text: impl Into<String>) -> Self {
+    pub fn new(line_number: usize, text: impl Into<LineText>) -> Self {
         Self {
             line_number,
             text: text.into(),
@@ -58,7 +98,7 @@ impl LineContent {
     }
 
     pub fn text(&self) -> &str {
-        &self.text
+        self.text.as_str()
     }
 }
 
@@ -69,6 +109,9 @@ pub struct DiffLine {
     right: Option<LineContent>,
     moved_from: Option<usize>,
     moved_to: Option<usize>,
+    moved_block_id: Option<usize>,
+    left_changes: Vec<ChangeSpan>,
+    right_changes: Vec<ChangeSpan>,
 }
 
 impl DiffLine {
@@ -79,6 +122,9 @@ impl DiffLine {
             right,
             moved_from: None,
             moved_to: None,
+            moved_block_id: None,
+            left_changes: Vec::new(),
+            right_changes: Vec::new(),
         }
     }
 
@@ -88,6 +134,21 @@ impl DiffLine {
         self
     }
 
+    pub fn with_moved_block_id(mut self, moved_block_id: Option<usize>) -> Self {
+        self.moved_block_id = moved_block_id;
+        self
+    }
+
+    pub fn with_changes(
+        mut self,
+        left_changes: Vec<ChangeSpan>,
+        right_changes: Vec<ChangeSpan>,
+    ) -> Self {
+        self.left_changes = left_changes;
+        self.right_changes = right_changes;
+        self
+    }
+
     pub fn state(&self) -> DiffState {
         self.state.clone()
     }
@@ -107,6 +168,21 @@ impl DiffLine {
     pub fn moved_to(&self) -> Option<usize> {
         self.moved_to
     }
+
+    /// Id of the `MovedBlock` this line belongs to, if its move was reported as a block.
+    pub fn moved_block_id(&self) -> Option<usize> {
+        self.moved_block_id
+    }
+
+    /// Byte ranges of the left text that differ from the paired right text.
+    pub fn left_changes(&self) -> &[ChangeSpan] {
+        &self.left_changes
+    }
+
+    /// Byte ranges of the right text that differ from the paired left text.
+    pub fn right_changes(&self) -> &[ChangeSpan] {
+        &self.right_changes
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -115,6 +191,7 @@ pub struct DiffStatistics {
     deletions: usize,
     moves: usize,
     unchanged: usize,
+    modifications: usize,
 }
 
 impl DiffStatistics {
@@ -126,6 +203,7 @@ impl DiffStatistics {
                 DiffState::Deleted => stats.deletions += 1,
                 DiffState::Unchanged => stats.unchanged += 1,
                 DiffState::Moved => stats.moves += 1,
+                DiffState::Modified => stats.modifications += 1,
             }
         }
 
@@ -133,7 +211,7 @@ impl DiffStatistics {
     }
 
     pub fn total_changes(&self) -> usize {
-        self.additions + self.deletions + self.moves
+        self.additions + self.deletions + self.moves + self.modifications
     }
 
     pub fn additions(&self) -> usize {
@@ -151,10 +229,15 @@ impl DiffStatistics {
     pub fn unchanged(&self) -> usize {
         self.unchanged
     }
+
+    pub fn modifications(&self) -> usize {
+        self.modifications
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MovedBlock {
+    id: usize,
     source_start: usize,
     source_end: usize,
     destination_start: usize,
@@ -163,12 +246,14 @@ pub struct MovedBlock {
 
 impl MovedBlock {
     pub fn new(
+        id: usize,
         source_start: usize,
         source_end: usize,
         destination_start: usize,
         destination_end: usize,
     ) -> Self {
         Self {
+            id,
             source_start,
             source_end,
             destination_start,
@@ -176,6 +261,11 @@ impl MovedBlock {
         }
     }
 
+    /// One-based id, assigned in destination order, that `DiffLine::moved_block_id` refers to.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     pub fn source_start(&self) -> usize {
         self.source_start
     }
@@ -191,6 +281,29 @@ impl MovedBlock {
     pub fn destination_end(&self) -> usize {
         self.destination_end
     }
+
+    /// The block for display, with its lines located in the files of each side they came
+    /// from per [CSV-File-RotatedSetV1], e.g. `block #3 moved from app.log.1:120–140 to 410–430`.
+    pub fn describe(
+        &self,
+        source_origins: &LineOrigins,
+        destination_origins: &LineOrigins,
+    ) -> String {
+        format!(
+            "block #{} moved from {} to {}",
+            self.id,
+            source_origins.describe_range(self.source_start, self.source_end),
+            destination_origins.describe_range(self.destination_start, self.destination_end)
+        )
+    }
+}
+
+/// Line numbers of a single file on each side; see `MovedBlock::describe` for sets.
+impl fmt::Display for MovedBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let single_file = LineOrigins::default();
+        f.write_str(&self.describe(&single_file, &single_file))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -234,323 +347,602 @@ impl DiffResult {
     pub fn moved_blocks(&self) -> &[MovedBlock] {
         &self.moved_blocks
     }
+
+    pub fn into_parts(self) -> (Vec<DiffLine>, Vec<MovedBlock>) {
+        (self.lines, self.moved_blocks)
+    }
 }
 
-pub struct HeckelDiffEngine;
+/// Occurrences of one distinct comparable line in the old and new inputs.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SymbolEntry {
+    old_lines: Vec<usize>,
+    new_lines: Vec<usize>,
+}
 
-impl HeckelDiffEngine {
-    pub fn new() -> Self {
-        Self
+impl SymbolEntry {
+    #[cfg(test)]
+    fn counts(&self) -> (usize, usize) {
+        (self.old_lines.len(), self.new_lines.len())
     }
+}
 
-    fn build_symbol_table<'a>(
+/// Heckel's symbol table. Every input line is mapped to a dense symbol id so the linking
+/// passes compare integers and find occurrences without rescanning the inputs.
+struct SymbolTable<'a> {
+    ids: HashMap<&'a ComparableLine, usize>,
+    entries: Vec<SymbolEntry>,
+    old_symbols: Vec<usize>,
+    new_symbols: Vec<usize>,
+}
+
+impl<'a> SymbolTable<'a> {
+    fn build(
         lines_a: &'a [ComparableLine],
         lines_b: &'a [ComparableLine],
-    ) -> HashMap<&'a ComparableLine, (usize, usize)> {
-        let mut table: HashMap<&'a ComparableLine, (usize, usize)> = HashMap::new();
+        control: &DiffControl<'_>,
+    ) -> Result<Self, DiffCancelled> {
+        let total = lines_a.len() + lines_b.len();
+        let mut table = Self {
+            ids: HashMap::with_capacity(lines_a.len() + lines_b.len()),
+            entries: Vec::new(),
+            old_symbols: Vec::with_capacity(lines_a.len()),
+            new_symbols: Vec::with_capacity(lines_b.len()),
+        };
 
-        for line in lines_a {
-            let entry = table.entry(line).or_insert((0, 0));
-            entry.0 += 1;
+        for (i, line) in lines_a.iter().enumerate() {
+            control.checkpoint(DiffPhase::Indexing, i, total)?;
+            let id = table.symbol_id(line);
+            table.entries[id].old_lines.push(i);
+            table.old_symbols.push(id);
         }
 
-        for line in lines_b {
-            let entry = table.entry(line).or_insert((0, 0));
-            entry.1 += 1;
+        for (j, line) in lines_b.iter().enumerate() {
+            control.checkpoint(DiffPhase::Indexing, lines_a.len() + j, total)?;
+            let id = table.symbol_id(line);
+            table.entries[id].new_lines.push(j);
+            table.new_symbols.push(id);
         }
 
-        table
+        Ok(table)
     }
 
-    fn link_unique_anchors<'a>(
+    fn symbol_id(&mut self, line: &'a ComparableLine) -> usize {
+        let next_id = self.entries.len();
+        let id = *self.ids.entry(line).or_insert(next_id);
+        if id == next_id {
+            self.entries.push(SymbolEntry::default());
+        }
+        id
+    }
+
+    #[cfg(test)]
+    fn get(&self, line: &ComparableLine) -> Option<&SymbolEntry> {
+        self.ids.get(line).map(|&id| &self.entries[id])
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn old_len(&self) -> usize {
+        self.old_symbols.len()
+    }
+
+    fn new_len(&self) -> usize {
+        self.new_symbols.len()
+    }
+
+    fn is_same_line(&self, i: usize, j: usize) -> bool {
+        self.old_symbols[i] == self.new_symbols[j]
+    }
+}
+
+pub struct HeckelDiffEngine {
+    min_moved_block_size: usize,
+}
+
+impl HeckelDiffEngine {
+    pub fn new() -> Self {
+        Self {
+            min_moved_block_size: DEFAULT_MIN_MOVED_BLOCK_SIZE,
+        }
+    }
+
+    /// Moves of fewer contiguous lines keep their `Moved` state but are not reported as
+    /// a `MovedBlock`, which keeps the connector view free of one-line noise.
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = min_moved_block_size.max(1);
+        self
+    }
+
+    fn build_symbol_table<'a>(
         lines_a: &'a [ComparableLine],
         lines_b: &'a [ComparableLine],
-        table: &HashMap<&'a ComparableLine, (usize, usize)>,
-    ) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
-        let mut oa: Vec<Option<usize>> = vec![None; lines_a.len()];
-        let mut na: Vec<Option<usize>> = vec![None; lines_b.len()];
+        control: &DiffControl<'_>,
+    ) -> Result<SymbolTable<'a>, DiffCancelled> {
+        SymbolTable::build(lines_a, lines_b, control)
+    }
 
-        for (i, line) in lines_a.iter().enumerate() {
-            if let Some((1, 1)) = table.get(line) {
-                if let Some(j) = lines_b.iter().position(|l| l == line) {
-                    oa[i] = Some(j);
-                    na[j] = Some(i);
-                }
+    fn link_unique_anchors(table: &SymbolTable<'_>) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+        let mut oa: Vec<Option<usize>> = vec![None; table.old_len()];
+        let mut na: Vec<Option<usize>> = vec![None; table.new_len()];
+
+        for entry in &table.entries {
+            if let ([i], [j]) = (entry.old_lines.as_slice(), entry.new_lines.as_slice()) {
+                oa[*i] = Some(*j);
+                na[*j] = Some(*i);
             }
         }
 
         (oa, na)
     }
 
-    fn link_non_unique_matches<'a>(
-        lines_a: &'a [ComparableLine],
-        lines_b: &'a [ComparableLine],
-        table: &HashMap<&'a ComparableLine, (usize, usize)>,
+    /// Heckel pass 4: spreads matches forward from linked lines to equal, still unlinked
+    /// successors. The start of both files acts as a virtual anchor so identical leading
+    /// lines pair up even when they are not unique.
+    fn propagate_forward(
+        table: &SymbolTable<'_>,
+        oa: &mut [Option<usize>],
+        na: &mut [Option<usize>],
+        control: &DiffControl<'_>,
+    ) -> Result<(), DiffCancelled> {
+        if table.old_len() > 0
+            && table.new_len() > 0
+            && oa[0].is_none()
+            && na[0].is_none()
+            && table.is_same_line(0, 0)
+        {
+            oa[0] = Some(0);
+            na[0] = Some(0);
+        }
+
+        for i in 0..table.old_len().saturating_sub(1) {
+            control.checkpoint(DiffPhase::Matching, i, table.old_len())?;
+            let Some(j) = oa[i] else {
+                continue;
+            };
+            let (next_i, next_j) = (i + 1, j + 1);
+            if next_j < table.new_len()
+                && oa[next_i].is_none()
+                && na[next_j].is_none()
+                && table.is_same_line(next_i, next_j)
+            {
+                oa[next_i] = Some(next_j);
+                na[next_j] = Some(next_i);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Heckel pass 5: mirrors `propagate_forward`, spreading matches towards earlier lines
+    /// with the end of both files acting as a virtual anchor.
+    fn propagate_backward(
+        table: &SymbolTable<'_>,
+        oa: &mut [Option<usize>],
+        na: &mut [Option<usize>],
+        control: &DiffControl<'_>,
+    ) -> Result<(), DiffCancelled> {
+        if table.old_len() > 0 && table.new_len() > 0 {
+            let (i, j) = (table.old_len() - 1, table.new_len() - 1);
+            if oa[i].is_none() && na[j].is_none() && table.is_same_line(i, j) {
+                oa[i] = Some(j);
+                na[j] = Some(i);
+            }
+        }
+
+        for i in (1..table.old_len()).rev() {
+            control.checkpoint(
+                DiffPhase::Matching,
+                table.old_len() - 1 - i,
+                table.old_len(),
+            )?;
+            let Some(j) = oa[i] else {
+                continue;
+            };
+            if j == 0 {
+                continue;
+            }
+            let (prev_i, prev_j) = (i - 1, j - 1);
+            if oa[prev_i].is_none() && na[prev_j].is_none() && table.is_same_line(prev_i, prev_j) {
+                oa[prev_i] = Some(prev_j);
+                na[prev_j] = Some(prev_i);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Links each remaining old line to the first unlinked new occurrence of the same text.
+    /// A per-symbol cursor skips occurrences that are already taken, which keeps the pass
+    /// linear because links are never removed.
+    fn link_non_unique_matches(
+        table: &SymbolTable<'_>,
         oa: &mut [Option<usize>],
         na: &mut [Option<usize>],
-    ) {
-        for (i, line_a) in lines_a.iter().enumerate() {
+        control: &DiffControl<'_>,
+    ) -> Result<(), DiffCancelled> {
+        let mut cursors = vec![0usize; table.entries.len()];
+
+        for (i, &symbol) in table.old_symbols.iter().enumerate() {
+            control.checkpoint(DiffPhase::Matching, i, table.old_len())?;
             if oa[i].is_some() {
                 continue;
             }
 
-            if let Some((count_a, count_b)) = table.get(line_a) {
-                if *count_a == 0 || *count_b == 0 {
-                    continue;
-                }
+            let new_lines = &table.entries[symbol].new_lines;
+            let cursor = &mut cursors[symbol];
+            while *cursor < new_lines.len() && na[new_lines[*cursor]].is_some() {
+                *cursor += 1;
+            }
 
-                for (j, line_b) in lines_b.iter().enumerate() {
-                    if na[j].is_none() && line_a == line_b {
-                        oa[i] = Some(j);
-                        na[j] = Some(i);
-                        break;
-                    }
-                }
+            if let Some(&j) = new_lines.get(*cursor) {
+                oa[i] = Some(j);
+                na[j] = Some(i);
+                *cursor += 1;
             }
         }
+
+        Ok(())
     }
+}
 
-    fn build_diff_lines(
-        lines_a: &[ComparableLine],
-        lines_b: &[ComparableLine],
-        oa: &[Option<usize>],
-        na: &[Option<usize>],
-    ) -> (Vec<DiffLine>, Vec<MovedBlock>) {
-        let mut result_lines: Vec<DiffLine> = Vec::new();
-        let mut matched_info: Vec<(usize, usize, usize)> = Vec::new();
-
-        let mut processed_old = vec![false; lines_a.len()];
-        let mut i_ptr: usize = 0;
-
-        for j in 0..lines_b.len() {
-            let matched_old = na
-                .get(j)
-                .copied()
-                .flatten()
-                .filter(|&i| oa.get(i).copied().flatten() == Some(j) && !processed_old[i]);
-
-            if let Some(i_match) = matched_old {
-                // Emit deletions for old lines that occur before the matched index
-                while i_ptr < i_match {
-                    if processed_old[i_ptr] {
-                        i_ptr += 1;
-                        continue;
-                    }
+fn link_lines(oa: &mut [Option<usize>], na: &mut [Option<usize>], i: usize, j: usize) {
+    oa[i] = Some(j);
+    na[j] = Some(i);
+}
 
-                    match oa[i_ptr] {
-                        Some(mapped_j) if mapped_j >= j => break,
-                        Some(mapped_j) if mapped_j < j => {
-                            processed_old[i_ptr] = true;
-                            i_ptr += 1;
-                        }
-                        _ => {
-                            result_lines.push(DiffLine::new(
-                                DiffState::Deleted,
-                                Some(LineContent::new(
-                                    i_ptr + 1,
-                                    lines_a[i_ptr].original_text.clone(),
-                                )),
-                                None,
-                            ));
-                            processed_old[i_ptr] = true;
-                            i_ptr += 1;
-                        }
-                    }
-                }
+/// Links the common prefix and suffix of two symbol ranges and returns what remains.
+fn link_common_affixes(
+    a: &[usize],
+    b: &[usize],
+    a_range: Range<usize>,
+    b_range: Range<usize>,
+    oa: &mut [Option<usize>],
+    na: &mut [Option<usize>],
+) -> (Range<usize>, Range<usize>) {
+    let (mut a_lo, mut a_hi) = (a_range.start, a_range.end);
+    let (mut b_lo, mut b_hi) = (b_range.start, b_range.end);
+
+    while a_lo < a_hi && b_lo < b_hi && a[a_lo] == b[b_lo] {
+        link_lines(oa, na, a_lo, b_lo);
+        a_lo += 1;
+        b_lo += 1;
+    }
 
-                let line_index = result_lines.len();
-                result_lines.push(DiffLine::new(
-                    DiffState::Unchanged,
-                    Some(LineContent::new(
-                        i_match + 1,
-                        lines_a[i_match].original_text.clone(),
-                    )),
-                    Some(LineContent::new(j + 1, lines_b[j].original_text.clone())),
-                ));
-                matched_info.push((line_index, i_match, j));
-
-                processed_old[i_match] = true;
-                if i_ptr == i_match {
+    while a_lo < a_hi && b_lo < b_hi && a[a_hi - 1] == b[b_hi - 1] {
+        a_hi -= 1;
+        b_hi -= 1;
+        link_lines(oa, na, a_hi, b_hi);
+    }
+
+    (a_lo..a_hi, b_lo..b_hi)
+}
+
+/// Links lines that occur exactly once in each input but were left unmatched by an
+/// alignment-based engine, so relocated lines surface as moves like they do with Heckel.
+fn link_remaining_unique_lines(
+    table: &SymbolTable<'_>,
+    oa: &mut [Option<usize>],
+    na: &mut [Option<usize>],
+) {
+    for entry in &table.entries {
+        if let ([i], [j]) = (entry.old_lines.as_slice(), entry.new_lines.as_slice())
+            && oa[*i].is_none()
+            && na[*j].is_none()
+        {
+            oa[*i] = Some(*j);
+            na[*j] = Some(*i);
+        }
+    }
+}
+
+/// Row in the result, old index and new index of a line matched between both inputs.
+type MatchedLine = (usize, usize, usize);
+
+/// Smallest number of contiguous moved lines reported as a `MovedBlock` by default.
+pub const DEFAULT_MIN_MOVED_BLOCK_SIZE: usize = 1;
+
+/// Turns line links into the shared `DiffResult` shape used by every engine.
+/// A minimum moved block size set on `control` takes precedence over the engine's.
+fn assemble_diff_result(
+    lines_a: &[ComparableLine],
+    lines_b: &[ComparableLine],
+    oa: &[Option<usize>],
+    na: &[Option<usize>],
+    min_moved_block_size: usize,
+    control: &DiffControl<'_>,
+) -> Result<DiffResult, DiffCancelled> {
+    let (mut lines, matched_info) = build_diff_lines(lines_a, lines_b, oa, na, control)?;
+    let min_moved_block_size = control
+        .min_moved_block_size()
+        .unwrap_or(min_moved_block_size);
+    let moved_blocks = classify_matched_lines(&mut lines, &matched_info, min_moved_block_size);
+    let lines = intra_line_diff::pair_modified_lines(lines, lines_a, lines_b, control)?;
+
+    Ok(DiffResult::with_moved_blocks(lines, moved_blocks))
+}
+
+fn build_diff_lines(
+    lines_a: &[ComparableLine],
+    lines_b: &[ComparableLine],
+    oa: &[Option<usize>],
+    na: &[Option<usize>],
+    control: &DiffControl<'_>,
+) -> Result<(Vec<DiffLine>, Vec<MatchedLine>), DiffCancelled> {
+    let mut result_lines: Vec<DiffLine> = Vec::new();
+    let mut matched_info: Vec<MatchedLine> = Vec::new();
+
+    let mut processed_old = vec![false; lines_a.len()];
+    let mut i_ptr: usize = 0;
+
+    for j in 0..lines_b.len() {
+        control.checkpoint(DiffPhase::Assembling, j, lines_b.len())?;
+        let matched_old = na
+            .get(j)
+            .copied()
+            .flatten()
+            .filter(|&i| oa.get(i).copied().flatten() == Some(j) && !processed_old[i]);
+
+        if let Some(i_match) = matched_old {
+            // Emit deletions for old lines that occur before the matched index
+            while i_ptr < i_match {
+                if processed_old[i_ptr] {
                     i_ptr += 1;
+                    continue;
                 }
 
-                while i_ptr < lines_a.len() && processed_old[i_ptr] {
-                    i_ptr += 1;
+                match oa[i_ptr] {
+                    Some(mapped_j) if mapped_j >= j => break,
+                    Some(mapped_j) if mapped_j < j => {
+                        processed_old[i_ptr] = true;
+                        i_ptr += 1;
+                    }
+                    _ => {
+                        result_lines.push(DiffLine::new(
+                            DiffState::Deleted,
+                            Some(LineContent::new(
+                                i_ptr + 1,
+                                lines_a[i_ptr].original_text.clone(),
+                            )),
+                            None,
+                        ));
+                        processed_old[i_ptr] = true;
+                        i_ptr += 1;
+                    }
                 }
-            } else {
-                result_lines.push(DiffLine::new(
-                    DiffState::Added,
-                    None,
-                    Some(LineContent::new(j + 1, lines_b[j].original_text.clone())),
-                ));
             }
-        }
 
-        // Emit deletions for any remaining old lines
-        while i_ptr < lines_a.len() {
-            if processed_old[i_ptr] {
+            let line_index = result_lines.len();
+            result_lines.push(DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(
+                    i_match + 1,
+                    lines_a[i_match].original_text.clone(),
+                )),
+                Some(LineContent::new(j + 1, lines_b[j].original_text.clone())),
+            ));
+            matched_info.push((line_index, i_match, j));
+
+            processed_old[i_match] = true;
+            if i_ptr == i_match {
                 i_ptr += 1;
-                continue;
             }
 
+            while i_ptr < lines_a.len() && processed_old[i_ptr] {
+                i_ptr += 1;
+            }
+        } else {
             result_lines.push(DiffLine::new(
-                DiffState::Deleted,
-                Some(LineContent::new(
-                    i_ptr + 1,
-                    lines_a[i_ptr].original_text.clone(),
-                )),
+                DiffState::Added,
                 None,
+                Some(LineContent::new(j + 1, lines_b[j].original_text.clone())),
             ));
-            processed_old[i_ptr] = true;
-            i_ptr += 1;
         }
-
-        let moved_blocks = Self::classify_matched_lines(&mut result_lines, &matched_info);
-
-        (result_lines, moved_blocks)
     }
 
-    fn classify_matched_lines(
-        lines: &mut [DiffLine],
-        matched_info: &[(usize, usize, usize)],
-    ) -> Vec<MovedBlock> {
-        if matched_info.is_empty() {
-            return Vec::new();
+    // Emit deletions for any remaining old lines
+    while i_ptr < lines_a.len() {
+        if processed_old[i_ptr] {
+            i_ptr += 1;
+            continue;
         }
 
-        let sequence: Vec<usize> = matched_info
-            .iter()
-            .map(|(_, old_idx, _)| *old_idx)
-            .collect();
-        let lis_positions = Self::longest_increasing_subsequence_indices(&sequence);
+        result_lines.push(DiffLine::new(
+            DiffState::Deleted,
+            Some(LineContent::new(
+                i_ptr + 1,
+                lines_a[i_ptr].original_text.clone(),
+            )),
+            None,
+        ));
+        processed_old[i_ptr] = true;
+        i_ptr += 1;
+    }
 
-        let mut is_in_lis = vec![false; matched_info.len()];
-        for idx in lis_positions {
-            if let Some(flag) = is_in_lis.get_mut(idx) {
-                *flag = true;
-            }
-        }
+    Ok((result_lines, matched_info))
+}
 
-        let mut moved_blocks = Vec::new();
-        let mut current_block: Option<(usize, usize, usize, usize)> = None;
-
-        for (idx, (line_idx, old_idx, new_idx)) in matched_info.iter().enumerate() {
-            let line = &mut lines[*line_idx];
-            if is_in_lis[idx] {
-                line.state = DiffState::Unchanged;
-                line.moved_from = None;
-                line.moved_to = None;
-
-                if let Some((source_start, source_end, dest_start, dest_end)) = current_block.take()
-                {
-                    moved_blocks.push(MovedBlock::new(
-                        source_start,
-                        source_end,
-                        dest_start,
-                        dest_end,
-                    ));
-                }
-            } else {
-                line.state = DiffState::Moved;
-                line.moved_from = Some(old_idx + 1);
-                line.moved_to = Some(new_idx + 1);
+/// Marks matched rows outside the longest in-order chain as moved and groups them into
+/// blocks. A block only grows while both its source and destination lines stay
+/// contiguous, so unrelated moves that happen to be adjacent are reported separately.
+fn classify_matched_lines(
+    lines: &mut [DiffLine],
+    matched_info: &[MatchedLine],
+    min_moved_block_size: usize,
+) -> Vec<MovedBlock> {
+    if matched_info.is_empty() {
+        return Vec::new();
+    }
 
-                let source_line = old_idx + 1;
-                let dest_line = new_idx + 1;
+    let sequence: Vec<usize> = matched_info
+        .iter()
+        .map(|(_, old_idx, _)| *old_idx)
+        .collect();
+    let lis_positions = longest_increasing_subsequence_indices(&sequence);
 
-                match current_block {
-                    Some((source_start, _, dest_start, _)) => {
-                        current_block = Some((source_start, source_line, dest_start, dest_line));
-                    }
-                    None => {
-                        current_block = Some((source_line, source_line, dest_line, dest_line));
-                    }
-                }
-            }
+    let mut is_in_lis = vec![false; matched_info.len()];
+    for idx in lis_positions {
+        if let Some(flag) = is_in_lis.get_mut(idx) {
+            *flag = true;
         }
+    }
 
-        if let Some((source_start, source_end, dest_start, dest_end)) = current_block {
-            moved_blocks.push(MovedBlock::new(
-                source_start,
-                source_end,
-                dest_start,
-                dest_end,
-            ));
+    // Runs of contiguous moved lines, as ranges into `matched_info`.
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for (idx, (line_idx, old_idx, new_idx)) in matched_info.iter().enumerate() {
+        let line = &mut lines[*line_idx];
+        if is_in_lis[idx] {
+            line.state = DiffState::Unchanged;
+            line.moved_from = None;
+            line.moved_to = None;
+            continue;
         }
 
-        moved_blocks
+        line.state = DiffState::Moved;
+        line.moved_from = Some(old_idx + 1);
+        line.moved_to = Some(new_idx + 1);
+
+        let extends_last_run = runs.last().is_some_and(|run| {
+            let (_, prev_old, prev_new) = matched_info[run.end - 1];
+            run.end == idx && *old_idx == prev_old + 1 && *new_idx == prev_new + 1
+        });
+        match runs.last_mut() {
+            Some(run) if extends_last_run => run.end = idx + 1,
+            _ => runs.push(idx..idx + 1),
+        }
     }
 
-    fn longest_increasing_subsequence_indices(sequence: &[usize]) -> Vec<usize> {
-        if sequence.is_empty() {
-            return Vec::new();
+    let mut moved_blocks = Vec::new();
+    for run in runs
+        .into_iter()
+        .filter(|run| run.len() >= min_moved_block_size)
+    {
+        let id = moved_blocks.len() + 1;
+        let (_, source_start, dest_start) = matched_info[run.start];
+        let (_, source_end, dest_end) = matched_info[run.end - 1];
+        for (line_idx, _, _) in &matched_info[run] {
+            lines[*line_idx].moved_block_id = Some(id);
         }
+        moved_blocks.push(MovedBlock::new(
+            id,
+            source_start + 1,
+            source_end + 1,
+            dest_start + 1,
+            dest_end + 1,
+        ));
+    }
 
-        let n = sequence.len();
-        let mut tail_indices = vec![0usize; n];
-        let mut predecessors: Vec<Option<usize>> = vec![None; n];
-        let mut length = 0usize;
-
-        for (i, &value) in sequence.iter().enumerate() {
-            let mut left = 0usize;
-            let mut right = length;
-
-            while left < right {
-                let mid = (left + right) / 2;
-                if sequence[tail_indices[mid]] < value {
-                    left = mid + 1;
-                } else {
-                    right = mid;
-                }
-            }
+    moved_blocks
+}
 
-            if left > 0 {
-                predecessors[i] = Some(tail_indices[left - 1]);
-            }
+fn longest_increasing_subsequence_indices(sequence: &[usize]) -> Vec<usize> {
+    if sequence.is_empty() {
+        return Vec::new();
+    }
+
+    let n = sequence.len();
+    let mut tail_indices = vec![0usize; n];
+    let mut predecessors: Vec<Option<usize>> = vec![None; n];
+    let mut length = 0usize;
+
+    for (i, &value) in sequence.iter().enumerate() {
+        let mut left = 0usize;
+        let mut right = length;
 
-            tail_indices[left] = i;
-            if left + 1 > length {
-                length = left + 1;
+        while left < right {
+            let mid = (left + right) / 2;
+            if sequence[tail_indices[mid]] < value {
+                left = mid + 1;
+            } else {
+                right = mid;
             }
         }
 
-        if length == 0 {
-            return Vec::new();
+        if left > 0 {
+            predecessors[i] = Some(tail_indices[left - 1]);
         }
 
-        let mut lis_indices = Vec::with_capacity(length);
-        let mut k = tail_indices[length - 1];
-        loop {
-            lis_indices.push(k);
-            if let Some(prev) = predecessors[k] {
-                k = prev;
-            } else {
-                break;
-            }
+        tail_indices[left] = i;
+        if left + 1 > length {
+            length = left + 1;
+        }
+    }
+
+    if length == 0 {
+        return Vec::new();
+    }
+
+    let mut lis_indices = Vec::with_capacity(length);
+    let mut k = tail_indices[length - 1];
+    loop {
+        lis_indices.push(k);
+        if let Some(prev) = predecessors[k] {
+            k = prev;
+        } else {
+            break;
         }
-        lis_indices.reverse();
-        lis_indices
     }
+    lis_indices.reverse();
+    lis_indices
 }
 
 pub trait DiffEngineOperations: Send + Sync {
-    fn compute_diff(&self, lines_a: &[ComparableLine], lines_b: &[ComparableLine]) -> DiffResult;
-}
+    /// Computes the diff while polling `control` for cancellation and reporting progress,
+    /// so callers can abandon superseded work per [CSV-UX-ResponsiveV1].
+    fn compute_diff_with_control(
+        &self,
+        lines_a: &[ComparableLine],
+        lines_b: &[ComparableLine],
+        control: &DiffControl<'_>,
+    ) -> Result<DiffResult, DiffCancelled>;
 
-impl DiffEngineOperations for HeckelDiffEngine {
     fn compute_diff(&self, lines_a: &[ComparableLine], lines_b: &[ComparableLine]) -> DiffResult {
-        let table = Self::build_symbol_table(lines_a, lines_b);
-        let (mut oa, mut na) = Self::link_unique_anchors(lines_a, lines_b, &table);
-        Self::link_non_unique_matches(lines_a, lines_b, &table, &mut oa, &mut na);
-        let (lines, moved_blocks) = Self::build_diff_lines(lines_a, lines_b, &oa, &na);
+        self.compute_diff_with_control(lines_a, lines_b, &DiffControl::none())
+            .expect("a diff without a cancellation token cannot be cancelled")
+    }
+}
 
-        DiffResult::with_moved_blocks(lines, moved_blocks)
+impl DiffEngineOperations for HeckelDiffEngine {
+    fn compute_diff_with_control(
+        &self,
+        lines_a: &[ComparableLine],
+        lines_b: &[ComparableLine],
+        control: &DiffControl<'_>,
+    ) -> Result<DiffResult, DiffCancelled> {
+        const MATCHING_PASSES: usize = 4;
+
+        let table = Self::build_symbol_table(lines_a, lines_b, control)?;
+        control.report(DiffPhase::Matching, 0, MATCHING_PASSES)?;
+        let (mut oa, mut na) = Self::link_unique_anchors(&table);
+        // [CSV-Diff-HeckelV1] Spread matches from unique anchors before falling back to
+        // greedy linking, so repeated lines pair with their neighbours' counterparts.
+        control.report(DiffPhase::Matching, 1, MATCHING_PASSES)?;
+        Self::propagate_forward(&table, &mut oa, &mut na, control)?;
+        control.report(DiffPhase::Matching, 2, MATCHING_PASSES)?;
+        Self::propagate_backward(&table, &mut oa, &mut na, control)?;
+        control.report(DiffPhase::Matching, 3, MATCHING_PASSES)?;
+        Self::link_non_unique_matches(&table, &mut oa, &mut na, control)?;
+        control.report(DiffPhase::Matching, MATCHING_PASSES, MATCHING_PASSES)?;
+
+        assemble_diff_result(
+            lines_a,
+            lines_b,
+            &oa,
+            &na,
+            self.min_moved_block_size,
+            control,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::diff_control::{CancellationToken, DiffProgress};
+    use std::cell::RefCell;
 
     fn same(text: &str) -> ComparableLine {
         ComparableLine::new(text, text)
@@ -578,11 +970,21 @@ mod tests {
         let lines_a = vec![same("a"), same("b"), same("a")];
         let lines_b = vec![same("c"), same("b")];
 
-        let table = HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b);
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
 
-        assert_eq!(table.get(&lines_a[0]), Some(&(2, 0)));
-        assert_eq!(table.get(&lines_a[1]), Some(&(1, 1)));
-        assert_eq!(table.get(&lines_b[0]), Some(&(0, 1)));
+        assert_eq!(
+            table.get(&lines_a[0]).map(SymbolEntry::counts),
+            Some((2, 0))
+        );
+        assert_eq!(
+            table.get(&lines_a[1]).map(SymbolEntry::counts),
+            Some((1, 1))
+        );
+        assert_eq!(
+            table.get(&lines_b[0]).map(SymbolEntry::counts),
+            Some((0, 1))
+        );
         assert_eq!(table.len(), 3);
     }
 
@@ -591,8 +993,9 @@ mod tests {
         let lines_a = vec![same("alpha"), same("beta"), same("gamma")];
         let lines_b = vec![same("delta"), same("beta"), same("epsilon")];
 
-        let table = HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b);
-        let (oa, na) = HeckelDiffEngine::link_unique_anchors(&lines_a, &lines_b, &table);
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+        let (oa, na) = HeckelDiffEngine::link_unique_anchors(&table);
 
         assert_eq!(oa, vec![None, Some(1), None]);
         assert_eq!(na, vec![None, Some(1), None]);
@@ -603,9 +1006,11 @@ mod tests {
         let lines_a = vec![same("foo"), same("bar"), same("foo")];
         let lines_b = vec![same("foo"), same("foo"), same("bar")];
 
-        let table = HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b);
-        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&lines_a, &lines_b, &table);
-        HeckelDiffEngine::link_non_unique_matches(&lines_a, &lines_b, &table, &mut oa, &mut na);
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&table);
+        HeckelDiffEngine::link_non_unique_matches(&table, &mut oa, &mut na, &DiffControl::none())
+            .unwrap();
 
         assert_eq!(oa, vec![Some(0), Some(2), Some(1)]);
         assert_eq!(na, vec![Some(0), Some(2), Some(1)]);
@@ -690,4 +1095,391 @@ mod tests {
             "Expected at least one moved block"
         );
     }
+
+    #[test]
+    fn test_similar_replacement_is_reported_as_modified() {
+        // [CSV-Diff-IntraLineV1]
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![
+            same("INFO: starting"),
+            same("ERROR: Failed to process message ID 12345."),
+            same("INFO: done"),
+        ];
+        let lines_b = vec![
+            same("INFO: starting"),
+            same("ERROR: Failed to process message ID 67890."),
+            same("INFO: done"),
+        ];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.lines().len(), 3);
+        assert_eq!(result.statistics().modifications(), 1);
+        assert_eq!(result.statistics().additions(), 0);
+        assert_eq!(result.statistics().deletions(), 0);
+
+        let modified = &result.lines()[1];
+        assert_eq!(modified.state(), DiffState::Modified);
+        let left_text = modified.left().unwrap().text();
+        let span = modified.left_changes()[0];
+        assert_eq!(&left_text[span.start()..span.end()], "12345");
+    }
+
+    #[test]
+    fn test_forward_propagation_links_repeated_successors() {
+        let lines_a = vec![same("boot"), same("heartbeat"), same("heartbeat")];
+        let lines_b = vec![
+            same("heartbeat"),
+            same("boot"),
+            same("heartbeat"),
+            same("heartbeat"),
+        ];
+
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&table);
+        HeckelDiffEngine::propagate_forward(&table, &mut oa, &mut na, &DiffControl::none())
+            .unwrap();
+
+        assert_eq!(oa, vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(na, vec![None, Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_backward_propagation_links_repeated_predecessors() {
+        let lines_a = vec![same("heartbeat"), same("heartbeat"), same("shutdown")];
+        let lines_b = vec![
+            same("heartbeat"),
+            same("heartbeat"),
+            same("shutdown"),
+            same("heartbeat"),
+        ];
+
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&table);
+        HeckelDiffEngine::propagate_backward(&table, &mut oa, &mut na, &DiffControl::none())
+            .unwrap();
+
+        assert_eq!(oa, vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(na, vec![Some(0), Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn test_file_boundaries_act_as_virtual_anchors() {
+        let lines_a = vec![same("tick"), same("tick"), same("tick")];
+        let lines_b = vec![same("tick"), same("tock"), same("tick")];
+
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&table);
+        HeckelDiffEngine::propagate_forward(&table, &mut oa, &mut na, &DiffControl::none())
+            .unwrap();
+        HeckelDiffEngine::propagate_backward(&table, &mut oa, &mut na, &DiffControl::none())
+            .unwrap();
+
+        assert_eq!(oa, vec![Some(0), None, Some(2)]);
+        assert_eq!(na, vec![Some(0), None, Some(2)]);
+    }
+
+    #[test]
+    fn test_repeated_heartbeats_align_around_anchors() {
+        // [CSV-Diff-HeckelV1] Greedy first-occurrence matching would pair the first old
+        // heartbeat with the new leading one and report spurious moves.
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![
+            same("boot"),
+            same("heartbeat"),
+            same("heartbeat"),
+            same("task done"),
+            same("heartbeat"),
+        ];
+        let lines_b = vec![
+            same("heartbeat"),
+            same("boot"),
+            same("heartbeat"),
+            same("heartbeat"),
+            same("task done"),
+            same("heartbeat"),
+        ];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().moves(), 0);
+        assert_eq!(result.statistics().additions(), 1);
+        assert_eq!(result.statistics().deletions(), 0);
+        assert_eq!(result.statistics().unchanged(), 5);
+        assert!(result.moved_blocks().is_empty());
+        assert_eq!(result.lines()[0].state(), DiffState::Added);
+        assert_eq!(result.lines()[0].right().map(|c| c.line_number()), Some(1));
+    }
+
+    #[test]
+    fn test_repeated_lines_between_moved_anchors_follow_their_block() {
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![
+            same("section one"),
+            same("retry"),
+            same("retry"),
+            same("section two"),
+            same("retry"),
+        ];
+        let lines_b = vec![
+            same("section two"),
+            same("retry"),
+            same("section one"),
+            same("retry"),
+            same("retry"),
+        ];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().additions(), 0);
+        assert_eq!(result.statistics().deletions(), 0);
+        let moved_pairs: Vec<(Option<usize>, Option<usize>)> = result
+            .lines()
+            .iter()
+            .filter(|line| line.state() == DiffState::Moved)
+            .map(|line| (line.moved_from(), line.moved_to()))
+            .collect();
+        assert_eq!(moved_pairs, vec![(Some(4), Some(1)), (Some(5), Some(2))]);
+    }
+
+    #[test]
+    fn test_non_contiguous_moves_are_split_into_separate_blocks() {
+        // [CSV-UI-MovedBlocksV1] "y" and "x" land next to each other but come from
+        // unrelated places, so each gets its own block.
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![
+            same("x"),
+            same("k1"),
+            same("k2"),
+            same("y"),
+            same("k3"),
+            same("k4"),
+        ];
+        let lines_b = vec![
+            same("k1"),
+            same("k2"),
+            same("k3"),
+            same("k4"),
+            same("y"),
+            same("x"),
+        ];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(
+            result.moved_blocks(),
+            &[
+                MovedBlock::new(1, 4, 4, 5, 5),
+                MovedBlock::new(2, 1, 1, 6, 6)
+            ]
+        );
+        let block_ids: Vec<Option<usize>> = result
+            .lines()
+            .iter()
+            .filter(|line| line.state() == DiffState::Moved)
+            .map(|line| line.moved_block_id())
+            .collect();
+        assert_eq!(block_ids, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_contiguous_moved_lines_share_one_block() {
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![same("a"), same("b"), same("c"), same("d"), same("e")];
+        let lines_b = vec![same("d"), same("e"), same("a"), same("b"), same("c")];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.moved_blocks(), &[MovedBlock::new(1, 4, 5, 1, 2)]);
+        assert!(
+            result
+                .lines()
+                .iter()
+                .filter(|line| line.state() == DiffState::Moved)
+                .all(|line| line.moved_block_id() == Some(1))
+        );
+    }
+
+    #[test]
+    fn test_min_moved_block_size_drops_small_blocks_but_keeps_moved_lines() {
+        let engine = HeckelDiffEngine::new().with_min_moved_block_size(2);
+        let lines_a = vec![same("x"), same("k1"), same("k2"), same("k3")];
+        let lines_b = vec![same("k1"), same("k2"), same("k3"), same("x")];
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert!(result.moved_blocks().is_empty());
+        assert_eq!(result.statistics().moves(), 1);
+        let moved = result
+            .lines()
+            .iter()
+            .find(|line| line.state() == DiffState::Moved)
+            .expect("x is still reported as moved");
+        assert_eq!(moved.moved_block_id(), None);
+    }
+
+    #[test]
+    fn test_min_moved_block_size_of_the_control_overrides_the_engine() {
+        // [CSV-UI-MovedBlocksV1] The presenter passes the user's setting with each diff.
+        let engine = HeckelDiffEngine::new().with_min_moved_block_size(2);
+        let lines_a = vec![same("x"), same("k1"), same("k2"), same("k3")];
+        let lines_b = vec![same("k1"), same("k2"), same("k3"), same("x")];
+
+        let control = DiffControl::none().with_min_moved_block_size(1);
+        let result = engine
+            .compute_diff_with_control(&lines_a, &lines_b, &control)
+            .unwrap();
+
+        assert_eq!(result.moved_blocks().len(), 1);
+    }
+
+    #[test]
+    fn test_moved_block_describes_itself() {
+        let block = MovedBlock::new(3, 120, 140, 410, 430);
+
+        assert_eq!(block.to_string(), "block #3 moved from 120–140 to 410–430");
+    }
+
+    #[test]
+    fn test_moved_block_locates_its_lines_in_rotated_files() {
+        // [CSV-File-RotatedSetV1]
+        let block = MovedBlock::new(3, 120, 140, 410, 430);
+        let mut left = LineOrigins::default();
+        left.push_file(std::path::PathBuf::from("logs/app.log.1"), 200);
+        left.push_file(std::path::PathBuf::from("logs/app.log"), 300);
+
+        assert_eq!(
+            block.describe(&left, &LineOrigins::default()),
+            "block #3 moved from app.log.1:120–140 to 410–430"
+        );
+    }
+
+    #[test]
+    fn test_symbol_table_records_occurrences() {
+        let lines_a = vec![same("a"), same("b"), same("a")];
+        let lines_b = vec![same("a"), same("c")];
+
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines_a, &lines_b, &DiffControl::none()).unwrap();
+
+        let entry = table.get(&lines_a[0]).expect("symbol for 'a'");
+        assert_eq!(entry.old_lines, vec![0, 2]);
+        assert_eq!(entry.new_lines, vec![0]);
+        assert!(table.is_same_line(2, 0));
+        assert!(!table.is_same_line(1, 1));
+    }
+
+    #[test]
+    fn test_large_repetitive_input_stays_tractable() {
+        // [CSV-Core-LargeFileV1] Scanning all new lines per old line made inputs like this
+        // quadratic; occurrence lists keep them near-linear.
+        let engine = HeckelDiffEngine::new();
+        let mut lines_a: Vec<ComparableLine> = Vec::new();
+        let mut lines_b: Vec<ComparableLine> = vec![same("extra heartbeat")];
+        for index in 0..20_000 {
+            let line = if index % 100 == 0 {
+                format!("checkpoint {index}")
+            } else {
+                "heartbeat".to_string()
+            };
+            lines_a.push(same(&line));
+            lines_b.push(same(&line));
+        }
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().additions(), 1);
+        assert_eq!(result.statistics().deletions(), 0);
+        assert_eq!(result.statistics().moves(), 0);
+        assert_eq!(result.statistics().unchanged(), 20_000);
+    }
+
+    #[test]
+    fn test_cancelled_diff_stops_early() {
+        // [CSV-UX-ResponsiveV1]
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![same("a"), same("b")];
+        let lines_b = vec![same("b"), same("a")];
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let result = engine.compute_diff_with_control(&lines_a, &lines_b, &DiffControl::new(token));
+
+        assert_eq!(result, Err(DiffCancelled));
+    }
+
+    #[test]
+    fn test_progress_is_reported_for_each_phase_in_order() {
+        let engine = HeckelDiffEngine::new();
+        let lines_a = vec![same("a"), same("b"), same("c")];
+        let lines_b = vec![same("a"), same("c"), same("d")];
+        let phases = RefCell::new(Vec::new());
+        let record = |progress: DiffProgress| {
+            let mut phases = phases.borrow_mut();
+            if phases.last() != Some(&progress.phase()) {
+                phases.push(progress.phase());
+            }
+        };
+        let control = DiffControl::none().with_progress(&record);
+
+        let result = engine
+            .compute_diff_with_control(&lines_a, &lines_b, &control)
+            .expect("diff should not be cancelled");
+
+        assert_eq!(result.statistics().unchanged(), 2);
+        assert_eq!(
+            *phases.borrow(),
+            vec![
+                DiffPhase::Indexing,
+                DiffPhase::Matching,
+                DiffPhase::Assembling,
+                DiffPhase::Refining
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_passes_stop_when_cancelled() {
+        // [CSV-UX-ResponsiveV1] Each pass polls the token inside its loop, not only after it.
+        let lines: Vec<ComparableLine> = (0..3).map(|_| same("tick")).collect();
+        let table =
+            HeckelDiffEngine::build_symbol_table(&lines, &lines, &DiffControl::none()).unwrap();
+        let (mut oa, mut na) = HeckelDiffEngine::link_unique_anchors(&table);
+        let token = CancellationToken::new();
+        token.cancel();
+        let control = DiffControl::new(token);
+
+        assert_eq!(
+            HeckelDiffEngine::propagate_forward(&table, &mut oa, &mut na, &control),
+            Err(DiffCancelled)
+        );
+        assert_eq!(
+            HeckelDiffEngine::propagate_backward(&table, &mut oa, &mut na, &control),
+            Err(DiffCancelled)
+        );
+        assert_eq!(
+            HeckelDiffEngine::link_non_unique_matches(&table, &mut oa, &mut na, &control),
+            Err(DiffCancelled)
+        );
+    }
+
+    #[test]
+    fn test_cancellation_during_a_phase_aborts_the_diff() {
+        let engine = HeckelDiffEngine::new();
+        let lines: Vec<ComparableLine> = (0..10_000).map(|i| same(&format!("line {i}"))).collect();
+        let token = CancellationToken::new();
+        let cancel_when_matching = |progress: DiffProgress| {
+            if progress.phase() == DiffPhase::Matching {
+                token.cancel();
+            }
+        };
+        let control = DiffControl::new(token.clone()).with_progress(&cancel_when_matching);
+
+        let result = engine.compute_diff_with_control(&lines, &lines, &control);
+
+        assert_eq!(result, Err(DiffCancelled));
+    }
 }
@@ -0,0 +1,287 @@
+use std::ops::Range;
+
+use super::{
+    ComparableLine, DEFAULT_MIN_MOVED_BLOCK_SIZE, DiffEngineOperations, DiffResult, SymbolTable,
+    assemble_diff_result, link_common_affixes, link_remaining_unique_lines,
+};
+use crate::core::diff_control::{DiffCancelled, DiffControl, DiffPhase};
+
+/// Minimal edit-script engine based on Myers' O(ND) algorithm per [CSV-Diff-AlgorithmChoiceV1].
+/// Uses the linear-space bisection variant so memory stays proportional to the input.
+pub struct MyersDiffEngine {
+    min_moved_block_size: usize,
+}
+
+impl Default for MyersDiffEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MyersDiffEngine {
+    pub fn new() -> Self {
+        Self {
+            min_moved_block_size: DEFAULT_MIN_MOVED_BLOCK_SIZE,
+        }
+    }
+
+    /// See `HeckelDiffEngine::with_min_moved_block_size`.
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = min_moved_block_size.max(1);
+        self
+    }
+}
+
+impl DiffEngineOperations for MyersDiffEngine {
+    fn compute_diff_with_control(
+        &self,
+        lines_a: &[ComparableLine],
+        lines_b: &[ComparableLine],
+        control: &DiffControl<'_>,
+    ) -> Result<DiffResult, DiffCancelled> {
+        let table = SymbolTable::build(lines_a, lines_b, control)?;
+        control.report(DiffPhase::Matching, 0, 1)?;
+        let mut oa: Vec<Option<usize>> = vec![None; table.old_len()];
+        let mut na: Vec<Option<usize>> = vec![None; table.new_len()];
+
+        align(
+            &table.old_symbols,
+            &table.new_symbols,
+            0..table.old_len(),
+            0..table.new_len(),
+            &mut oa,
+            &mut na,
+            control,
+        )?;
+        link_remaining_unique_lines(&table, &mut oa, &mut na);
+        control.report(DiffPhase::Matching, 1, 1)?;
+
+        assemble_diff_result(
+            lines_a,
+            lines_b,
+            &oa,
+            &na,
+            self.min_moved_block_size,
+            control,
+        )
+    }
+}
+
+/// Links a longest common subsequence of `a[a_range]` and `b[b_range]`.
+pub(super) fn align(
+    a: &[usize],
+    b: &[usize],
+    a_range: Range<usize>,
+    b_range: Range<usize>,
+    oa: &mut [Option<usize>],
+    na: &mut [Option<usize>],
+    control: &DiffControl<'_>,
+) -> Result<(), DiffCancelled> {
+    // An explicit work list keeps deep recursion off the stack for very large inputs.
+    let mut pending = vec![(a_range, b_range)];
+
+    while let Some((a_range, b_range)) = pending.pop() {
+        if control.is_cancelled() {
+            return Err(DiffCancelled);
+        }
+
+        let (a_range, b_range) = link_common_affixes(a, b, a_range, b_range, oa, na);
+        if a_range.is_empty() || b_range.is_empty() {
+            continue;
+        }
+
+        let Some((x, y)) = bisect(&a[a_range.clone()], &b[b_range.clone()], control)? else {
+            continue;
+        };
+        if (x == 0 && y == 0) || (x == a_range.len() && y == b_range.len()) {
+            debug_assert!(false, "bisect must split the ranges");
+            continue;
+        }
+
+        let a_mid = a_range.start + x;
+        let b_mid = b_range.start + y;
+        pending.push((a_mid..a_range.end, b_mid..b_range.end));
+        pending.push((a_range.start..a_mid, b_range.start..b_mid));
+    }
+
+    Ok(())
+}
+
+/// Finds the middle of the shortest edit script by running the forward and reverse
+/// searches until they overlap. Returns `None` when the inputs share no lines at all.
+fn bisect(
+    a: &[usize],
+    b: &[usize],
+    control: &DiffControl<'_>,
+) -> Result<Option<(usize, usize)>, DiffCancelled> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max_d = (n + m + 1) / 2;
+    let v_offset = max_d;
+    let v_length = (2 * max_d + 2) as usize;
+    let mut v1 = vec![-1isize; v_length];
+    let mut v2 = vec![-1isize; v_length];
+    v1[(v_offset + 1) as usize] = 0;
+    v2[(v_offset + 1) as usize] = 0;
+
+    let delta = n - m;
+    // With an odd delta the forward search detects the overlap, otherwise the reverse one.
+    let front = delta % 2 != 0;
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0isize, 0isize, 0isize, 0isize);
+
+    for d in 0..max_d {
+        // Each round costs O(d), so polling the token once per round is negligible.
+        if control.is_cancelled() {
+            return Err(DiffCancelled);
+        }
+
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let k1_offset = (v_offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && v1[k1_offset - 1] < v1[k1_offset + 1]) {
+                v1[k1_offset + 1]
+            } else {
+                v1[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            v1[k1_offset] = x1;
+
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if front {
+                let k2_offset = v_offset + delta - k1;
+                if (0..v_length as isize).contains(&k2_offset) && v2[k2_offset as usize] != -1 {
+                    let x2 = n - v2[k2_offset as usize];
+                    if x1 >= x2 {
+                        return Ok(Some((x1 as usize, y1 as usize)));
+                    }
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let k2_offset = (v_offset + k2) as usize;
+            let mut x2 = if k2 == -d || (k2 != d && v2[k2_offset - 1] < v2[k2_offset + 1]) {
+                v2[k2_offset + 1]
+            } else {
+                v2[k2_offset - 1] + 1
+            };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                x2 += 1;
+                y2 += 1;
+            }
+            v2[k2_offset] = x2;
+
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !front {
+                let k1_offset = v_offset + delta - k2;
+                if (0..v_length as isize).contains(&k1_offset) && v1[k1_offset as usize] != -1 {
+                    let x1 = v1[k1_offset as usize];
+                    let y1 = v_offset + x1 - k1_offset;
+                    if x1 >= n - x2 {
+                        return Ok(Some((x1 as usize, y1 as usize)));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::diff_engine::DiffState;
+
+    fn same(text: &str) -> ComparableLine {
+        ComparableLine::new(text, text)
+    }
+
+    fn lines(texts: &[&str]) -> Vec<ComparableLine> {
+        texts.iter().map(|text| same(text)).collect()
+    }
+
+    #[test]
+    fn test_align_links_longest_common_subsequence() {
+        let a = [0, 1, 2, 0, 1, 1, 0];
+        let b = [2, 1, 0, 1, 0, 2];
+        let mut oa = vec![None; a.len()];
+        let mut na = vec![None; b.len()];
+
+        align(
+            &a,
+            &b,
+            0..a.len(),
+            0..b.len(),
+            &mut oa,
+            &mut na,
+            &DiffControl::none(),
+        )
+        .unwrap();
+
+        let links: Vec<(usize, usize)> = oa
+            .iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.map(|j| (i, j)))
+            .collect();
+        assert_eq!(links.len(), 4, "LCS of the classic example has length 4");
+        assert!(links.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert!(links.iter().all(|&(i, j)| a[i] == b[j]));
+    }
+
+    #[test]
+    fn test_myers_reports_additions_and_deletions() {
+        // [CSV-Diff-AlgorithmChoiceV1]
+        let engine = MyersDiffEngine::new();
+        let lines_a = lines(&["start", "removed step", "middle", "end"]);
+        let lines_b = lines(&["start", "middle", "inserted step", "end"]);
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().unchanged(), 3);
+        assert_eq!(result.statistics().deletions(), 1);
+        assert_eq!(result.statistics().additions(), 1);
+        assert_eq!(result.lines()[1].state(), DiffState::Deleted);
+        assert_eq!(result.lines()[3].state(), DiffState::Added);
+    }
+
+    #[test]
+    fn test_myers_reports_relocated_unique_lines_as_moves() {
+        let engine = MyersDiffEngine::new();
+        let lines_a = lines(&["a", "b", "c"]);
+        let lines_b = lines(&["c", "a", "b"]);
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().additions(), 0);
+        assert_eq!(result.statistics().deletions(), 0);
+        assert_eq!(result.statistics().moves(), 1);
+        assert_eq!(result.moved_blocks().len(), 1);
+    }
+
+    #[test]
+    fn test_myers_handles_empty_and_disjoint_inputs() {
+        let engine = MyersDiffEngine::new();
+
+        let result = engine.compute_diff(&[], &lines(&["x"]));
+        assert_eq!(result.statistics().additions(), 1);
+
+        let result = engine.compute_diff(&lines(&["x", "x"]), &lines(&["y", "y"]));
+        assert_eq!(result.statistics().additions(), 2);
+        assert_eq!(result.statistics().deletions(), 2);
+    }
+}
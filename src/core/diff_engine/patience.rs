@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::{
+    ComparableLine, DEFAULT_MIN_MOVED_BLOCK_SIZE, DiffEngineOperations, DiffResult, SymbolTable,
+    assemble_diff_result, link_common_affixes, link_lines, link_remaining_unique_lines,
+    longest_increasing_subsequence_indices, myers,
+};
+use crate::core::diff_control::{DiffCancelled, DiffControl, DiffPhase};
+
+/// Patience diff engine per [CSV-Diff-AlgorithmChoiceV1]. Lines that are unique on both
+/// sides anchor the alignment, and the gaps between anchors are aligned recursively, so
+/// frequent lines such as heartbeats never pull unrelated regions together.
+pub struct PatienceDiffEngine {
+    min_moved_block_size: usize,
+}
+
+impl Default for PatienceDiffEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatienceDiffEngine {
+    pub fn new() -> Self {
+        Self {
+            min_moved_block_size: DEFAULT_MIN_MOVED_BLOCK_SIZE,
+        }
+    }
+
+    /// See `HeckelDiffEngine::with_min_moved_block_size`.
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = min_moved_block_size.max(1);
+        self
+    }
+}
+
+impl DiffEngineOperations for PatienceDiffEngine {
+    fn compute_diff_with_control(
+        &self,
+        lines_a: &[ComparableLine],
+        lines_b: &[ComparableLine],
+        control: &DiffControl<'_>,
+    ) -> Result<DiffResult, DiffCancelled> {
+        let table = SymbolTable::build(lines_a, lines_b, control)?;
+        control.report(DiffPhase::Matching, 0, 1)?;
+        let mut oa: Vec<Option<usize>> = vec![None; table.old_len()];
+        let mut na: Vec<Option<usize>> = vec![None; table.new_len()];
+
+        align(
+            &table.old_symbols,
+            &table.new_symbols,
+            0..table.old_len(),
+            0..table.new_len(),
+            &mut oa,
+            &mut na,
+            control,
+        )?;
+        link_remaining_unique_lines(&table, &mut oa, &mut na);
+        control.report(DiffPhase::Matching, 1, 1)?;
+
+        assemble_diff_result(
+            lines_a,
+            lines_b,
+            &oa,
+            &na,
+            self.min_moved_block_size,
+            control,
+        )
+    }
+}
+
+fn align(
+    a: &[usize],
+    b: &[usize],
+    a_range: Range<usize>,
+    b_range: Range<usize>,
+    oa: &mut [Option<usize>],
+    na: &mut [Option<usize>],
+    control: &DiffControl<'_>,
+) -> Result<(), DiffCancelled> {
+    let mut pending = vec![(a_range, b_range)];
+
+    while let Some((a_range, b_range)) = pending.pop() {
+        if control.is_cancelled() {
+            return Err(DiffCancelled);
+        }
+
+        let (a_range, b_range) = link_common_affixes(a, b, a_range, b_range, oa, na);
+        if a_range.is_empty() || b_range.is_empty() {
+            continue;
+        }
+
+        let anchors = unique_common_anchors(a, b, a_range.clone(), b_range.clone());
+        if anchors.is_empty() {
+            // No line is unique on both sides; fall back to a minimal alignment.
+            myers::align(a, b, a_range, b_range, oa, na, control)?;
+            continue;
+        }
+
+        let (mut a_lo, mut b_lo) = (a_range.start, b_range.start);
+        for (i, j) in anchors {
+            link_lines(oa, na, i, j);
+            pending.push((a_lo..i, b_lo..j));
+            a_lo = i + 1;
+            b_lo = j + 1;
+        }
+        pending.push((a_lo..a_range.end, b_lo..b_range.end));
+    }
+
+    Ok(())
+}
+
+/// Returns the longest in-order chain of lines that occur exactly once in both ranges.
+fn unique_common_anchors(
+    a: &[usize],
+    b: &[usize],
+    a_range: Range<usize>,
+    b_range: Range<usize>,
+) -> Vec<(usize, usize)> {
+    // symbol -> (count in a, last index in a, count in b, last index in b)
+    let mut occurrences: HashMap<usize, (usize, usize, usize, usize)> = HashMap::new();
+    for i in a_range {
+        let entry = occurrences.entry(a[i]).or_insert((0, 0, 0, 0));
+        entry.0 += 1;
+        entry.1 = i;
+    }
+    for j in b_range {
+        if let Some(entry) = occurrences.get_mut(&b[j]) {
+            entry.2 += 1;
+            entry.3 = j;
+        }
+    }
+
+    let mut candidates: Vec<(usize, usize)> = occurrences
+        .into_values()
+        .filter(|&(count_a, _, count_b, _)| count_a == 1 && count_b == 1)
+        .map(|(_, i, _, j)| (i, j))
+        .collect();
+    candidates.sort_unstable();
+
+    let sequence: Vec<usize> = candidates.iter().map(|&(_, j)| j).collect();
+    longest_increasing_subsequence_indices(&sequence)
+        .into_iter()
+        .map(|idx| candidates[idx])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::diff_engine::DiffState;
+
+    fn same(text: &str) -> ComparableLine {
+        ComparableLine::new(text, text)
+    }
+
+    fn lines(texts: &[&str]) -> Vec<ComparableLine> {
+        texts.iter().map(|text| same(text)).collect()
+    }
+
+    #[test]
+    fn test_unique_common_anchors_follow_longest_chain() {
+        let a = [0, 1, 2, 3, 3];
+        let b = [2, 0, 1, 3];
+
+        let anchors = unique_common_anchors(&a, &b, 0..a.len(), 0..b.len());
+
+        assert_eq!(anchors, vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_patience_keeps_repeated_braces_with_their_blocks() {
+        // [CSV-Diff-AlgorithmChoiceV1]
+        let engine = PatienceDiffEngine::new();
+        let lines_a = lines(&["{", "alpha", "}", "{", "beta", "}"]);
+        let lines_b = lines(&["{", "alpha", "}", "{", "gamma", "}", "{", "beta", "}"]);
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().unchanged(), 6);
+        assert_eq!(result.statistics().additions(), 3);
+        assert_eq!(result.statistics().deletions(), 0);
+        assert_eq!(result.statistics().moves(), 0);
+        let alpha = result
+            .lines()
+            .iter()
+            .find(|line| line.left().map(|c| c.text()) == Some("alpha"))
+            .expect("alpha line");
+        assert_eq!(alpha.state(), DiffState::Unchanged);
+    }
+
+    #[test]
+    fn test_patience_reports_relocated_unique_lines_as_moves() {
+        let engine = PatienceDiffEngine::new();
+        let lines_a = lines(&["a", "b", "c"]);
+        let lines_b = lines(&["c", "a", "b"]);
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().additions(), 0);
+        assert_eq!(result.statistics().deletions(), 0);
+        assert_eq!(result.statistics().moves(), 1);
+        assert_eq!(result.moved_blocks().len(), 1);
+    }
+
+    #[test]
+    fn test_patience_falls_back_to_myers_without_unique_lines() {
+        let engine = PatienceDiffEngine::new();
+        let lines_a = lines(&["tick", "tock", "tick", "tock"]);
+        let lines_b = lines(&["tock", "tick", "tock", "tick"]);
+
+        let result = engine.compute_diff(&lines_a, &lines_b);
+
+        assert_eq!(result.statistics().unchanged(), 3);
+        assert_eq!(result.statistics().additions(), 1);
+        assert_eq!(result.statistics().deletions(), 1);
+    }
+}
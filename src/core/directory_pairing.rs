@@ -0,0 +1,234 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A file of the left run directory and the file of the right one it is compared with,
+/// per [CSV-File-DirectoryCompareV1]. A file without a partner has only one side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePair {
+    key: String,
+    left: Option<PathBuf>,
+    right: Option<PathBuf>,
+}
+
+impl FilePair {
+    /// Relative path, with `/` separators, that paired the files.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn left(&self) -> Option<&Path> {
+        self.left.as_deref()
+    }
+
+    pub fn right(&self) -> Option<&Path> {
+        self.right.as_deref()
+    }
+
+    /// Both files, if the pair has both sides.
+    pub fn files(&self) -> Option<(&Path, &Path)> {
+        self.left().zip(self.right())
+    }
+}
+
+/// How the files of a pair compare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairOutcome {
+    /// The pair was diffed; `total_changes` as in `DiffStatistics::total_changes`.
+    Compared { total_changes: usize },
+    /// Only one directory has the file.
+    Unpaired,
+    /// Reading or diffing the files failed, with the reason.
+    Failed(String),
+}
+
+/// One line of the directory comparison summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairSummary {
+    pair: FilePair,
+    outcome: PairOutcome,
+}
+
+impl PairSummary {
+    pub fn new(pair: FilePair, outcome: PairOutcome) -> Self {
+        Self { pair, outcome }
+    }
+
+    pub fn pair(&self) -> &FilePair {
+        &self.pair
+    }
+
+    pub fn outcome(&self) -> &PairOutcome {
+        &self.outcome
+    }
+}
+
+/// Every file below `directory`, with its path relative to `directory`, in path order.
+pub fn list_directory_files(directory: &Path) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![directory.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                let path = entry.path();
+                files.push((relative_key(directory, &path), path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn relative_key(directory: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(directory).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Pairs the files of two directories, as listed by `list_directory_files`, by relative
+/// path once every match of `name_pattern` is removed from it, per
+/// [CSV-File-DirectoryCompareV1]. A pattern like `-\d{8}-\d{6}` pairs `db-20240101-120000.log`
+/// with `db-20240102-083000.log`. When several files of one side end up with the same key,
+/// the first pairs and the others are listed alone. The pairs are ordered by key.
+pub fn pair_files(
+    left_files: Vec<(String, PathBuf)>,
+    right_files: Vec<(String, PathBuf)>,
+    name_pattern: Option<&Regex>,
+) -> Vec<FilePair> {
+    let pairing_key = |relative: &str| match name_pattern {
+        Some(pattern) => pattern.replace_all(relative, "").into_owned(),
+        None => relative.to_string(),
+    };
+
+    let mut pairs: BTreeMap<String, FilePair> = BTreeMap::new();
+    let mut leftovers = Vec::new();
+    for (relative, path) in left_files {
+        match pairs.entry(pairing_key(&relative)) {
+            Entry::Vacant(entry) => {
+                let key = entry.key().clone();
+                entry.insert(FilePair {
+                    key,
+                    left: Some(path),
+                    right: None,
+                });
+            }
+            Entry::Occupied(_) => leftovers.push(FilePair {
+                key: relative,
+                left: Some(path),
+                right: None,
+            }),
+        }
+    }
+    for (relative, path) in right_files {
+        let key = pairing_key(&relative);
+        let pair = pairs.entry(key.clone()).or_insert_with(|| FilePair {
+            key,
+            left: None,
+            right: None,
+        });
+        if pair.right.is_none() {
+            pair.right = Some(path);
+        } else {
+            leftovers.push(FilePair {
+                key: relative,
+                left: None,
+                right: Some(path),
+            });
+        }
+    }
+
+    let mut pairs: Vec<FilePair> = pairs.into_values().chain(leftovers).collect();
+    pairs.sort_by(|a, b| a.key.cmp(&b.key));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn files(names: &[&str]) -> Vec<(String, PathBuf)> {
+        names
+            .iter()
+            .map(|name| (name.to_string(), PathBuf::from(name)))
+            .collect()
+    }
+
+    #[test]
+    fn files_pair_by_relative_path_below_both_directories() {
+        // [CSV-File-DirectoryCompareV1]
+        let left_dir = TempDir::new().expect("temp dir");
+        let right_dir = TempDir::new().expect("temp dir");
+        for dir in [left_dir.path(), right_dir.path()] {
+            std::fs::create_dir(dir.join("db")).unwrap();
+            std::fs::write(dir.join("db").join("server.log"), "").unwrap();
+            std::fs::write(dir.join("api.log"), "").unwrap();
+        }
+        std::fs::write(left_dir.path().join("cache.log"), "").unwrap();
+
+        let pairs = pair_files(
+            list_directory_files(left_dir.path()).unwrap(),
+            list_directory_files(right_dir.path()).unwrap(),
+            None,
+        );
+
+        let keys: Vec<&str> = pairs.iter().map(FilePair::key).collect();
+        assert_eq!(keys, ["api.log", "cache.log", "db/server.log"]);
+        assert_eq!(
+            pairs[2].files(),
+            Some((
+                left_dir.path().join("db").join("server.log").as_path(),
+                right_dir.path().join("db").join("server.log").as_path()
+            ))
+        );
+        assert_eq!(pairs[1].right(), None);
+    }
+
+    #[test]
+    fn name_pattern_pairs_files_whose_names_differ_by_run() {
+        // [CSV-File-DirectoryCompareV1]
+        let pattern = Regex::new(r"-\d{8}").unwrap();
+
+        let pairs = pair_files(
+            files(&["db-20240101.log", "api-20240101.log"]),
+            files(&["db-20240102.log", "api-20240102.log"]),
+            Some(&pattern),
+        );
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[1].key(), "db.log");
+        assert_eq!(
+            pairs[1].files(),
+            Some((Path::new("db-20240101.log"), Path::new("db-20240102.log")))
+        );
+    }
+
+    #[test]
+    fn files_sharing_a_key_with_an_earlier_one_are_listed_alone() {
+        // [CSV-File-DirectoryCompareV1]
+        let pattern = Regex::new(r"\.\d+").unwrap();
+
+        let pairs = pair_files(
+            files(&["app.1.log", "app.2.log"]),
+            files(&["app.7.log"]),
+            Some(&pattern),
+        );
+
+        let keys: Vec<&str> = pairs.iter().map(FilePair::key).collect();
+        assert_eq!(keys, ["app.2.log", "app.log"]);
+        assert_eq!(pairs[0].right(), None);
+        assert_eq!(
+            pairs[1].files(),
+            Some((Path::new("app.1.log"), Path::new("app.7.log")))
+        );
+    }
+}
@@ -0,0 +1,555 @@
+use crate::core::diff_control::{DiffCancelled, DiffControl, DiffPhase};
+use crate::core::diff_engine::{ComparableLine, DiffLine, DiffState};
+
+/// Minimum token similarity for a deleted/added pair to be shown as one modified line.
+const MIN_PAIRING_SIMILARITY: f64 = 0.5;
+/// Upper bound on LCS table cells so pathological lines cannot stall the diff.
+const MAX_TOKEN_CELLS: usize = 250_000;
+/// Number of following additions a deletion is compared against, which keeps long runs of
+/// unmatched lines linear instead of comparing every deletion with every addition.
+const MAX_PAIRING_CANDIDATES: usize = 16;
+
+/// Byte range within a line's original text that differs from its paired line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeSpan {
+    start: usize,
+    end: usize,
+}
+
+impl ChangeSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        debug_assert!(start <= end);
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl Token<'_> {
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    fn is_whitespace(&self) -> bool {
+        self.text.chars().all(char::is_whitespace)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenClass {
+    Word,
+    Whitespace,
+    Punctuation,
+}
+
+fn classify_char(ch: char) -> TokenClass {
+    if ch.is_alphanumeric() || ch == '_' {
+        TokenClass::Word
+    } else if ch.is_whitespace() {
+        TokenClass::Whitespace
+    } else {
+        TokenClass::Punctuation
+    }
+}
+
+/// Splits a line into word, whitespace and single punctuation tokens.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(usize, TokenClass)> = None;
+
+    for (idx, ch) in text.char_indices() {
+        let class = classify_char(ch);
+        match current {
+            Some((_, current_class))
+                if current_class == class && class != TokenClass::Punctuation => {}
+            Some((start, _)) => {
+                tokens.push(Token {
+                    text: &text[start..idx],
+                    start,
+                });
+                current = Some((idx, class));
+            }
+            None => current = Some((idx, class)),
+        }
+    }
+
+    if let Some((start, _)) = current {
+        tokens.push(Token {
+            text: &text[start..],
+            start,
+        });
+    }
+
+    tokens
+}
+
+/// Marks which tokens on each side take part in a longest common subsequence.
+/// Returns `None` when the inputs are too large to align token by token.
+fn common_token_flags(
+    tokens_a: &[Token<'_>],
+    tokens_b: &[Token<'_>],
+) -> Option<(Vec<bool>, Vec<bool>)> {
+    let n = tokens_a.len();
+    let m = tokens_b.len();
+    if n.saturating_mul(m) > MAX_TOKEN_CELLS {
+        return None;
+    }
+
+    let width = m + 1;
+    let mut table = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if tokens_a[i].text == tokens_b[j].text {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut flags_a = vec![false; n];
+    let mut flags_b = vec![false; m];
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < n && j < m {
+        if tokens_a[i].text == tokens_b[j].text {
+            flags_a[i] = true;
+            flags_b[j] = true;
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    Some((flags_a, flags_b))
+}
+
+/// Dice coefficient over the non-whitespace tokens of two lines, in `0.0..=1.0`.
+pub fn line_similarity(text_a: &str, text_b: &str) -> f64 {
+    let tokens_a: Vec<Token<'_>> = tokenize(text_a)
+        .into_iter()
+        .filter(|token| !token.is_whitespace())
+        .collect();
+    let tokens_b: Vec<Token<'_>> = tokenize(text_b)
+        .into_iter()
+        .filter(|token| !token.is_whitespace())
+        .collect();
+
+    let total = tokens_a.len() + tokens_b.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let Some((flags_a, _)) = common_token_flags(&tokens_a, &tokens_b) else {
+        return 0.0;
+    };
+    let common = flags_a.iter().filter(|&&flag| flag).count();
+
+    (2 * common) as f64 / total as f64
+}
+
+/// Collects unmatched tokens into spans, bridging gaps that consist only of whitespace.
+fn collect_spans(text: &str, tokens: &[Token<'_>], flags: &[bool]) -> Vec<ChangeSpan> {
+    let mut spans: Vec<ChangeSpan> = Vec::new();
+    for (token, &is_common) in tokens.iter().zip(flags) {
+        if is_common {
+            continue;
+        }
+
+        match spans.last_mut() {
+            Some(last) if text[last.end..token.start].trim().is_empty() => last.end = token.end(),
+            _ => spans.push(ChangeSpan::new(token.start, token.end())),
+        }
+    }
+    spans
+}
+
+/// Computes the token-level spans that differ between two lines.
+pub fn changed_spans(text_a: &str, text_b: &str) -> (Vec<ChangeSpan>, Vec<ChangeSpan>) {
+    let tokens_a = tokenize(text_a);
+    let tokens_b = tokenize(text_b);
+
+    match common_token_flags(&tokens_a, &tokens_b) {
+        Some((flags_a, flags_b)) => (
+            collect_spans(text_a, &tokens_a, &flags_a),
+            collect_spans(text_b, &tokens_b, &flags_b),
+        ),
+        None => (
+            vec![ChangeSpan::new(0, text_a.len())],
+            vec![ChangeSpan::new(0, text_b.len())],
+        ),
+    }
+}
+
+fn comparable_text(lines: &[ComparableLine], line_number: usize) -> &str {
+    lines
+        .get(line_number.wrapping_sub(1))
+        .map(|line| line.comparable_text.as_str())
+        .unwrap_or_default()
+}
+
+/// Pairs similar deleted/added lines inside each run of unmatched lines into
+/// `DiffState::Modified` entries carrying token-level change spans per [CSV-Diff-IntraLineV1].
+///
+/// Similarity is judged on the comparable text so ignored timestamps do not prevent
+/// pairing, while the change spans index into the original text shown to the user.
+pub fn pair_modified_lines(
+    lines: Vec<DiffLine>,
+    lines_a: &[ComparableLine],
+    lines_b: &[ComparableLine],
+    control: &DiffControl<'_>,
+) -> Result<Vec<DiffLine>, DiffCancelled> {
+    let total = lines.len();
+    let mut result = Vec::with_capacity(total);
+    let mut run: Vec<DiffLine> = Vec::new();
+
+    for (index, line) in lines.into_iter().enumerate() {
+        control.checkpoint(DiffPhase::Refining, index, total)?;
+        match line.state() {
+            DiffState::Added | DiffState::Deleted => run.push(line),
+            _ => {
+                flush_run(&mut run, &mut result, lines_a, lines_b, control)?;
+                result.push(line);
+            }
+        }
+    }
+    flush_run(&mut run, &mut result, lines_a, lines_b, control)?;
+
+    Ok(result)
+}
+
+fn flush_run(
+    run: &mut Vec<DiffLine>,
+    output: &mut Vec<DiffLine>,
+    lines_a: &[ComparableLine],
+    lines_b: &[ComparableLine],
+    control: &DiffControl<'_>,
+) -> Result<(), DiffCancelled> {
+    let run_lines = std::mem::take(run);
+    let pairs = find_similar_pairs(&run_lines, lines_a, lines_b, control)?;
+    if pairs.is_empty() {
+        // Nothing to pair; keep the engine's original ordering untouched.
+        output.extend(run_lines);
+        return Ok(());
+    }
+
+    let (deleted, added): (Vec<DiffLine>, Vec<DiffLine>) = run_lines
+        .into_iter()
+        .partition(|line| line.state() == DiffState::Deleted);
+    let mut deleted_iter = deleted.into_iter().enumerate().peekable();
+    let mut added_iter = added.into_iter().enumerate().peekable();
+
+    let pair_count = pairs.len();
+    for (pair_index, (d_idx, a_idx)) in pairs.into_iter().enumerate() {
+        control.checkpoint(DiffPhase::Refining, pair_index, pair_count)?;
+        while let Some((_, line)) = deleted_iter.next_if(|(idx, _)| *idx < d_idx) {
+            output.push(line);
+        }
+        while let Some((_, line)) = added_iter.next_if(|(idx, _)| *idx < a_idx) {
+            output.push(line);
+        }
+
+        let (_, deleted_line) = deleted_iter.next().expect("paired deletion exists");
+        let (_, added_line) = added_iter.next().expect("paired addition exists");
+        let left = deleted_line.left().cloned();
+        let right = added_line.right().cloned();
+
+        let (left_changes, right_changes) = match (&left, &right) {
+            (Some(left), Some(right)) => changed_spans(left.text(), right.text()),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        output.push(
+            DiffLine::new(DiffState::Modified, left, right)
+                .with_changes(left_changes, right_changes),
+        );
+    }
+
+    output.extend(deleted_iter.map(|(_, line)| line));
+    output.extend(added_iter.map(|(_, line)| line));
+    Ok(())
+}
+
+/// Greedily pairs deletions with the most similar later addition so both sides stay
+/// monotonic. Returned indices refer to the run's deletions and additions respectively.
+/// A long run costs many similarity comparisons, so they are counted against the
+/// control's checkpoint cadence as well.
+fn find_similar_pairs(
+    run: &[DiffLine],
+    lines_a: &[ComparableLine],
+    lines_b: &[ComparableLine],
+    control: &DiffControl<'_>,
+) -> Result<Vec<(usize, usize)>, DiffCancelled> {
+    let deleted_texts: Vec<&str> = run
+        .iter()
+        .filter(|line| line.state() == DiffState::Deleted)
+        .map(|line| {
+            line.left()
+                .map(|content| comparable_text(lines_a, content.line_number()))
+                .unwrap_or_default()
+        })
+        .collect();
+    let added_texts: Vec<&str> = run
+        .iter()
+        .filter(|line| line.state() == DiffState::Added)
+        .map(|line| {
+            line.right()
+                .map(|content| comparable_text(lines_b, content.line_number()))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let total_comparisons = deleted_texts.len() * added_texts.len().min(MAX_PAIRING_CANDIDATES);
+    let mut comparisons = 0usize;
+    let mut pairs = Vec::new();
+    let mut next_added = 0usize;
+    for (d_idx, deleted_text) in deleted_texts.iter().enumerate() {
+        let mut best: Option<(usize, f64)> = None;
+        for (a_idx, added_text) in added_texts
+            .iter()
+            .enumerate()
+            .skip(next_added)
+            .take(MAX_PAIRING_CANDIDATES)
+        {
+            control.checkpoint(DiffPhase::Refining, comparisons, total_comparisons)?;
+            comparisons += 1;
+
+            let score = line_similarity(deleted_text, added_text);
+            // The first of equally similar candidates wins.
+            if score >= MIN_PAIRING_SIMILARITY
+                && best.is_none_or(|(_, best_score)| score > best_score)
+            {
+                best = Some((a_idx, score));
+            }
+        }
+
+        if let Some((a_idx, _)) = best {
+            pairs.push((d_idx, a_idx));
+            next_added = a_idx + 1;
+        }
+    }
+
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::diff_control::{CancellationToken, DiffProgress};
+    use crate::core::diff_engine::LineContent;
+
+    fn same(text: &str) -> ComparableLine {
+        ComparableLine::new(text, text)
+    }
+
+    fn slices<'a>(text: &'a str, spans: &[ChangeSpan]) -> Vec<&'a str> {
+        spans
+            .iter()
+            .map(|span| &text[span.start()..span.end()])
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_splits_words_whitespace_and_punctuation() {
+        let tokens: Vec<&str> = tokenize("id=42, ok")
+            .iter()
+            .map(|token| token.text)
+            .collect();
+
+        assert_eq!(tokens, vec!["id", "=", "42", ",", " ", "ok"]);
+    }
+
+    #[test]
+    fn changed_spans_highlight_only_the_differing_number() {
+        let left = "Failed to process message ID 12345.";
+        let right = "Failed to process message ID 12346.";
+
+        let (left_spans, right_spans) = changed_spans(left, right);
+
+        assert_eq!(slices(left, &left_spans), vec!["12345"]);
+        assert_eq!(slices(right, &right_spans), vec!["12346"]);
+    }
+
+    #[test]
+    fn changed_spans_merge_adjacent_tokens() {
+        let left = "cache size 1024MB";
+        let right = "cache limit is 2048MB";
+
+        let (left_spans, right_spans) = changed_spans(left, right);
+
+        assert_eq!(slices(left, &left_spans), vec!["size 1024MB"]);
+        assert_eq!(slices(right, &right_spans), vec!["limit is 2048MB"]);
+    }
+
+    #[test]
+    fn similarity_ignores_whitespace_tokens() {
+        assert_eq!(line_similarity("a  b", "a b"), 1.0);
+        assert_eq!(line_similarity("alpha", "omega"), 0.0);
+    }
+
+    #[test]
+    fn similar_deleted_and_added_lines_become_modified() {
+        // [CSV-Diff-IntraLineV1]
+        let lines_a = vec![same("start"), same("connect to db attempt 1"), same("end")];
+        let lines_b = vec![same("start"), same("connect to db attempt 2"), same("end")];
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(1, "start")),
+                Some(LineContent::new(1, "start")),
+            ),
+            DiffLine::new(
+                DiffState::Added,
+                None,
+                Some(LineContent::new(2, "connect to db attempt 2")),
+            ),
+            DiffLine::new(
+                DiffState::Deleted,
+                Some(LineContent::new(2, "connect to db attempt 1")),
+                None,
+            ),
+            DiffLine::new(
+                DiffState::Unchanged,
+                Some(LineContent::new(3, "end")),
+                Some(LineContent::new(3, "end")),
+            ),
+        ];
+
+        let paired = pair_modified_lines(lines, &lines_a, &lines_b, &DiffControl::none()).unwrap();
+
+        assert_eq!(paired.len(), 3);
+        let modified = &paired[1];
+        assert_eq!(modified.state(), DiffState::Modified);
+        assert_eq!(modified.left().map(|c| c.line_number()), Some(2));
+        assert_eq!(modified.right().map(|c| c.line_number()), Some(2));
+        assert_eq!(
+            slices(modified.left().unwrap().text(), modified.left_changes()),
+            vec!["1"]
+        );
+        assert_eq!(
+            slices(modified.right().unwrap().text(), modified.right_changes()),
+            vec!["2"]
+        );
+    }
+
+    #[test]
+    fn dissimilar_lines_keep_original_order() {
+        let lines_a = vec![same("alpha beta")];
+        let lines_b = vec![same("gamma delta")];
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Added,
+                None,
+                Some(LineContent::new(1, "gamma delta")),
+            ),
+            DiffLine::new(
+                DiffState::Deleted,
+                Some(LineContent::new(1, "alpha beta")),
+                None,
+            ),
+        ];
+
+        let paired =
+            pair_modified_lines(lines.clone(), &lines_a, &lines_b, &DiffControl::none()).unwrap();
+
+        assert_eq!(paired, lines);
+    }
+
+    #[test]
+    fn pairing_checks_cancellation_while_comparing_one_long_run() {
+        // [CSV-UX-ResponsiveV1] 300 deletions against 300 additions stay below one
+        // checkpoint interval of lines but cost 4800 similarity comparisons.
+        let count = 300;
+        let lines_a: Vec<ComparableLine> = (0..count).map(|i| same(&format!("old {i}"))).collect();
+        let lines_b: Vec<ComparableLine> = (0..count)
+            .map(|i| same(&format!("new {}", i + count)))
+            .collect();
+        let mut lines: Vec<DiffLine> = (0..count)
+            .map(|i| {
+                DiffLine::new(
+                    DiffState::Deleted,
+                    Some(LineContent::new(i + 1, lines_a[i].original_text.clone())),
+                    None,
+                )
+            })
+            .collect();
+        lines.extend((0..count).map(|i| {
+            DiffLine::new(
+                DiffState::Added,
+                None,
+                Some(LineContent::new(i + 1, lines_b[i].original_text.clone())),
+            )
+        }));
+        let token = CancellationToken::new();
+        let cancel_once_comparing = |progress: DiffProgress| {
+            if progress.total() == count * MAX_PAIRING_CANDIDATES {
+                token.cancel();
+            }
+        };
+        let control = DiffControl::new(token.clone()).with_progress(&cancel_once_comparing);
+
+        let result = pair_modified_lines(lines, &lines_a, &lines_b, &control);
+
+        assert_eq!(result, Err(DiffCancelled));
+    }
+
+    #[test]
+    fn pairing_uses_comparable_text_and_keeps_unpaired_lines() {
+        let lines_a = vec![
+            ComparableLine::new("[T1] worker 7 started", "worker 7 started"),
+            ComparableLine::new("[T2] obsolete", "obsolete"),
+        ];
+        let lines_b = vec![ComparableLine::new(
+            "[T9] worker 8 started",
+            "worker 8 started",
+        )];
+        let lines = vec![
+            DiffLine::new(
+                DiffState::Added,
+                None,
+                Some(LineContent::new(1, "[T9] worker 8 started")),
+            ),
+            DiffLine::new(
+                DiffState::Deleted,
+                Some(LineContent::new(1, "[T1] worker 7 started")),
+                None,
+            ),
+            DiffLine::new(
+                DiffState::Deleted,
+                Some(LineContent::new(2, "[T2] obsolete")),
+                None,
+            ),
+        ];
+
+        let paired = pair_modified_lines(lines, &lines_a, &lines_b, &DiffControl::none()).unwrap();
+
+        let states: Vec<DiffState> = paired.iter().map(DiffLine::state).collect();
+        assert_eq!(states, vec![DiffState::Modified, DiffState::Deleted]);
+        assert_eq!(
+            slices(paired[0].right().unwrap().text(), paired[0].right_changes()),
+            vec!["T9", "8"]
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use regex::{NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+use crate::core::line_store::LineText;
+
+/// What a rule does with the text its pattern matches.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum NormalizationAction {
+    /// Deletes the match.
+    Remove,
+    /// Replaces the match with a fixed marker such as `<UUID>`.
+    Placeholder(String),
+    /// Replaces every digit in the match with `#`, keeping its shape.
+    MaskDigits,
+}
+
+/// One named step of the normalization pipeline per [CSV-Core-NormalizationV1].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NormalizationRule {
+    name: String,
+    pattern: String,
+    action: NormalizationAction,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl NormalizationRule {
+    pub fn new(
+        name: impl Into<String>,
+        pattern: impl Into<String>,
+        action: NormalizationAction,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            action,
+            enabled: true,
+        }
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn action(&self) -> &NormalizationAction {
+        &self.action
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Parses one line of the rule editor: `name; action; pattern`, where the action is
+    /// `remove`, `mask-digits` or `placeholder:TEXT`. A leading `#` disables the rule.
+    ///
+    /// Fields are trimmed and end at the next `;`, except the pattern, which runs to the end
+    /// of the line. A name, placeholder text or pattern in double quotes is taken as it is,
+    /// with `""` for a quote, so it can hold `;` and leading or trailing spaces.
+    pub fn parse_line(line: &str, line_number: usize) -> Result<Self, NormalizationError> {
+        let syntax_error = |message| NormalizationError::invalid_syntax(line_number, message);
+        let (enabled, body) = match line.trim_start().strip_prefix('#') {
+            Some(rest) => (false, rest),
+            None => (true, line),
+        };
+
+        let (name, rest) = next_field(body, false).map_err(syntax_error)?;
+        if name.is_empty() {
+            return Err(syntax_error("the rule needs a name"));
+        }
+
+        let rest = rest.trim_start();
+        let (action, rest) = match rest.strip_prefix("placeholder:") {
+            Some(text) => {
+                let (text, rest) = next_field(text, false).map_err(syntax_error)?;
+                (NormalizationAction::Placeholder(text.into_owned()), rest)
+            }
+            None => {
+                let (keyword, rest) = next_field(rest, false).map_err(syntax_error)?;
+                let action = match keyword.as_ref() {
+                    "remove" => NormalizationAction::Remove,
+                    "mask-digits" => NormalizationAction::MaskDigits,
+                    other => {
+                        return Err(NormalizationError::invalid_syntax(
+                            line_number,
+                            format!(
+                                "unknown action '{other}', use remove, mask-digits or placeholder:TEXT"
+                            ),
+                        ));
+                    }
+                };
+                (action, rest)
+            }
+        };
+
+        let (pattern, _) = next_field(rest, true).map_err(syntax_error)?;
+
+        Ok(Self::new(name, pattern, action).with_enabled(enabled))
+    }
+}
+
+/// Splits the next field off a rule line and returns it with the text after its `;`.
+/// The last field of a line takes the rest of it, `;` included.
+fn next_field(text: &str, is_last: bool) -> Result<(Cow<'_, str>, &str), &'static str> {
+    let text = text.trim_start();
+    let Some(quoted) = text.strip_prefix('"') else {
+        if is_last {
+            return Ok((Cow::Borrowed(text.trim()), ""));
+        }
+        return match text.split_once(';') {
+            Some((field, rest)) => Ok((Cow::Borrowed(field.trim()), rest)),
+            None => Err("expected 'name; action; pattern'"),
+        };
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        if ch != '"' {
+            value.push(ch);
+            continue;
+        }
+        let after = &quoted[index + 1..];
+        if after.starts_with('"') {
+            value.push('"');
+            chars.next();
+            continue;
+        }
+
+        let after = after.trim_start();
+        return match after.strip_prefix(';') {
+            Some(rest) if !is_last => Ok((Cow::Owned(value), rest)),
+            None if is_last && after.is_empty() => Ok((Cow::Owned(value), "")),
+            None if !is_last => Err("expected 'name; action; pattern'"),
+            _ => Err("unexpected text after a quoted field"),
+        };
+    }
+
+    Err("a quoted field is missing its closing quote")
+}
+
+/// Quotes a field that would not read back as it is: one holding `;` before the last
+/// field, one with surrounding spaces, and one starting with a quote or, as the
+/// name does, with the `#` that disables a rule.
+fn quote_field(field: &str, is_last: bool) -> Cow<'_, str> {
+    let needs_quotes =
+        (!is_last && field.contains(';')) || field.trim() != field || field.starts_with(['"', '#']);
+    if needs_quotes {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+impl fmt::Display for NormalizationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match &self.action {
+            NormalizationAction::Remove => Cow::Borrowed("remove"),
+            NormalizationAction::MaskDigits => Cow::Borrowed("mask-digits"),
+            NormalizationAction::Placeholder(text) => {
+                Cow::Owned(format!("placeholder:{}", quote_field(text, false)))
+            }
+        };
+        let disabled = if self.enabled { "" } else { "#" };
+        write!(
+            f,
+            "{disabled}{}; {action}; {}",
+            quote_field(&self.name, false),
+            quote_field(&self.pattern, true)
+        )
+    }
+}
+
+/// Parses the rule editor text, one rule per non-blank line.
+pub fn parse_rules(text: &str) -> Result<Vec<NormalizationRule>, NormalizationError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| NormalizationRule::parse_line(line, index + 1))
+        .collect()
+}
+
+/// Formats rules for the rule editor; the inverse of `parse_rules`.
+pub fn format_rules(rules: &[NormalizationRule]) -> String {
+    rules
+        .iter()
+        .map(NormalizationRule::to_string)
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Rules for the volatile fields most logs contain, offered from the Diff menu.
+pub fn common_normalization_rules() -> Vec<NormalizationRule> {
+    vec![
+        NormalizationRule::new(
+            "uuid",
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+            NormalizationAction::Placeholder("<UUID>".to_string()),
+        ),
+        NormalizationRule::new(
+            "hex pointer",
+            r"\b0x[0-9a-fA-F]+\b",
+            NormalizationAction::Placeholder("<PTR>".to_string()),
+        ),
+        NormalizationRule::new(
+            "pid",
+            r"(?i)\bpid[=: ]\s*\d+",
+            NormalizationAction::MaskDigits,
+        ),
+        NormalizationRule::new(
+            "thread id",
+            r"(?i)\b(?:tid|thread)[=: #]\s*\d+",
+            NormalizationAction::MaskDigits,
+        ),
+        NormalizationRule::new(
+            "request id",
+            r"(?i)\brequest[_-]?id[=: ]\s*\S+",
+            NormalizationAction::Placeholder("request_id=<ID>".to_string()),
+        ),
+        NormalizationRule::new(
+            "duration",
+            r"\b\d+(?:\.\d+)?\s?(?:ns|us|µs|ms|s)\b",
+            NormalizationAction::Placeholder("<DURATION>".to_string()),
+        ),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizationError {
+    InvalidSyntax {
+        line_number: usize,
+        message: String,
+    },
+    InvalidPattern {
+        name: String,
+        pattern: String,
+        message: String,
+    },
+}
+
+impl NormalizationError {
+    pub fn invalid_syntax(line_number: usize, message: impl Into<String>) -> Self {
+        Self::InvalidSyntax {
+            line_number,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for NormalizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NormalizationError::InvalidSyntax {
+                line_number,
+                message,
+            } => write!(f, "normalization rule on line {line_number}: {message}"),
+            NormalizationError::InvalidPattern {
+                name,
+                pattern,
+                message,
+            } => write!(
+                f,
+                "normalization rule '{name}' has an invalid pattern '{pattern}': {message}"
+            ),
+        }
+    }
+}
+
+impl Error for NormalizationError {}
+
+struct CompiledRule {
+    regex: Regex,
+    action: NormalizationAction,
+}
+
+/// Applies the enabled rules in order to produce the text that lines are compared by,
+/// per [CSV-Core-NormalizationV1].
+#[derive(Default)]
+pub struct LineNormalizer {
+    rules: Vec<CompiledRule>,
+}
+
+impl LineNormalizer {
+    pub fn new(rules: &[NormalizationRule]) -> Result<Self, NormalizationError> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.is_enabled())
+            .map(|rule| {
+                let regex =
+                    Regex::new(rule.pattern()).map_err(|e| NormalizationError::InvalidPattern {
+                        name: rule.name().to_string(),
+                        pattern: rule.pattern().to_string(),
+                        message: e.to_string(),
+                    })?;
+                Ok(CompiledRule {
+                    regex,
+                    action: rule.action().clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn normalize<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(line);
+        for rule in &self.rules {
+            let replaced = match &rule.action {
+                NormalizationAction::Remove => rule.regex.replace_all(&text, NoExpand("")),
+                NormalizationAction::Placeholder(marker) => {
+                    rule.regex.replace_all(&text, NoExpand(marker))
+                }
+                NormalizationAction::MaskDigits => {
+                    rule.regex.replace_all(&text, |caps: &regex::Captures<'_>| {
+                        caps[0]
+                            .chars()
+                            .map(|c| if c.is_ascii_digit() { '#' } else { c })
+                            .collect::<String>()
+                    })
+                }
+            };
+            if let Cow::Owned(updated) = replaced {
+                text = Cow::Owned(updated);
+            }
+        }
+        text
+    }
+
+    pub fn normalize_lines(&self, lines: Vec<LineText>) -> Vec<LineText> {
+        if self.is_empty() {
+            return lines;
+        }
+
+        lines
+            .into_iter()
+            .map(|line| match self.normalize(&line) {
+                Cow::Borrowed(_) => line,
+                Cow::Owned(normalized) => LineText::from(normalized),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_apply_in_order_with_each_action() {
+        // [CSV-Core-NormalizationV1]
+        let normalizer = LineNormalizer::new(&[
+            NormalizationRule::new(
+                "uuid",
+                r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}",
+                NormalizationAction::Placeholder("<UUID>".to_string()),
+            ),
+            NormalizationRule::new("pid", r"pid=\d+", NormalizationAction::MaskDigits),
+            NormalizationRule::new("duration", r" took \d+ms", NormalizationAction::Remove),
+        ])
+        .unwrap();
+
+        let normalized = normalizer
+            .normalize("pid=4711 job 123e4567-e89b-12d3-a456-426614174000 done took 35ms");
+
+        assert_eq!(normalized, "pid=#### job <UUID> done");
+    }
+
+    #[test]
+    fn later_rules_see_the_output_of_earlier_ones() {
+        let normalizer = LineNormalizer::new(&[
+            NormalizationRule::new(
+                "ptr",
+                r"0x[0-9a-f]+",
+                NormalizationAction::Placeholder("$PTR".to_string()),
+            ),
+            NormalizationRule::new("digits", r"\d", NormalizationAction::MaskDigits),
+        ])
+        .unwrap();
+
+        assert_eq!(normalizer.normalize("at 0x7ffe01 row 42"), "at $PTR row ##");
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped_and_unmatched_lines_are_borrowed() {
+        let normalizer = LineNormalizer::new(&[NormalizationRule::new(
+            "digits",
+            r"\d",
+            NormalizationAction::Remove,
+        )
+        .with_enabled(false)])
+        .unwrap();
+
+        assert!(normalizer.is_empty());
+        assert!(matches!(normalizer.normalize("line 1"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn invalid_pattern_error_names_the_rule() {
+        let result = LineNormalizer::new(&[NormalizationRule::new(
+            "broken",
+            "(",
+            NormalizationAction::Remove,
+        )]);
+
+        match result {
+            Err(NormalizationError::InvalidPattern { name, pattern, .. }) => {
+                assert_eq!(name, "broken");
+                assert_eq!(pattern, "(");
+            }
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn rule_text_round_trips_through_the_editor_format() {
+        let rules = vec![
+            NormalizationRule::new("pid", r"pid=\d+", NormalizationAction::MaskDigits),
+            NormalizationRule::new(
+                "uuid",
+                "[0-9a-f-]{36}",
+                NormalizationAction::Placeholder("<UUID>".to_string()),
+            )
+            .with_enabled(false),
+            NormalizationRule::new("semicolons", "a;b", NormalizationAction::Remove),
+        ];
+
+        let text = format_rules(&rules);
+
+        assert_eq!(
+            text,
+            "pid; mask-digits; pid=\\d+\r\n#uuid; placeholder:<UUID>; [0-9a-f-]{36}\r\nsemicolons; remove; a;b"
+        );
+        assert_eq!(parse_rules(&text).unwrap(), rules);
+    }
+
+    #[test]
+    fn quoted_fields_keep_separators_spaces_and_quotes() {
+        // [CSV-UI-NormalizationRulesV1]
+        let rules = vec![
+            NormalizationRule::new(
+                "request; id",
+                r"id=(\S+)",
+                NormalizationAction::Placeholder(" <ID> ".to_string()),
+            ),
+            NormalizationRule::new(
+                "#hash",
+                " padded ",
+                NormalizationAction::Placeholder("a;b".to_string()),
+            )
+            .with_enabled(false),
+            NormalizationRule::new("quotes", r#""quoted""#, NormalizationAction::Remove),
+        ];
+
+        let text = format_rules(&rules);
+
+        assert_eq!(
+            text,
+            concat!(
+                "\"request; id\"; placeholder:\" <ID> \"; id=(\\S+)\r\n",
+                "#\"#hash\"; placeholder:\"a;b\"; \" padded \"\r\n",
+                "quotes; remove; \"\"\"quoted\"\"\"",
+            )
+        );
+        assert_eq!(parse_rules(&text).unwrap(), rules);
+        assert_eq!(
+            NormalizationRule::parse_line("x; placeholder:; \"\"", 1).unwrap(),
+            NormalizationRule::new("x", "", NormalizationAction::Placeholder(String::new()))
+        );
+        assert!(parse_rules("\"open; remove; x").is_err());
+        assert!(parse_rules("\"a\" b; remove; x").is_err());
+    }
+
+    #[test]
+    fn malformed_rule_lines_report_their_line_number() {
+        let error = parse_rules("pid; mask-digits; \\d+\n\nbad line").unwrap_err();
+        assert!(matches!(
+            error,
+            NormalizationError::InvalidSyntax { line_number: 3, .. }
+        ));
+
+        let error = parse_rules("x; shout; y").unwrap_err();
+        assert!(error.to_string().contains("unknown action 'shout'"));
+    }
+
+    #[test]
+    fn common_rules_compile() {
+        let normalizer = LineNormalizer::new(&common_normalization_rules()).unwrap();
+
+        assert_eq!(
+            normalizer.normalize("tid=12 handled request_id=ab12 in 3.5ms at 0xdeadbeef"),
+            "tid=## handled request_id=<ID> in <DURATION> at <PTR>"
+        );
+    }
+}
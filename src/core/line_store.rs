@@ -0,0 +1,308 @@
+use memmap2::{Mmap, MmapOptions};
+use std::fmt;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+/// Text of one file, split into lines the way `str::lines` does, per [CSV-Core-LineStoreV1].
+/// Only the start offset of every line is kept next to the text, so a line costs eight
+/// bytes on top of its own.
+pub struct LineStore {
+    text: StoreText,
+    /// Start of every line, followed by the end of the text; one entry more than lines.
+    offsets: Vec<usize>,
+}
+
+enum StoreText {
+    /// Decoded text held in memory.
+    Owned(String),
+    /// A UTF-8 file mapped into memory; the OS pages it in and out as lines are read.
+    Mapped(Mmap),
+}
+
+impl LineStore {
+    pub fn from_text(text: String) -> Self {
+        let offsets = line_offsets(text.as_bytes());
+        Self {
+            text: StoreText::Owned(text),
+            offsets,
+        }
+    }
+
+    /// Maps the first `len` bytes of `file` into memory when they are UTF-8 text as they
+    /// are; otherwise returns `None` and the caller decodes the file instead.
+    ///
+    /// The mapping is a snapshot of the file as long as it was when measured, so a log
+    /// still being written can be mapped: lines appended later are not seen, and a
+    /// character cut off by a write in progress ends the last line early. Bytes rewritten
+    /// in place are checked again when their line is read. Truncating the file in place
+    /// while it is mapped, as `copytruncate` rotation does, still makes reading the lost
+    /// lines fail with a bus error on Unix; Windows refuses such a truncation instead.
+    pub fn map(file: &File, len: u64) -> io::Result<Option<Self>> {
+        let len = usize::try_from(len).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "the file is too large to map")
+        })?;
+        // SAFETY: the map is read-only and only ever read as bytes; see above for the
+        // file changing underneath it.
+        let map = unsafe { MmapOptions::new().len(len).map(file)? };
+        match std::str::from_utf8(&map) {
+            // `error_len` is `None` for a character cut off at the end of the snapshot.
+            Err(err) if err.error_len().is_some() => return Ok(None),
+            _ => {}
+        }
+        let offsets = line_offsets(&map);
+        Ok(Some(Self {
+            text: StoreText::Mapped(map),
+            offsets,
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the lines are read from a memory-mapped file.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.text, StoreText::Mapped(_))
+    }
+
+    /// Zero-based line `index`, without its line ending.
+    pub fn line(&self, index: usize) -> &str {
+        let Range { start, end } = self.offsets[index]..self.offsets[index + 1];
+        let line = match &self.text {
+            StoreText::Owned(text) => &text[start..end],
+            StoreText::Mapped(map) => mapped_line(&map[start..end]),
+        };
+        match line.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => line,
+        }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.len()).map(|index| self.line(index))
+    }
+}
+
+/// A line of a mapped file, checked again on every read since the file may have changed
+/// since it was mapped; a line that is no longer UTF-8 ends where its valid text does.
+fn mapped_line(bytes: &[u8]) -> &str {
+    std::str::from_utf8(bytes)
+        .unwrap_or_else(|err| std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default())
+}
+
+impl fmt::Debug for LineStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineStore")
+            .field("lines", &self.len())
+            .field("is_mapped", &self.is_mapped())
+            .finish()
+    }
+}
+
+fn line_offsets(bytes: &[u8]) -> Vec<usize> {
+    let mut offsets = vec![0];
+    offsets.extend(
+        bytes
+            .iter()
+            .enumerate()
+            .filter(|&(_, &byte)| byte == b'\n')
+            .map(|(position, _)| position + 1),
+    );
+    // A last line without a line ending still counts; a trailing newline adds no line.
+    if offsets.last() != Some(&bytes.len()) {
+        offsets.push(bytes.len());
+    }
+    offsets
+}
+
+/// Text of one line, per [CSV-Core-LineStoreV1]: a line of a shared `LineStore`, which
+/// costs no copy of the text, or text of its own, such as a line with its timestamps
+/// replaced. Lines compare and hash by their text, whichever way it is held.
+#[derive(Clone)]
+pub struct LineText(LineTextRepr);
+
+#[derive(Clone)]
+enum LineTextRepr {
+    Owned(Box<str>),
+    Stored { store: Arc<LineStore>, index: usize },
+}
+
+impl LineText {
+    /// Line `index` of `store`, which stays alive as long as the line does.
+    pub fn stored(store: &Arc<LineStore>, index: usize) -> Self {
+        debug_assert!(index < store.len());
+        Self(LineTextRepr::Stored {
+            store: Arc::clone(store),
+            index,
+        })
+    }
+
+    /// Every line of `store`, in order.
+    pub fn all_of(store: &Arc<LineStore>) -> impl Iterator<Item = LineText> + '_ {
+        (0..store.len()).map(|index| Self::stored(store, index))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            LineTextRepr::Owned(text) => text,
+            LineTextRepr::Stored { store, index } => store.line(*index),
+        }
+    }
+}
+
+impl Deref for LineText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for LineText {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for LineText {
+    fn from(text: String) -> Self {
+        Self(LineTextRepr::Owned(text.into_boxed_str()))
+    }
+}
+
+impl From<&str> for LineText {
+    fn from(text: &str) -> Self {
+        Self(LineTextRepr::Owned(text.into()))
+    }
+}
+
+impl PartialEq for LineText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for LineText {}
+
+impl PartialEq<str> for LineText {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for LineText {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for LineText {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl Hash for LineText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for LineText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for LineText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::{Seek, SeekFrom, Write};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn lines_split_like_str_lines() {
+        // [CSV-Core-LineStoreV1]
+        for text in [
+            "",
+            "\n",
+            "a",
+            "a\n",
+            "a\r\nb",
+            "a\n\nb\r\n",
+            "a\rb\n",
+            "x\r",
+        ] {
+            let store = LineStore::from_text(text.to_string());
+
+            let lines: Vec<&str> = store.lines().collect();
+            assert_eq!(lines, text.lines().collect::<Vec<_>>(), "{text:?}");
+            assert_eq!(store.len(), lines.len());
+        }
+    }
+
+    #[test]
+    fn utf8_files_are_mapped_and_other_files_are_not() {
+        // [CSV-Core-LineStoreV1]
+        let mut utf8 = NamedTempFile::new().expect("temp file");
+        utf8.write_all("Grüße\r\nnext\n".as_bytes()).unwrap();
+        let mut latin1 = NamedTempFile::new().expect("temp file");
+        latin1.write_all(b"Gr\xFC\xDFe\n").unwrap();
+
+        let store = LineStore::map(utf8.as_file(), 14).unwrap().expect("mapped");
+
+        assert!(store.is_mapped());
+        assert_eq!(store.lines().collect::<Vec<_>>(), ["Grüße", "next"]);
+        assert!(LineStore::map(latin1.as_file(), 6).unwrap().is_none());
+    }
+
+    #[test]
+    fn mapped_files_are_a_snapshot_of_a_log_still_being_written() {
+        // [CSV-Core-LineStoreV1] The writer appends and rewrites bytes, never truncates.
+        let mut file = NamedTempFile::new().expect("temp file");
+        file.write_all("alpha\nbeta\nGr\u{FC}".as_bytes()).unwrap();
+        let len = file.as_file().metadata().unwrap().len() - 1;
+        let store = LineStore::map(file.as_file(), len)
+            .unwrap()
+            .expect("mapped");
+        assert_eq!(store.lines().collect::<Vec<_>>(), ["alpha", "beta", "Gr"]);
+
+        let mut writer = OpenOptions::new().write(true).open(file.path()).unwrap();
+        writer.seek(SeekFrom::Start(8)).unwrap();
+        writer.write_all(b"\xFF").unwrap();
+        writer.seek(SeekFrom::End(0)).unwrap();
+        writer.write_all(b"e\nlater\n").unwrap();
+
+        assert_eq!(store.lines().collect::<Vec<_>>(), ["alpha", "be", "Gr"]);
+    }
+
+    #[test]
+    fn stored_lines_equal_owned_lines_of_the_same_text() {
+        // [CSV-Core-LineStoreV1] Diffing relies on equal text meaning equal lines.
+        let store = Arc::new(LineStore::from_text("alpha\nbeta\n".to_string()));
+        let stored: Vec<LineText> = LineText::all_of(&store).collect();
+
+        assert_eq!(stored, [LineText::from("alpha"), LineText::from("beta")]);
+        assert_eq!(stored[1], "beta");
+        let hash = |line: &LineText| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            line.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&stored[0]), hash(&LineText::from("alpha")));
+    }
+}
@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extensions compressed rotations get, stripped before reading the rotation index.
+const COMPRESSED_EXTENSIONS: [&str; 4] = ["gz", "zst", "bz2", "xz"];
+
+/// What one side of the comparison reads, per [CSV-File-RotatedSetV1]. Persisted as a
+/// plain path for a single file, as before sets existed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LogSource {
+    File(PathBuf),
+    /// Files read one after the other, in this order.
+    Files(Vec<PathBuf>),
+    /// Files whose name matches `glob` (`*` and `?` in the file name only), read as a
+    /// rotation set, oldest first.
+    Rotation {
+        glob: PathBuf,
+    },
+}
+
+impl LogSource {
+    /// The rotation set `path` belongs to: `app.log` with `app.log.1`, `app.log.2.gz`, ...
+    pub fn rotation_of(path: &Path) -> Self {
+        let mut glob = path.as_os_str().to_owned();
+        glob.push("*");
+        LogSource::Rotation { glob: glob.into() }
+    }
+
+    /// This source with `path` read after everything it read so far. A rotation set is
+    /// expanded to the files it matches now.
+    pub fn with_appended(self, path: PathBuf) -> Self {
+        let mut files = match self {
+            LogSource::File(file) => vec![file],
+            LogSource::Files(files) => files,
+            rotation @ LogSource::Rotation { .. } => rotation.resolve().unwrap_or_else(|err| {
+                log::debug!("[CSV-File-RotatedSetV1] Dropping the rotation set: {err}");
+                Vec::new()
+            }),
+        };
+        files.push(path);
+        LogSource::Files(files)
+    }
+
+    /// The files to read, in reading order.
+    pub fn resolve(&self) -> io::Result<Vec<PathBuf>> {
+        match self {
+            LogSource::File(path) => Ok(vec![path.clone()]),
+            LogSource::Files(files) => Ok(files.clone()),
+            LogSource::Rotation { glob } => resolve_rotation_glob(glob),
+        }
+    }
+
+    /// Directory of the first file, where file dialogs for this side start.
+    pub fn directory(&self) -> Option<&Path> {
+        let first = match self {
+            LogSource::File(path) => path,
+            LogSource::Files(files) => files.first()?,
+            LogSource::Rotation { glob } => glob,
+        };
+        first.parent()
+    }
+}
+
+impl fmt::Display for LogSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogSource::File(path) | LogSource::Rotation { glob: path } => {
+                write!(f, "{}", path.display())
+            }
+            LogSource::Files(files) => {
+                let names: Vec<String> = files.iter().map(|f| file_name(f)).collect();
+                write!(f, "{}", names.join(" + "))
+            }
+        }
+    }
+}
+
+fn resolve_rotation_glob(glob: &Path) -> io::Result<Vec<PathBuf>> {
+    let pattern = glob
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the glob has no file name"))?;
+    let directory = match glob.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_file() && name.to_str().is_some_and(|n| matches_glob(pattern, n)) {
+            files.push(entry.path());
+        }
+    }
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no file matches '{}'", glob.display()),
+        ));
+    }
+
+    order_rotation(&mut files);
+    Ok(files)
+}
+
+/// Orders a rotation set oldest first: the highest rotation index comes first and the
+/// live file, without an index, last. Files without an index are ordered by name, which
+/// suits date-stamped rotations.
+pub fn order_rotation(files: &mut [PathBuf]) {
+    files.sort_by_cached_key(|path| (Reverse(rotation_index(path)), file_name(path)));
+}
+
+/// The `N` of `app.log.N` or `app.log.N.gz`; zero for the live file.
+fn rotation_index(path: &Path) -> u32 {
+    let name = file_name(path);
+    let name = match name.rsplit_once('.') {
+        Some((stem, extension)) if COMPRESSED_EXTENSIONS.contains(&extension) => stem,
+        _ => &name,
+    };
+    name.rsplit_once('.')
+        .and_then(|(_, suffix)| suffix.parse().ok())
+        .unwrap_or(0)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of characters and `?`
+/// for any single character.
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was and how much of the name it has swallowed.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, swallowed)) => {
+                    backtrack = Some((star, swallowed + 1));
+                    p = star + 1;
+                    n = swallowed + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Where a line of a side's concatenated text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineLocation<'a> {
+    path: &'a Path,
+    line_number: usize,
+}
+
+impl LineLocation<'_> {
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// One-based line number within `path`.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+}
+
+/// Maps the line numbers of a side, as in `LineContent`, back to the file and the line
+/// within it, per [CSV-File-RotatedSetV1].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineOrigins {
+    /// Each file with the number of lines read from it.
+    files: Vec<(PathBuf, usize)>,
+}
+
+impl LineOrigins {
+    /// Records that the next `line_count` lines came from `path`.
+    pub fn push_file(&mut self, path: PathBuf, line_count: usize) {
+        self.files.push((path, line_count));
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Location of the one-based `line_number` of the concatenated text.
+    pub fn locate(&self, line_number: usize) -> Option<LineLocation<'_>> {
+        let mut remaining = line_number.checked_sub(1)?;
+        for (path, line_count) in &self.files {
+            if remaining < *line_count {
+                return Some(LineLocation {
+                    path,
+                    line_number: remaining + 1,
+                });
+            }
+            remaining -= line_count;
+        }
+        None
+    }
+
+    /// `line_number` for display: the plain number for a single file, and the file name
+    /// with the line within it, e.g. `app.log.1:42`, for a set.
+    pub fn describe(&self, line_number: usize) -> String {
+        match self.locate(line_number) {
+            Some(location) if self.files.len() > 1 => {
+                format!("{}:{}", file_name(location.path), location.line_number)
+            }
+            _ => line_number.to_string(),
+        }
+    }
+
+    /// The lines `first` to `last` for display, like `describe`. A range within one file
+    /// of a set names the file once, e.g. `app.log.1:120–140`.
+    pub fn describe_range(&self, first: usize, last: usize) -> String {
+        match (self.locate(first), self.locate(last)) {
+            (Some(start), Some(end)) if self.files.len() > 1 && start.path == end.path => {
+                format!(
+                    "{}:{}\u{2013}{}",
+                    file_name(start.path),
+                    start.line_number,
+                    end.line_number
+                )
+            }
+            _ => format!("{}\u{2013}{}", self.describe(first), self.describe(last)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rotation_set_is_read_oldest_first() {
+        // [CSV-File-RotatedSetV1]
+        let temp_dir = TempDir::new().expect("temp dir");
+        for name in [
+            "app.log",
+            "app.log.1",
+            "app.log.2.gz",
+            "app.log.10.gz",
+            "other.log",
+        ] {
+            std::fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+
+        let files = LogSource::rotation_of(&temp_dir.path().join("app.log"))
+            .resolve()
+            .unwrap();
+
+        let names: Vec<String> = files.iter().map(|path| file_name(path)).collect();
+        assert_eq!(
+            names,
+            ["app.log.10.gz", "app.log.2.gz", "app.log.1", "app.log"]
+        );
+    }
+
+    #[test]
+    fn glob_without_matches_is_an_error() {
+        // [CSV-File-RotatedSetV1]
+        let temp_dir = TempDir::new().expect("temp dir");
+        let source = LogSource::Rotation {
+            glob: temp_dir.path().join("missing-*.log"),
+        };
+
+        assert_eq!(
+            source.resolve().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn glob_wildcards_match_runs_and_single_characters() {
+        assert!(matches_glob("app.log*", "app.log"));
+        assert!(matches_glob("app-*.log", "app-2024-01-02.log"));
+        assert!(matches_glob("a?c*d", "abcxxd"));
+        assert!(!matches_glob("app.log*", "app.txt"));
+        assert!(!matches_glob("a?c", "ac"));
+    }
+
+    #[test]
+    fn line_numbers_map_back_to_file_and_local_line() {
+        // [CSV-File-RotatedSetV1]
+        let mut origins = LineOrigins::default();
+        origins.push_file(PathBuf::from("logs/app.log.1"), 3);
+        origins.push_file(PathBuf::from("logs/app.log"), 2);
+
+        let location = origins.locate(4).unwrap();
+        assert_eq!(location.path(), Path::new("logs/app.log"));
+        assert_eq!(location.line_number(), 1);
+        assert_eq!(origins.describe(3), "app.log.1:3");
+        assert_eq!(origins.locate(6), None);
+        assert_eq!(origins.locate(0), None);
+        assert_eq!(origins.describe_range(4, 5), "app.log:1\u{2013}2");
+        assert_eq!(origins.describe_range(2, 4), "app.log.1:2\u{2013}app.log:1");
+        assert_eq!(LineOrigins::default().describe_range(2, 4), "2\u{2013}4");
+    }
+
+    #[test]
+    fn single_paths_persist_as_before_and_sets_round_trip() {
+        // [CSV-File-RotatedSetV1] Settings written before sets existed hold a plain path.
+        let single: LogSource = serde_json::from_str(r#""logs/app.log""#).unwrap();
+        assert_eq!(single, LogSource::File(PathBuf::from("logs/app.log")));
+
+        let appended = single.with_appended(PathBuf::from("logs/app.log.1"));
+        let json = serde_json::to_string(&appended).unwrap();
+        assert_eq!(json, r#"["logs/app.log","logs/app.log.1"]"#);
+        let rotation = LogSource::rotation_of(Path::new("logs/app.log"));
+        let json = serde_json::to_string(&rotation).unwrap();
+        assert_eq!(serde_json::from_str::<LogSource>(&json).unwrap(), rotation);
+    }
+}
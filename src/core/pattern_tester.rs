@@ -0,0 +1,217 @@
+use regex::Regex;
+
+use crate::core::line_normalizer::LineNormalizer;
+use crate::core::timestamp_parser::{STRIP_GROUP_NAME, TimestampParserError, replace_matches};
+
+/// Lines from the start of each file the pattern tester previews.
+pub const PATTERN_TEST_SAMPLE_LINES: usize = 10;
+
+/// What the timestamp patterns did to one sample line, per [CSV-UX-PatternTesterV1].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternTestLine {
+    original_text: String,
+    matches: Vec<String>,
+    comparable_text: String,
+}
+
+impl PatternTestLine {
+    pub fn new(
+        original_text: impl Into<String>,
+        matches: Vec<String>,
+        comparable_text: impl Into<String>,
+    ) -> Self {
+        Self {
+            original_text: original_text.into(),
+            matches,
+            comparable_text: comparable_text.into(),
+        }
+    }
+
+    pub fn original_text(&self) -> &str {
+        &self.original_text
+    }
+
+    /// Text each match replaced, in the order the patterns ran. With a `strip` group
+    /// this is the group's span rather than the whole match.
+    pub fn matches(&self) -> &[String] {
+        &self.matches
+    }
+
+    pub fn comparable_text(&self) -> &str {
+        &self.comparable_text
+    }
+
+    pub fn is_matched(&self) -> bool {
+        !self.matches.is_empty()
+    }
+}
+
+/// Pattern test of the sample lines of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternTestResult {
+    lines: Vec<PatternTestLine>,
+}
+
+impl PatternTestResult {
+    pub fn lines(&self) -> &[PatternTestLine] {
+        &self.lines
+    }
+
+    pub fn matched_line_count(&self) -> usize {
+        self.lines.iter().filter(|line| line.is_matched()).count()
+    }
+
+    /// Share of the sample lines any pattern matched, from 0.0 to 1.0. A file without
+    /// lines has nothing to match and counts as fully matched.
+    pub fn match_fraction(&self) -> f64 {
+        if self.lines.is_empty() {
+            return 1.0;
+        }
+        self.matched_line_count() as f64 / self.lines.len() as f64
+    }
+}
+
+/// Runs `patterns` over `lines` exactly as the diff does, recording what each pattern
+/// matched and the comparable text that results once `normalizer` ran too. Unlike
+/// `strip_timestamps`, which returns unmatched lines unchanged, this shows which lines
+/// the patterns missed.
+pub fn test_pattern_list(
+    lines: &[String],
+    patterns: &[String],
+    replacement: &str,
+    normalizer: &LineNormalizer,
+) -> Result<PatternTestResult, TimestampParserError> {
+    let regexes = patterns
+        .iter()
+        .enumerate()
+        .filter(|(_, pattern)| !pattern.is_empty())
+        .map(|(index, pattern)| {
+            Regex::new(pattern)
+                .map_err(|e| TimestampParserError::invalid_pattern(index, pattern.as_str(), &e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let lines = lines
+        .iter()
+        .map(|line| {
+            let mut matches = Vec::new();
+            let stripped = regexes.iter().fold(line.clone(), |text, regex| {
+                matches.extend(matched_texts(regex, &text));
+                replace_matches(regex, &text, replacement).into_owned()
+            });
+            let comparable_text = normalizer.normalize(&stripped).into_owned();
+            PatternTestLine::new(line.as_str(), matches, comparable_text)
+        })
+        .collect();
+
+    Ok(PatternTestResult { lines })
+}
+
+/// Spans of `text` that `replace_matches` replaces.
+fn matched_texts(regex: &Regex, text: &str) -> Vec<String> {
+    let has_strip_group = regex
+        .capture_names()
+        .any(|name| name == Some(STRIP_GROUP_NAME));
+    regex
+        .captures_iter(text)
+        .filter_map(|caps| {
+            if has_strip_group {
+                caps.name(STRIP_GROUP_NAME)
+            } else {
+                caps.get(0)
+            }
+        })
+        .map(|span| span.as_str().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{NormalizationAction, NormalizationRule};
+
+    fn lines(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_and_comparable_text_are_shown_per_line() {
+        // [CSV-UX-PatternTesterV1]
+        let sample = lines(&[
+            "[10:00:01] Starting service",
+            "no timestamp here",
+            "[10:00:02] Ready after 42 ms",
+        ]);
+        let normalizer = LineNormalizer::new(&[NormalizationRule::new(
+            "durations",
+            r"\d+ ms",
+            NormalizationAction::Placeholder("<DUR>".to_string()),
+        )])
+        .unwrap();
+
+        let result =
+            test_pattern_list(&sample, &[r"^\[[^\]]+\] ".to_string()], "", &normalizer).unwrap();
+
+        assert_eq!(
+            result.lines(),
+            [
+                PatternTestLine::new(
+                    "[10:00:01] Starting service",
+                    vec!["[10:00:01] ".to_string()],
+                    "Starting service"
+                ),
+                PatternTestLine::new("no timestamp here", Vec::new(), "no timestamp here"),
+                PatternTestLine::new(
+                    "[10:00:02] Ready after 42 ms",
+                    vec!["[10:00:02] ".to_string()],
+                    "Ready after <DUR>"
+                ),
+            ]
+        );
+        assert_eq!(result.matched_line_count(), 2);
+        assert!((result.match_fraction() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_pattern_matching_nothing_is_reported_as_zero_coverage() {
+        // [CSV-UX-PatternTesterV1] The parser silently returns such lines unchanged.
+        let sample = lines(&["2024-01-01 alpha", "2024-01-02 beta"]);
+
+        let result = test_pattern_list(
+            &sample,
+            &[r"^\[\d+\]".to_string()],
+            "",
+            &LineNormalizer::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.matched_line_count(), 0);
+        assert_eq!(result.match_fraction(), 0.0);
+        assert_eq!(result.lines()[0].comparable_text(), "2024-01-01 alpha");
+    }
+
+    #[test]
+    fn strip_groups_report_only_their_span_and_invalid_patterns_fail() {
+        let sample = lines(&["id=7 at 10:00:01 done"]);
+
+        let result = test_pattern_list(
+            &sample,
+            &[r"at (?P<strip>\d{2}:\d{2}:\d{2})".to_string()],
+            "<TS>",
+            &LineNormalizer::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.lines()[0].matches(), ["10:00:01"]);
+        assert_eq!(result.lines()[0].comparable_text(), "id=7 at <TS> done");
+        assert!(matches!(
+            test_pattern_list(
+                &sample,
+                &["ok".to_string(), "(".to_string()],
+                "",
+                &LineNormalizer::default()
+            ),
+            Err(TimestampParserError::InvalidPattern { index: 1, .. })
+        ));
+    }
+}
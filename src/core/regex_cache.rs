@@ -0,0 +1,146 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Compiled patterns kept by default. Typing a pattern compiles every valid prefix of it,
+/// so the cache must forget old entries rather than grow for the whole session.
+pub const DEFAULT_REGEX_CACHE_CAPACITY: usize = 64;
+
+/// Counters describing how well the regex cache works, per [CSV-Core-RegexCacheV2].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexCacheStats {
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    len: usize,
+    capacity: usize,
+}
+
+impl RegexCacheStats {
+    /// Lookups that found the pattern already compiled.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to compile the pattern, including those that failed to compile.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Patterns dropped to make room for newer ones.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+struct CacheEntry {
+    regex: Regex,
+    last_used: u64,
+}
+
+/// Least recently used cache of compiled patterns holding at most `capacity` entries,
+/// per [CSV-Core-RegexCacheV2]. A capacity of zero disables caching. Capacities are
+/// small, so the least recently used entry is found by a linear scan.
+pub struct RegexCache {
+    capacity: usize,
+    entries: HashMap<String, CacheEntry>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl RegexCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Returns the cached regex for `pattern`, or compiles it with `compile` and caches it.
+    /// Patterns that fail to compile are not cached.
+    pub fn get_or_compile<E>(
+        &mut self,
+        pattern: &str,
+        compile: impl FnOnce(&str) -> Result<Regex, E>,
+    ) -> Result<Regex, E> {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(pattern) {
+            entry.last_used = self.clock;
+            self.hits += 1;
+            return Ok(entry.regex.clone());
+        }
+
+        self.misses += 1;
+        let regex = compile(pattern)?;
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+            self.entries.insert(
+                pattern.to_string(),
+                CacheEntry {
+                    regex: regex.clone(),
+                    last_used: self.clock,
+                },
+            );
+        }
+        Ok(regex)
+    }
+
+    /// Changes the capacity, evicting the least recently used patterns that no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict_least_recently_used();
+        }
+    }
+
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.entries.contains_key(pattern)
+    }
+
+    pub fn stats(&self) -> RegexCacheStats {
+        RegexCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            len: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(pattern, _)| pattern.clone());
+        if let Some(pattern) = oldest {
+            self.entries.remove(&pattern);
+            self.evictions += 1;
+        }
+    }
+}
+
+impl Default for RegexCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_REGEX_CACHE_CAPACITY)
+    }
+}
@@ -1,18 +1,93 @@
+use crate::core::diff_engine::{DEFAULT_MIN_MOVED_BLOCK_SIZE, DiffAlgorithm};
+use crate::core::line_normalizer::NormalizationRule;
+use crate::core::log_source::LogSource;
+use crate::core::regex_cache::DEFAULT_REGEX_CACHE_CAPACITY;
+use crate::core::text_decoding::TextEncoding;
+use crate::core::timestamp_presets::TimestampPreset;
+use crate::core::timing_analysis::DEFAULT_SLOWDOWN_RATIO;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::path::PathBuf;
+
+/// Algorithm the user chose for one pair of sources per [CSV-Diff-AlgorithmChoiceV1].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ComparisonAlgorithm {
+    left: LogSource,
+    right: LogSource,
+    algorithm: DiffAlgorithm,
+}
+
+impl ComparisonAlgorithm {
+    pub fn new(left: LogSource, right: LogSource, algorithm: DiffAlgorithm) -> Self {
+        Self {
+            left,
+            right,
+            algorithm,
+        }
+    }
+
+    /// Whether this is the algorithm for comparing `left` with `right`.
+    pub fn compares(&self, left: &LogSource, right: &LogSource) -> bool {
+        self.left == *left && self.right == *right
+    }
+
+    pub fn algorithm(&self) -> DiffAlgorithm {
+        self.algorithm
+    }
+}
 
 /// Snapshot of persisted fields between sessions per [CSV-Tech-SettingsPersistenceV1].
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct AppSettings {
+    /// What each side reads per [CSV-File-RotatedSetV1]; a plain path for a single file.
+    #[serde(default)]
+    left_file_path: Option<LogSource>,
+    #[serde(default)]
+    right_file_path: Option<LogSource>,
+    /// Encodings chosen for the files per [CSV-File-EncodingV1]; unset means detected.
     #[serde(default)]
-    left_file_path: Option<PathBuf>,
+    left_encoding: Option<TextEncoding>,
     #[serde(default)]
-    right_file_path: Option<PathBuf>,
+    right_encoding: Option<TextEncoding>,
+    /// Timestamp patterns for both files, one per line, per [CSV-Core-TSPatternListV1].
     #[serde(default)]
     timestamp_pattern: String,
+    /// Patterns for the right file only; empty means the right file uses `timestamp_pattern`.
+    #[serde(default)]
+    right_timestamp_pattern: String,
+    /// Text substituted for timestamp matches; empty removes them.
+    #[serde(default)]
+    timestamp_replacement: String,
+    /// `time` format description for parsing matches per [CSV-Core-TSExtractV1].
+    #[serde(default)]
+    timestamp_format: String,
     #[serde(default)]
     timestamp_history: VecDeque<String>,
+    /// Named, user-managed patterns per [CSV-Core-TSPresetsV1].
+    #[serde(default)]
+    timestamp_presets: Vec<TimestampPreset>,
+    /// Algorithm of the last comparison, also used for pairs compared for the first time.
+    #[serde(default)]
+    diff_algorithm: DiffAlgorithm,
+    /// Algorithms chosen for earlier comparisons, most recent first.
+    #[serde(default)]
+    comparison_algorithms: Vec<ComparisonAlgorithm>,
+    /// Fewest contiguous moved lines shown as a moved block per [CSV-UI-MovedBlocksV1];
+    /// unset means `DEFAULT_MIN_MOVED_BLOCK_SIZE`.
+    #[serde(default)]
+    min_moved_block_size: Option<usize>,
+    #[serde(default)]
+    normalization_rules: Vec<NormalizationRule>,
+    /// Slowdown threshold per [CSV-Core-TimingV1]; unset means `DEFAULT_SLOWDOWN_RATIO`.
+    #[serde(default)]
+    slowdown_ratio: Option<f64>,
+    /// Compiled timestamp patterns kept per [CSV-Core-RegexCacheV2]; unset means
+    /// `DEFAULT_REGEX_CACHE_CAPACITY`.
+    #[serde(default)]
+    regex_cache_capacity: Option<usize>,
+    /// Regex removed from relative paths before pairing the files of two directories per
+    /// [CSV-File-DirectoryCompareV1]; empty pairs them by relative path alone.
+    #[serde(default)]
+    pairing_pattern: String,
 }
 
 impl AppSettings {
@@ -21,32 +96,163 @@ impl AppSettings {
     }
 
     pub fn with_values(
-        left_file_path: Option<PathBuf>,
-        right_file_path: Option<PathBuf>,
+        left_file_path: Option<LogSource>,
+        right_file_path: Option<LogSource>,
         timestamp_pattern: String,
         timestamp_history: VecDeque<String>,
+        diff_algorithm: DiffAlgorithm,
     ) -> Self {
         Self {
             left_file_path,
             right_file_path,
+            left_encoding: None,
+            right_encoding: None,
             timestamp_pattern,
+            right_timestamp_pattern: String::new(),
+            timestamp_replacement: String::new(),
+            timestamp_format: String::new(),
             timestamp_history,
+            timestamp_presets: Vec::new(),
+            diff_algorithm,
+            comparison_algorithms: Vec::new(),
+            min_moved_block_size: None,
+            normalization_rules: Vec::new(),
+            slowdown_ratio: None,
+            regex_cache_capacity: None,
+            pairing_pattern: String::new(),
         }
     }
 
-    pub fn left_file_path(&self) -> Option<&PathBuf> {
+    pub fn with_comparison_algorithms(
+        mut self,
+        comparison_algorithms: Vec<ComparisonAlgorithm>,
+    ) -> Self {
+        self.comparison_algorithms = comparison_algorithms;
+        self
+    }
+
+    pub fn with_min_moved_block_size(mut self, min_moved_block_size: usize) -> Self {
+        self.min_moved_block_size = Some(min_moved_block_size);
+        self
+    }
+
+    pub fn with_file_encodings(
+        mut self,
+        left_encoding: Option<TextEncoding>,
+        right_encoding: Option<TextEncoding>,
+    ) -> Self {
+        self.left_encoding = left_encoding;
+        self.right_encoding = right_encoding;
+        self
+    }
+
+    pub fn with_right_timestamp_pattern(mut self, right_timestamp_pattern: String) -> Self {
+        self.right_timestamp_pattern = right_timestamp_pattern;
+        self
+    }
+
+    pub fn with_timestamp_replacement(mut self, timestamp_replacement: String) -> Self {
+        self.timestamp_replacement = timestamp_replacement;
+        self
+    }
+
+    pub fn with_timestamp_format(mut self, timestamp_format: String) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+
+    pub fn with_timestamp_presets(mut self, timestamp_presets: Vec<TimestampPreset>) -> Self {
+        self.timestamp_presets = timestamp_presets;
+        self
+    }
+
+    pub fn with_normalization_rules(mut self, normalization_rules: Vec<NormalizationRule>) -> Self {
+        self.normalization_rules = normalization_rules;
+        self
+    }
+
+    pub fn with_slowdown_ratio(mut self, slowdown_ratio: f64) -> Self {
+        self.slowdown_ratio = Some(slowdown_ratio);
+        self
+    }
+
+    pub fn with_regex_cache_capacity(mut self, regex_cache_capacity: usize) -> Self {
+        self.regex_cache_capacity = Some(regex_cache_capacity);
+        self
+    }
+
+    pub fn with_pairing_pattern(mut self, pairing_pattern: String) -> Self {
+        self.pairing_pattern = pairing_pattern;
+        self
+    }
+
+    pub fn left_file_path(&self) -> Option<&LogSource> {
         self.left_file_path.as_ref()
     }
 
-    pub fn right_file_path(&self) -> Option<&PathBuf> {
+    pub fn right_file_path(&self) -> Option<&LogSource> {
         self.right_file_path.as_ref()
     }
 
+    pub fn left_encoding(&self) -> Option<TextEncoding> {
+        self.left_encoding
+    }
+
+    pub fn right_encoding(&self) -> Option<TextEncoding> {
+        self.right_encoding
+    }
+
     pub fn timestamp_pattern(&self) -> &str {
         &self.timestamp_pattern
     }
 
+    pub fn right_timestamp_pattern(&self) -> &str {
+        &self.right_timestamp_pattern
+    }
+
+    pub fn timestamp_replacement(&self) -> &str {
+        &self.timestamp_replacement
+    }
+
+    pub fn timestamp_format(&self) -> &str {
+        &self.timestamp_format
+    }
+
     pub fn timestamp_history(&self) -> &VecDeque<String> {
         &self.timestamp_history
     }
+
+    pub fn timestamp_presets(&self) -> &[TimestampPreset] {
+        &self.timestamp_presets
+    }
+
+    pub fn diff_algorithm(&self) -> DiffAlgorithm {
+        self.diff_algorithm
+    }
+
+    pub fn comparison_algorithms(&self) -> &[ComparisonAlgorithm] {
+        &self.comparison_algorithms
+    }
+
+    pub fn min_moved_block_size(&self) -> usize {
+        self.min_moved_block_size
+            .unwrap_or(DEFAULT_MIN_MOVED_BLOCK_SIZE)
+    }
+
+    pub fn normalization_rules(&self) -> &[NormalizationRule] {
+        &self.normalization_rules
+    }
+
+    pub fn slowdown_ratio(&self) -> f64 {
+        self.slowdown_ratio.unwrap_or(DEFAULT_SLOWDOWN_RATIO)
+    }
+
+    pub fn regex_cache_capacity(&self) -> usize {
+        self.regex_cache_capacity
+            .unwrap_or(DEFAULT_REGEX_CACHE_CAPACITY)
+    }
+
+    pub fn pairing_pattern(&self) -> &str {
+        &self.pairing_pattern
+    }
 }
@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bytes examined when looking for the NUL bytes that give away BOM-less UTF-16.
+pub(crate) const UTF16_SNIFF_BYTES: usize = 4_096;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Text encodings log files are read in, per [CSV-File-EncodingV1].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Superset of Latin-1 used by Windows tools; every byte decodes to some character.
+    Windows1252,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 4] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Windows1252,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Windows1252 => "Windows-1252",
+        }
+    }
+
+    fn bom(&self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8 => UTF8_BOM,
+            TextEncoding::Utf16Le => UTF16_LE_BOM,
+            TextEncoding::Utf16Be => UTF16_BE_BOM,
+            TextEncoding::Windows1252 => &[],
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How a file was decoded, per [CSV-File-EncodingV1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodingOutcome {
+    encoding: TextEncoding,
+    was_detected: bool,
+    is_lossy: bool,
+}
+
+impl DecodingOutcome {
+    pub fn new(encoding: TextEncoding, was_detected: bool, is_lossy: bool) -> Self {
+        Self {
+            encoding,
+            was_detected,
+            is_lossy,
+        }
+    }
+
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    /// Whether the encoding was detected rather than chosen by the user.
+    pub fn was_detected(&self) -> bool {
+        self.was_detected
+    }
+
+    /// Whether some bytes were not valid in the encoding and were replaced by U+FFFD.
+    pub fn is_lossy(&self) -> bool {
+        self.is_lossy
+    }
+}
+
+impl Default for DecodingOutcome {
+    fn default() -> Self {
+        Self::new(TextEncoding::Utf8, true, false)
+    }
+}
+
+/// Text of a file together with how it was decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    text: String,
+    outcome: DecodingOutcome,
+}
+
+impl DecodedText {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Splits the text into lines the way `BufRead::lines` does.
+    pub fn lines(&self) -> Vec<String> {
+        self.text.lines().map(str::to_string).collect()
+    }
+
+    pub fn outcome(&self) -> DecodingOutcome {
+        self.outcome
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
+
+/// Decodes `bytes` in `encoding_override`, or in the detected encoding when there is
+/// none, per [CSV-File-EncodingV1]. Invalid bytes never fail the decoding; they become
+/// replacement characters and the result is marked lossy. Valid UTF-8 keeps its buffer,
+/// so large files are not held twice per [CSV-Core-LineStoreV1].
+pub fn decode_text(mut bytes: Vec<u8>, encoding_override: Option<TextEncoding>) -> DecodedText {
+    let encoding = encoding_override.unwrap_or_else(|| detect_encoding(&bytes));
+    if bytes.starts_with(encoding.bom()) {
+        bytes.drain(..encoding.bom().len());
+    }
+    let (text, is_lossy) = match encoding {
+        TextEncoding::Utf8 => match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(err) => (String::from_utf8_lossy(err.as_bytes()).into_owned(), true),
+        },
+        TextEncoding::Utf16Le => decode_utf16(&bytes, u16::from_le_bytes),
+        TextEncoding::Utf16Be => decode_utf16(&bytes, u16::from_be_bytes),
+        TextEncoding::Windows1252 => (bytes.iter().map(|&b| windows1252_char(b)).collect(), false),
+    };
+
+    DecodedText {
+        text,
+        outcome: DecodingOutcome::new(encoding, encoding_override.is_none(), is_lossy),
+    }
+}
+
+/// Whether UTF-8 text starting with `start` decodes to its own bytes: `decode_text` would
+/// neither strip a BOM nor take it for UTF-16. Lets valid UTF-8 files be used as they are
+/// per [CSV-Core-LineStoreV1].
+pub fn decodes_as_itself(start: &[u8], encoding_override: Option<TextEncoding>) -> bool {
+    let encoding = encoding_override
+        .or_else(|| sniff_utf16(&start[..start.len().min(UTF16_SNIFF_BYTES)]))
+        .unwrap_or(TextEncoding::Utf8);
+    encoding == TextEncoding::Utf8 && !start.starts_with(UTF8_BOM)
+}
+
+/// Picks the encoding of `bytes`: a byte order mark decides, then NUL bytes at every
+/// other position reveal UTF-16, and otherwise UTF-8 wins unless invalid sequences
+/// outnumber valid multi-byte characters, which points at a legacy single-byte encoding.
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    for encoding in [
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+    ] {
+        if bytes.starts_with(encoding.bom()) {
+            return encoding;
+        }
+    }
+
+    if let Some(encoding) = sniff_utf16(&bytes[..bytes.len().min(UTF16_SNIFF_BYTES)]) {
+        return encoding;
+    }
+
+    let mut multi_byte_chars = 0usize;
+    let mut invalid_sequences = 0usize;
+    for chunk in bytes.utf8_chunks() {
+        multi_byte_chars += chunk.valid().chars().filter(|c| c.len_utf8() > 1).count();
+        if !chunk.invalid().is_empty() {
+            invalid_sequences += 1;
+        }
+    }
+    if invalid_sequences == 0 || multi_byte_chars > invalid_sequences {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Windows1252
+    }
+}
+
+/// ASCII text in UTF-16 has a NUL in every high byte: at odd offsets in little endian
+/// and at even offsets in big endian.
+fn sniff_utf16(sample: &[u8]) -> Option<TextEncoding> {
+    if sample.len() < 2 {
+        return None;
+    }
+
+    let zeros_at = |parity: usize| {
+        sample
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    let (even_zeros, odd_zeros) = (zeros_at(0), zeros_at(1));
+    let pairs = sample.len() / 2;
+
+    if odd_zeros * 2 > pairs && even_zeros * 4 < odd_zeros {
+        Some(TextEncoding::Utf16Le)
+    } else if even_zeros * 2 > pairs && odd_zeros * 4 < even_zeros {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> (String, bool) {
+    let pairs = bytes.chunks_exact(2);
+    // A trailing odd byte is half a code unit.
+    let is_truncated = !pairs.remainder().is_empty();
+    let mut is_lossy = is_truncated;
+    let mut text: String = char::decode_utf16(pairs.map(|pair| to_unit([pair[0], pair[1]])))
+        .map(|unit| {
+            unit.unwrap_or_else(|_| {
+                is_lossy = true;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    if is_truncated {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    (text, is_lossy)
+}
+
+/// Windows-1252 differs from Latin-1 only in 0x80..=0x9F; its five unassigned bytes
+/// decode to the C1 control of the same value, as browsers do.
+fn windows1252_char(byte: u8) -> char {
+    const HIGH_CONTROLS: [char; 32] = [
+        '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}',
+        '\u{017D}', '\u{008F}', '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+        '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}',
+        '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH_CONTROLS[usize::from(byte - 0x80)],
+        _ => char::from(byte),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, to_bytes: fn(u16) -> [u8; 2], bom: &[u8]) -> Vec<u8> {
+        let mut bytes = bom.to_vec();
+        bytes.extend(text.encode_utf16().flat_map(to_bytes));
+        bytes
+    }
+
+    #[test]
+    fn byte_order_marks_decide_the_encoding_and_are_stripped() {
+        // [CSV-File-EncodingV1]
+        let le = utf16("[10:00] Grüße\r\nnext", u16::to_le_bytes, UTF16_LE_BOM);
+        let be = utf16("[10:00] Grüße\r\nnext", u16::to_be_bytes, UTF16_BE_BOM);
+        let utf8 = [UTF8_BOM, "[10:00] Grüße\nnext".as_bytes()].concat();
+
+        for bytes in [le, be, utf8] {
+            let decoded = decode_text(bytes, None);
+
+            assert!(decoded.outcome().was_detected());
+            assert!(!decoded.outcome().is_lossy());
+            assert_eq!(decoded.lines(), vec!["[10:00] Grüße", "next"]);
+        }
+    }
+
+    #[test]
+    fn utf16_without_bom_is_recognized_by_its_nul_bytes() {
+        // [CSV-File-EncodingV1] Some Windows tools write UTF-16 without a BOM.
+        let le = utf16("INFO started\r\n", u16::to_le_bytes, &[]);
+        let be = utf16("INFO started\r\n", u16::to_be_bytes, &[]);
+
+        assert_eq!(detect_encoding(&le), TextEncoding::Utf16Le);
+        assert_eq!(detect_encoding(&be), TextEncoding::Utf16Be);
+        assert_eq!(decode_text(le, None).lines(), vec!["INFO started"]);
+    }
+
+    #[test]
+    fn latin1_logs_fall_back_to_windows1252() {
+        // [CSV-File-EncodingV1] "Grüße €5" as written by a Windows-1252 tool.
+        let bytes = b"Gr\xFC\xDFe \x805\n";
+
+        let decoded = decode_text(bytes.to_vec(), None);
+
+        assert_eq!(decoded.outcome().encoding(), TextEncoding::Windows1252);
+        assert!(!decoded.outcome().is_lossy());
+        assert_eq!(decoded.lines(), vec!["Grüße €5"]);
+    }
+
+    #[test]
+    fn stray_invalid_bytes_in_utf8_decode_lossily() {
+        // [CSV-File-EncodingV1] One corrupt byte must not turn the whole file into mojibake.
+        let bytes = "naïve café\n"
+            .bytes()
+            .chain([0xFF, b'\n'])
+            .collect::<Vec<_>>();
+
+        let decoded = decode_text(bytes, None);
+
+        assert_eq!(decoded.outcome().encoding(), TextEncoding::Utf8);
+        assert!(decoded.outcome().is_lossy());
+        assert_eq!(decoded.lines(), vec!["naïve café", "\u{FFFD}"]);
+    }
+
+    #[test]
+    fn valid_utf8_keeps_its_buffer() {
+        // [CSV-Core-LineStoreV1] Decoding a large UTF-8 file must not hold it twice.
+        let bytes = "Grüße\nnext\n".as_bytes().to_vec();
+        let buffer = bytes.as_ptr();
+
+        let decoded = decode_text(bytes, None);
+
+        assert!(!decoded.outcome().is_lossy());
+        assert_eq!(decoded.text().as_ptr(), buffer);
+    }
+
+    #[test]
+    fn only_plain_utf8_decodes_as_itself() {
+        // [CSV-Core-LineStoreV1] Such files are read without decoding them.
+        let bom = [UTF8_BOM, b"text"].concat();
+        let le = utf16("INFO started\r\n", u16::to_le_bytes, &[]);
+
+        assert!(decodes_as_itself("Grüße\n".as_bytes(), None));
+        assert!(decodes_as_itself(b"text", Some(TextEncoding::Utf8)));
+        assert!(!decodes_as_itself(&bom, None));
+        assert!(!decodes_as_itself(&le, None));
+        assert!(!decodes_as_itself(b"text", Some(TextEncoding::Windows1252)));
+    }
+
+    #[test]
+    fn override_replaces_detection() {
+        // [CSV-File-EncodingV1]
+        let bytes = "Grüße".as_bytes();
+
+        let decoded = decode_text(bytes.to_vec(), Some(TextEncoding::Windows1252));
+
+        assert!(!decoded.outcome().was_detected());
+        assert_eq!(decoded.text(), "GrÃ¼ÃŸe");
+        let truncated = decode_text(vec![b'a', 0, b'b'], Some(TextEncoding::Utf16Le));
+        assert!(truncated.outcome().is_lossy());
+        assert_eq!(truncated.text(), "a\u{FFFD}");
+    }
+}
@@ -0,0 +1,310 @@
+use regex::{Captures, Regex};
+use std::error::Error;
+use std::fmt;
+use time::format_description::{self, OwnedFormatItem};
+use time::parsing::Parsed;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::core::timestamp_parser::{STRIP_GROUP_NAME, replace_matches};
+
+/// Name of the capture group holding the text to parse, when the match contains more.
+pub const TIMESTAMP_GROUP_NAME: &str = "ts";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampExtractorError {
+    InvalidPattern { pattern: String, message: String },
+    InvalidFormat { format: String, message: String },
+}
+
+impl fmt::Display for TimestampExtractorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampExtractorError::InvalidPattern { pattern, message } => {
+                write!(f, "invalid timestamp pattern '{pattern}': {message}")
+            }
+            TimestampExtractorError::InvalidFormat { format, message } => {
+                write!(f, "invalid timestamp format '{format}': {message}")
+            }
+        }
+    }
+}
+
+impl Error for TimestampExtractorError {}
+
+/// Checks a format description without building an extractor; an empty format, meaning
+/// timestamps are not parsed, is valid.
+pub fn validate_timestamp_format(format: &str) -> Result<(), TimestampExtractorError> {
+    if format.is_empty() {
+        return Ok(());
+    }
+
+    format_description::parse_owned::<2>(format)
+        .map(|_| ())
+        .map_err(|e| TimestampExtractorError::InvalidFormat {
+            format: format.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// A line with its timestamp replaced as by `TimestampParserOperations`, plus the time
+/// value the timestamp stood for, if it could be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedLine {
+    stripped_text: String,
+    timestamp: Option<OffsetDateTime>,
+}
+
+impl ExtractedLine {
+    pub fn new(stripped_text: impl Into<String>, timestamp: Option<OffsetDateTime>) -> Self {
+        Self {
+            stripped_text: stripped_text.into(),
+            timestamp,
+        }
+    }
+
+    pub fn stripped_text(&self) -> &str {
+        &self.stripped_text
+    }
+
+    pub fn timestamp(&self) -> Option<OffsetDateTime> {
+        self.timestamp
+    }
+
+    pub fn into_parts(self) -> (String, Option<OffsetDateTime>) {
+        (self.stripped_text, self.timestamp)
+    }
+}
+
+/// Interprets timestamp matches as time values per [CSV-Core-TSExtractV1].
+///
+/// The parsed text is the `ts` capture group if the pattern has one, else the `strip`
+/// group, else the whole match. Formats lacking an offset use `default_offset`, and
+/// time-of-day-only formats additionally use `default_date`.
+pub struct TimestampExtractor {
+    regex: Regex,
+    format: OwnedFormatItem,
+    default_offset: UtcOffset,
+    default_date: Date,
+}
+
+impl TimestampExtractor {
+    /// `format` uses the `time` crate's format description syntax, for example
+    /// `[hour]:[minute]:[second].[subsecond]`.
+    pub fn new(pattern: &str, format: &str) -> Result<Self, TimestampExtractorError> {
+        let regex = Regex::new(pattern).map_err(|e| TimestampExtractorError::InvalidPattern {
+            pattern: pattern.to_string(),
+            message: e.to_string(),
+        })?;
+        let format = format_description::parse_owned::<2>(format).map_err(|e| {
+            TimestampExtractorError::InvalidFormat {
+                format: format.to_string(),
+                message: e.to_string(),
+            }
+        })?;
+
+        Ok(Self {
+            regex,
+            format,
+            default_offset: UtcOffset::UTC,
+            default_date: OffsetDateTime::UNIX_EPOCH.date(),
+        })
+    }
+
+    pub fn with_default_offset(mut self, default_offset: UtcOffset) -> Self {
+        self.default_offset = default_offset;
+        self
+    }
+
+    pub fn with_default_date(mut self, default_date: Date) -> Self {
+        self.default_date = default_date;
+        self
+    }
+
+    /// Parses the first timestamp found in `line`.
+    pub fn parse_line(&self, line: &str) -> Option<OffsetDateTime> {
+        let caps = self.regex.captures(line)?;
+        self.parse_timestamp(timestamp_text(&caps))
+    }
+
+    /// Parses `text` in full with the configured format.
+    pub fn parse_timestamp(&self, text: &str) -> Option<OffsetDateTime> {
+        let mut parsed = Parsed::new();
+        let remaining = parsed.parse_item(text.as_bytes(), &self.format).ok()?;
+        if !remaining.is_empty() {
+            return None;
+        }
+
+        if let Ok(date_time) = OffsetDateTime::try_from(parsed) {
+            return Some(date_time);
+        }
+        if let Ok(date_time) = PrimitiveDateTime::try_from(parsed) {
+            return Some(date_time.assume_offset(self.default_offset));
+        }
+        let time = Time::try_from(parsed).ok()?;
+        Some(PrimitiveDateTime::new(self.default_date, time).assume_offset(self.default_offset))
+    }
+
+    /// Strips each line like `TimestampParserOperations::strip_timestamps` with this
+    /// pattern and pairs it with its parsed timestamp.
+    pub fn extract_lines(&self, lines: &[String], replacement: &str) -> Vec<ExtractedLine> {
+        lines
+            .iter()
+            .map(|line| {
+                ExtractedLine::new(
+                    replace_matches(&self.regex, line, replacement).into_owned(),
+                    self.parse_line(line),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Parses the timestamp of every line with the first pattern of the list that yields one.
+/// Patterns that do not compile are skipped, and an empty `format` parses nothing.
+pub fn parse_line_timestamps(
+    lines: &[impl AsRef<str>],
+    patterns: &[String],
+    format: &str,
+) -> Vec<Option<OffsetDateTime>> {
+    let extractors: Vec<TimestampExtractor> = if format.is_empty() {
+        Vec::new()
+    } else {
+        patterns
+            .iter()
+            .filter(|pattern| !pattern.is_empty())
+            .filter_map(|pattern| match TimestampExtractor::new(pattern, format) {
+                Ok(extractor) => Some(extractor),
+                Err(err) => {
+                    log::debug!("[CSV-Core-TSExtractV1] Not parsing timestamps: {err}");
+                    None
+                }
+            })
+            .collect()
+    };
+
+    lines
+        .iter()
+        .map(|line| {
+            extractors
+                .iter()
+                .find_map(|extractor| extractor.parse_line(line.as_ref()))
+        })
+        .collect()
+}
+
+fn timestamp_text<'h>(caps: &Captures<'h>) -> &'h str {
+    caps.name(TIMESTAMP_GROUP_NAME)
+        .or_else(|| caps.name(STRIP_GROUP_NAME))
+        .or_else(|| caps.get(0))
+        .map_or("", |m| m.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime, offset};
+
+    #[test]
+    fn extracts_full_date_times_next_to_stripped_text() {
+        // [CSV-Core-TSExtractV1]
+        let extractor = TimestampExtractor::new(
+            r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2} ",
+            "[year]-[month]-[day] [hour]:[minute]:[second] ",
+        )
+        .unwrap();
+        let lines = vec![
+            "2024-03-01 10:00:05 DB connection successful".to_string(),
+            "continuation without time".to_string(),
+        ];
+
+        let extracted = extractor.extract_lines(&lines, "");
+
+        assert_eq!(
+            extracted,
+            vec![
+                ExtractedLine::new(
+                    "DB connection successful",
+                    Some(datetime!(2024-03-01 10:00:05 UTC))
+                ),
+                ExtractedLine::new("continuation without time", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn time_of_day_formats_use_the_default_date_and_offset() {
+        // Bracketed times as in tests/log_file_A.txt.
+        let extractor = TimestampExtractor::new(
+            r"^\[(?P<ts>[^\]]+)\] ",
+            "[hour]:[minute]:[second].[subsecond]",
+        )
+        .unwrap()
+        .with_default_date(date!(2024 - 03 - 01))
+        .with_default_offset(offset!(+1));
+
+        assert_eq!(
+            extractor.parse_line("[10:00:05.250] started"),
+            Some(datetime!(2024-03-01 10:00:05.25 +1))
+        );
+    }
+
+    #[test]
+    fn explicit_offsets_are_kept() {
+        let extractor = TimestampExtractor::new(
+            r"^\S+",
+            "[year]-[month]-[day]T[hour]:[minute]:[second][offset_hour]:[offset_minute]",
+        )
+        .unwrap();
+
+        assert_eq!(
+            extractor.parse_line("2024-03-01T10:00:05+02:00 ready"),
+            Some(datetime!(2024-03-01 10:00:05 +2))
+        );
+    }
+
+    #[test]
+    fn text_the_format_does_not_fully_consume_is_not_a_timestamp() {
+        let extractor = TimestampExtractor::new(r"^\S+", "[hour]:[minute]").unwrap();
+
+        assert_eq!(extractor.parse_line("10:00:05 ready"), None);
+        assert_eq!(extractor.parse_line("ready"), None);
+    }
+
+    #[test]
+    fn invalid_format_is_reported() {
+        let result = TimestampExtractor::new(r"\d+", "[hour");
+
+        assert!(matches!(
+            result,
+            Err(TimestampExtractorError::InvalidFormat { .. })
+        ));
+        assert!(validate_timestamp_format("[hour").is_err());
+        assert!(validate_timestamp_format("").is_ok());
+    }
+
+    #[test]
+    fn line_timestamps_use_the_first_pattern_that_parses() {
+        // [CSV-Core-TSExtractV1][CSV-Core-TSPatternListV1]
+        let lines = vec![
+            "[10:00:05] old build".to_string(),
+            "at 10:00:07 new build".to_string(),
+            "no time".to_string(),
+        ];
+        let patterns = vec![
+            r"^\[(?P<ts>[^\]]+)\]".to_string(),
+            r"at (?P<ts>\S+)".to_string(),
+        ];
+
+        let timestamps = parse_line_timestamps(&lines, &patterns, "[hour]:[minute]:[second]");
+
+        assert_eq!(
+            timestamps,
+            vec![
+                Some(datetime!(1970-01-01 10:00:05 UTC)),
+                Some(datetime!(1970-01-01 10:00:07 UTC)),
+                None,
+            ]
+        );
+        assert_eq!(parse_line_timestamps(&lines, &patterns, ""), vec![None; 3]);
+    }
+}
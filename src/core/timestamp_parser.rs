@@ -1,20 +1,141 @@
-use regex::Regex;
-use std::collections::HashMap;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
-use std::sync::RwLock;
+use std::sync::Mutex;
+
+use crate::core::line_store::LineText;
+use crate::core::regex_cache::{RegexCache, RegexCacheStats};
+
+/// Characters of a pattern an error points at, per [CSV-UX-RegexDiagnosticsV1]. Columns
+/// are one-based and count characters; `end_column` is exclusive, so an error at the end
+/// of the pattern has an empty span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternSpan {
+    start_column: usize,
+    end_column: usize,
+}
+
+impl PatternSpan {
+    pub fn new(start_column: usize, end_column: usize) -> Self {
+        Self {
+            start_column,
+            end_column,
+        }
+    }
+
+    pub fn start_column(&self) -> usize {
+        self.start_column
+    }
+
+    pub fn end_column(&self) -> usize {
+        self.end_column
+    }
+}
+
+impl From<&regex_syntax::ast::Span> for PatternSpan {
+    fn from(span: &regex_syntax::ast::Span) -> Self {
+        Self::new(span.start.column, span.end.column)
+    }
+}
+
+/// Why a pattern does not compile, per [CSV-UX-RegexDiagnosticsV1].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternErrorKind {
+    /// The pattern is not a valid regular expression. `description` is the short reason,
+    /// e.g. `unclosed group`, and `span` where in the pattern it was found, when known.
+    Syntax {
+        description: String,
+        span: Option<PatternSpan>,
+    },
+    /// The pattern is valid but compiles to more than `size_limit` bytes.
+    TooBig { size_limit: usize },
+}
+
+impl PatternErrorKind {
+    /// Classifies `error`. The `regex` crate only reports syntax errors as text, so the
+    /// pattern is parsed again with `regex-syntax` to recover the reason and its position.
+    pub fn from_regex_error(pattern: &str, error: &regex::Error) -> Self {
+        if let regex::Error::CompiledTooBig(size_limit) = error {
+            return Self::TooBig {
+                size_limit: *size_limit,
+            };
+        }
+
+        match regex_syntax::Parser::new().parse(pattern) {
+            Err(regex_syntax::Error::Parse(err)) => Self::Syntax {
+                description: err.kind().to_string(),
+                span: Some(PatternSpan::from(err.span())),
+            },
+            Err(regex_syntax::Error::Translate(err)) => Self::Syntax {
+                description: err.kind().to_string(),
+                span: Some(PatternSpan::from(err.span())),
+            },
+            _ => Self::Syntax {
+                description: last_error_line(&error.to_string()).to_string(),
+                span: None,
+            },
+        }
+    }
+
+    pub fn span(&self) -> Option<PatternSpan> {
+        match self {
+            Self::Syntax { span, .. } => *span,
+            Self::TooBig { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PatternErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax {
+                description,
+                span: Some(span),
+            } => write!(f, "{description} at column {}", span.start_column()),
+            Self::Syntax {
+                description,
+                span: None,
+            } => write!(f, "{description}"),
+            Self::TooBig { size_limit } => {
+                write!(f, "pattern is too large (limit {size_limit} bytes)")
+            }
+        }
+    }
+}
+
+/// Regex error messages span several lines with the pattern and a caret; the last line
+/// holds the reason.
+fn last_error_line(message: &str) -> &str {
+    message
+        .lines()
+        .last()
+        .map(|line| line.trim().trim_start_matches("error: "))
+        .unwrap_or(message)
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TimestampParserError {
-    InvalidPattern { pattern: String, message: String },
-    ProcessingFailed { message: String },
+    /// `index` is the position of the offending entry in the pattern list.
+    InvalidPattern {
+        index: usize,
+        pattern: String,
+        message: String,
+        kind: PatternErrorKind,
+    },
+    ProcessingFailed {
+        message: String,
+    },
 }
 
 impl TimestampParserError {
-    pub fn invalid_pattern(pattern: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn invalid_pattern(index: usize, pattern: impl Into<String>, error: &regex::Error) -> Self {
+        let pattern = pattern.into();
         Self::InvalidPattern {
-            pattern: pattern.into(),
-            message: message.into(),
+            index,
+            kind: PatternErrorKind::from_regex_error(&pattern, error),
+            pattern,
+            message: error.to_string(),
         }
     }
 
@@ -28,9 +149,16 @@ impl TimestampParserError {
 impl fmt::Display for TimestampParserError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TimestampParserError::InvalidPattern { pattern, message } => {
-                write!(f, "invalid timestamp pattern '{pattern}': {message}")
-            }
+            TimestampParserError::InvalidPattern {
+                index,
+                pattern,
+                message,
+                ..
+            } => write!(
+                f,
+                "invalid timestamp pattern #{} '{pattern}': {message}",
+                index + 1
+            ),
             TimestampParserError::ProcessingFailed { message } => {
                 write!(f, "failed to strip timestamps: {message}")
             }
@@ -40,76 +168,286 @@ impl fmt::Display for TimestampParserError {
 
 impl Error for TimestampParserError {}
 
+/// Name of the capture group that, when present, limits the replaced text to its span.
+pub const STRIP_GROUP_NAME: &str = "strip";
+
 pub trait TimestampParserOperations: Send + Sync {
+    /// Replaces every match of each pattern, applied in list order, per
+    /// [CSV-Core-TSPatternListV1]. Empty patterns are skipped.
+    ///
+    /// An empty `replacement` removes the matches; otherwise it is substituted with
+    /// `$name` references expanded from the match's capture groups per [CSV-Core-TSReplaceV1].
+    ///
+    /// Lines without a match are returned as they are, still sharing their `LineStore`
+    /// per [CSV-Core-LineStoreV1].
     fn strip_timestamps(
         &self,
-        lines: &[String],
-        pattern: &str,
-    ) -> Result<Vec<String>, TimestampParserError>;
+        lines: &[LineText],
+        patterns: &[String],
+        replacement: &str,
+    ) -> Result<Vec<LineText>, TimestampParserError>;
+
+    /// Keeps at most `capacity` compiled patterns per [CSV-Core-RegexCacheV2]; zero
+    /// disables caching. Parsers without a cache ignore it.
+    fn set_cache_capacity(&self, _capacity: usize) {}
+}
+
+/// Splits the text of a timestamp input into its patterns, one per non-blank line.
+pub fn split_pattern_list(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Checks every pattern without touching any parser cache, reporting the first invalid
+/// entry per [CSV-UX-TimestampFeedbackV3].
+pub fn validate_pattern_list(patterns: &[String]) -> Result<(), TimestampParserError> {
+    for (index, pattern) in patterns.iter().enumerate() {
+        if let Err(e) = Regex::new(pattern) {
+            return Err(TimestampParserError::invalid_pattern(
+                index,
+                pattern.as_str(),
+                &e,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lines sampled from the start of each file when detecting the timestamp format.
+pub const TIMESTAMP_DETECTION_SAMPLE_LINES: usize = 50;
+
+/// Smallest share of the sampled non-blank lines a format must match to be proposed.
+const MIN_DETECTION_COVERAGE: f64 = 0.5;
+
+/// A well-known timestamp format: a pattern that strips it, with the timestamp in a `ts`
+/// group, and the `time` format description that parses that group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampFormatCandidate {
+    name: &'static str,
+    pattern: &'static str,
+    format: &'static str,
+}
+
+impl TimestampFormatCandidate {
+    pub const fn new(name: &'static str, pattern: &'static str, format: &'static str) -> Self {
+        Self {
+            name,
+            pattern,
+            format,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn pattern(&self) -> &'static str {
+        self.pattern
+    }
+
+    pub fn format(&self) -> &'static str {
+        self.format
+    }
+}
+
+/// Built-in formats tried by `detect_timestamp_pattern` per [CSV-Core-TSDetectV1]. More
+/// specific formats come first, since ties go to the earlier entry.
+pub const TIMESTAMP_FORMAT_LIBRARY: &[TimestampFormatCandidate] = &[
+    TimestampFormatCandidate::new(
+        "Bracketed date and time",
+        r"^\[(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d{3})\]\s*",
+        "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]",
+    ),
+    TimestampFormatCandidate::new(
+        "Bracketed time of day",
+        r"^\[(?P<ts>\d{2}:\d{2}:\d{2}\.\d{3})\]\s*",
+        "[hour]:[minute]:[second].[subsecond]",
+    ),
+    TimestampFormatCandidate::new(
+        "ISO 8601",
+        r"^(?P<ts>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?)\s*",
+        "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]\
+         [optional [[first [Z][[offset_hour sign:mandatory]:[offset_minute]]]]]",
+    ),
+    TimestampFormatCandidate::new(
+        "log4j",
+        r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2},\d{3})\s*",
+        "[year]-[month]-[day] [hour]:[minute]:[second],[subsecond]",
+    ),
+    TimestampFormatCandidate::new(
+        "Date and time",
+        r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)?)\s*",
+        "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]",
+    ),
+    TimestampFormatCandidate::new(
+        "Syslog",
+        r"^(?P<ts>(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) [ \d]\d \d{2}:\d{2}:\d{2})\s+",
+        "[month repr:short] [day padding:space] [hour]:[minute]:[second]",
+    ),
+    TimestampFormatCandidate::new(
+        "Windows event log",
+        r"^(?P<ts>\d{1,2}/\d{1,2}/\d{4} \d{1,2}:\d{2}:\d{2} [AP]M)\s*",
+        "[month padding:none]/[day padding:none]/[year] \
+         [hour repr:12 padding:none]:[minute]:[second] [period]",
+    ),
+    TimestampFormatCandidate::new(
+        "Epoch milliseconds",
+        r"^(?P<ts>\d{13})\b\s*",
+        "[unix_timestamp precision:millisecond]",
+    ),
+];
+
+/// Picks the library format matching the most of `lines`, typically the first
+/// `TIMESTAMP_DETECTION_SAMPLE_LINES` of both files, per [CSV-Core-TSDetectV1]. Returns
+/// `None` when no format matches at least half of the non-blank lines.
+pub fn detect_timestamp_pattern(lines: &[String]) -> Option<&'static TimestampFormatCandidate> {
+    let samples: Vec<&str> = lines
+        .iter()
+        .map(String::as_str)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<(&'static TimestampFormatCandidate, usize)> = None;
+    for candidate in TIMESTAMP_FORMAT_LIBRARY {
+        let regex = Regex::new(candidate.pattern()).expect("library patterns are valid");
+        let matched = samples.iter().filter(|line| regex.is_match(line)).count();
+        if matched > best.map_or(0, |(_, best_matched)| best_matched) {
+            best = Some((candidate, matched));
+        }
+    }
+
+    best.filter(|&(_, matched)| matched as f64 >= samples.len() as f64 * MIN_DETECTION_COVERAGE)
+        .map(|(candidate, _)| candidate)
 }
 
 pub struct CoreTimestampParser {
-    cache: RwLock<HashMap<String, Regex>>,
+    cache: Mutex<RegexCache>,
 }
 
 impl CoreTimestampParser {
     pub fn new() -> Self {
         Self {
-            cache: RwLock::new(HashMap::new()),
+            cache: Mutex::new(RegexCache::default()),
         }
     }
 
+    /// Hit, miss and eviction counts of the compiled pattern cache.
+    pub fn cache_stats(&self) -> RegexCacheStats {
+        self.cache.lock().unwrap().stats()
+    }
+
     #[cfg(test)]
     fn cache_len(&self) -> usize {
-        self.cache.read().unwrap().len()
+        self.cache_stats().len()
+    }
+
+    #[cfg(test)]
+    fn is_cached(&self, pattern: &str) -> bool {
+        self.cache.lock().unwrap().contains(pattern)
+    }
+
+    fn compiled(&self, index: usize, pattern: &str) -> Result<Regex, TimestampParserError> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get_or_compile(pattern, |pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| TimestampParserError::invalid_pattern(index, pattern, &e))
+            })
     }
 }
 
 impl TimestampParserOperations for CoreTimestampParser {
     fn strip_timestamps(
         &self,
-        lines: &[String],
-        pattern: &str,
-    ) -> Result<Vec<String>, TimestampParserError> {
-        if pattern.is_empty() {
+        lines: &[LineText],
+        patterns: &[String],
+        replacement: &str,
+    ) -> Result<Vec<LineText>, TimestampParserError> {
+        // Compile every entry up front so an invalid one fails before any work is done.
+        let regexes = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| !pattern.is_empty())
+            .map(|(index, pattern)| self.compiled(index, pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if regexes.is_empty() {
             return Ok(lines.to_vec());
         }
 
-        let regex = {
-            if let Some(cached) = self.cache.read().unwrap().get(pattern) {
-                cached.clone()
-            } else {
-                let compiled = Regex::new(pattern)
-                    .map_err(|e| TimestampParserError::invalid_pattern(pattern, e.to_string()))?;
-                let mut cache = self.cache.write().unwrap();
-                cache.entry(pattern.to_string()).or_insert(compiled).clone()
-            }
-        };
-
         let stripped_lines = lines
             .iter()
-            .map(|line| regex.replace_all(line, "").to_string())
+            .map(|line| {
+                let mut text = Cow::Borrowed(line.as_str());
+                for regex in &regexes {
+                    if let Cow::Owned(replaced) = replace_matches(regex, &text, replacement) {
+                        text = Cow::Owned(replaced);
+                    }
+                }
+                match text {
+                    Cow::Borrowed(_) => line.clone(),
+                    Cow::Owned(stripped) => LineText::from(stripped),
+                }
+            })
             .collect();
 
         Ok(stripped_lines)
     }
+
+    fn set_cache_capacity(&self, capacity: usize) {
+        self.cache.lock().unwrap().set_capacity(capacity);
+    }
+}
+
+/// Replaces each match of `regex` in `text`. With a `strip` group only that group's span is
+/// replaced and the rest of the match is kept; matches where the group did not take part
+/// are left alone.
+pub(crate) fn replace_matches<'t>(regex: &Regex, text: &'t str, replacement: &str) -> Cow<'t, str> {
+    let has_strip_group = regex
+        .capture_names()
+        .any(|name| name == Some(STRIP_GROUP_NAME));
+    if !has_strip_group {
+        return regex.replace_all(text, replacement);
+    }
+
+    regex.replace_all(text, |caps: &Captures<'_>| {
+        let whole = caps.get(0).expect("group 0 always participates");
+        let Some(strip) = caps.name(STRIP_GROUP_NAME) else {
+            return whole.as_str().to_string();
+        };
+
+        let mut replaced = text[whole.start()..strip.start()].to_string();
+        caps.expand(replacement, &mut replaced);
+        replaced.push_str(&text[strip.end()..whole.end()]);
+        replaced
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::TimestampExtractor;
+    use crate::core::regex_cache::DEFAULT_REGEX_CACHE_CAPACITY;
 
     #[test]
     fn test_valid_pattern_strips_timestamps() {
         let parser = CoreTimestampParser::new();
-        let lines = vec![
-            "[2023-10-27 10:00:00] INFO: System start".to_string(),
-            "DEBUG: No timestamp here".to_string(),
-            "[2023-10-27 10:00:01] WARN: System alert".to_string(),
+        let lines: Vec<LineText> = vec![
+            "[2023-10-27 10:00:00] INFO: System start".into(),
+            "DEBUG: No timestamp here".into(),
+            "[2023-10-27 10:00:01] WARN: System alert".into(),
         ];
         let pattern = r"\[\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\] ";
 
-        let result = parser.strip_timestamps(&lines, pattern).unwrap();
+        let result = parser
+            .strip_timestamps(&lines, &[pattern.to_string()], "")
+            .unwrap();
 
         assert_eq!(result[0], "INFO: System start");
         assert_eq!(result[1], "DEBUG: No timestamp here");
@@ -119,26 +457,65 @@ mod tests {
     #[test]
     fn test_invalid_regex_pattern_returns_error() {
         let parser = CoreTimestampParser::new();
-        let lines = vec!["line 1".to_string()];
+        let lines: Vec<LineText> = vec!["line 1".into()];
         let invalid_pattern = "[";
 
-        let result = parser.strip_timestamps(&lines, invalid_pattern);
+        let result = parser.strip_timestamps(&lines, &[invalid_pattern.to_string()], "");
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            TimestampParserError::InvalidPattern { pattern, .. } => {
+            TimestampParserError::InvalidPattern { index, pattern, .. } => {
+                assert_eq!(index, 0);
                 assert_eq!(pattern, invalid_pattern);
             }
             other => panic!("Expected InvalidPattern error, got {other:?}"),
         }
     }
 
+    #[test]
+    fn test_invalid_pattern_reports_reason_and_column() {
+        // [CSV-UX-RegexDiagnosticsV1]
+        let patterns = vec![r"^\d+ ".to_string(), r"ab(c".to_string()];
+
+        let error = validate_pattern_list(&patterns).unwrap_err();
+
+        let TimestampParserError::InvalidPattern { index, kind, .. } = error else {
+            panic!("Expected InvalidPattern error, got {error:?}");
+        };
+        assert_eq!(index, 1);
+        assert_eq!(
+            kind,
+            PatternErrorKind::Syntax {
+                description: "unclosed group".to_string(),
+                span: Some(PatternSpan::new(3, 4)),
+            }
+        );
+        assert_eq!(kind.to_string(), "unclosed group at column 3");
+    }
+
+    #[test]
+    fn test_error_columns_count_characters_and_oversized_patterns_are_told_apart() {
+        // [CSV-UX-RegexDiagnosticsV1] Unknown Unicode classes fail after parsing.
+        let pattern = r"é\p{Nope}";
+        let error = Regex::new(pattern).unwrap_err();
+
+        let kind = PatternErrorKind::from_regex_error(pattern, &error);
+
+        assert_eq!(kind.span().map(|span| span.start_column()), Some(2));
+        assert_eq!(
+            PatternErrorKind::from_regex_error("a", &regex::Error::CompiledTooBig(1_024)),
+            PatternErrorKind::TooBig { size_limit: 1_024 }
+        );
+    }
+
     #[test]
     fn test_empty_pattern_returns_original_lines() {
         let parser = CoreTimestampParser::new();
-        let lines = vec!["line 1".to_string(), "line 2".to_string()];
+        let lines: Vec<LineText> = vec!["line 1".into(), "line 2".into()];
 
-        let result = parser.strip_timestamps(&lines, "").unwrap();
+        let result = parser
+            .strip_timestamps(&lines, &[String::new()], "")
+            .unwrap();
 
         assert_eq!(result, lines);
         assert_eq!(parser.cache_len(), 0);
@@ -147,10 +524,12 @@ mod tests {
     #[test]
     fn test_no_matches_returns_original_lines() {
         let parser = CoreTimestampParser::new();
-        let lines = vec!["line 1".to_string(), "another line".to_string()];
+        let lines: Vec<LineText> = vec!["line 1".into(), "another line".into()];
         let pattern = "xyz";
 
-        let result = parser.strip_timestamps(&lines, pattern).unwrap();
+        let result = parser
+            .strip_timestamps(&lines, &[pattern.to_string()], "")
+            .unwrap();
 
         assert_eq!(result, lines);
         assert_eq!(parser.cache_len(), 1);
@@ -159,27 +538,256 @@ mod tests {
     #[test]
     fn test_regex_is_cached_after_first_use() {
         let parser = CoreTimestampParser::new();
-        let lines = vec![
-            "[10:00] entry".to_string(),
-            "[10:01] another".to_string(),
-            "no timestamp".to_string(),
+        let lines: Vec<LineText> = vec![
+            "[10:00] entry".into(),
+            "[10:01] another".into(),
+            "no timestamp".into(),
         ];
         let pattern = r"\[\d{2}:\d{2}\] ";
 
-        let result_one = parser.strip_timestamps(&lines, pattern).unwrap();
+        let result_one = parser
+            .strip_timestamps(&lines, &[pattern.to_string()], "")
+            .unwrap();
         assert_eq!(parser.cache_len(), 1);
 
-        let result_two = parser.strip_timestamps(&lines, pattern).unwrap();
+        let result_two = parser
+            .strip_timestamps(&lines, &[pattern.to_string()], "")
+            .unwrap();
         assert_eq!(parser.cache_len(), 1, "pattern should remain cached");
         assert_eq!(result_one, result_two);
     }
 
+    #[test]
+    fn test_cache_evicts_least_recently_used_pattern_when_full() {
+        // [CSV-Core-RegexCacheV2] Patterns typed character by character must not pile up.
+        let parser = CoreTimestampParser::new();
+        parser.set_cache_capacity(2);
+        let lines: Vec<LineText> = vec!["[10:00] entry".into()];
+        let strip = |pattern: &str| {
+            parser
+                .strip_timestamps(&lines, &[pattern.to_string()], "")
+                .unwrap()
+        };
+
+        strip(r"\[");
+        strip(r"\[\d");
+        strip(r"\[");
+        strip(r"\[\d{2}");
+
+        assert_eq!(parser.cache_len(), 2);
+        assert!(parser.is_cached(r"\["), "recently used pattern is kept");
+        assert!(parser.is_cached(r"\[\d{2}"));
+        assert!(
+            !parser.is_cached(r"\[\d"),
+            "least recently used pattern is evicted"
+        );
+        assert_eq!(parser.cache_stats().evictions(), 1);
+
+        // A smaller capacity from the settings keeps only the most recently used.
+        parser.set_cache_capacity(1);
+        assert!(parser.is_cached(r"\[\d{2}"));
+        assert_eq!(parser.cache_len(), 1);
+        assert_eq!(parser.cache_stats().capacity(), 1);
+        assert_eq!(parser.cache_stats().evictions(), 2);
+    }
+
+    #[test]
+    fn test_cache_reports_hits_and_misses() {
+        // [CSV-Core-RegexCacheV2]
+        let parser = CoreTimestampParser::new();
+        let lines: Vec<LineText> = vec!["entry".into()];
+        let patterns = vec!["^a".to_string(), "^b".to_string()];
+
+        parser.strip_timestamps(&lines, &patterns, "").unwrap();
+        parser.strip_timestamps(&lines, &patterns, "").unwrap();
+        let _ = parser.strip_timestamps(&lines, &["(".to_string()], "");
+
+        let stats = parser.cache_stats();
+        assert_eq!(stats.hits(), 2);
+        assert_eq!(
+            stats.misses(),
+            3,
+            "a pattern that fails to compile is a miss"
+        );
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.capacity(), DEFAULT_REGEX_CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn test_zero_capacity_disables_the_cache() {
+        let parser = CoreTimestampParser::new();
+        parser.set_cache_capacity(0);
+        let lines: Vec<LineText> = vec!["[10:00] entry".into()];
+
+        for _ in 0..2 {
+            let stripped = parser
+                .strip_timestamps(&lines, &[r"\[[^\]]+\] ".to_string()], "")
+                .unwrap();
+            assert_eq!(stripped, vec!["entry".to_string()]);
+        }
+
+        assert_eq!(parser.cache_len(), 0);
+        assert_eq!(parser.cache_stats().misses(), 2);
+    }
+
     #[test]
     fn test_invalid_pattern_does_not_pollute_cache() {
         let parser = CoreTimestampParser::new();
-        let lines = vec!["entry".to_string()];
+        let lines: Vec<LineText> = vec!["entry".into()];
 
-        assert!(parser.strip_timestamps(&lines, "[").is_err());
+        assert!(
+            parser
+                .strip_timestamps(&lines, &["[".to_string()], "")
+                .is_err()
+        );
         assert_eq!(parser.cache_len(), 0);
     }
+
+    #[test]
+    fn test_pattern_list_strips_each_format_in_order() {
+        // [CSV-Core-TSPatternListV1] Old and new builds log different timestamp formats.
+        let parser = CoreTimestampParser::new();
+        let lines: Vec<LineText> = vec![
+            "[10:00:00] old format".into(),
+            "2024-01-02T10:00:00Z new format".into(),
+        ];
+        let patterns = vec![
+            r"^\[\d{2}:\d{2}:\d{2}\] ".to_string(),
+            String::new(),
+            r"^\d{4}-\d{2}-\d{2}T\S+ ".to_string(),
+        ];
+
+        let result = parser.strip_timestamps(&lines, &patterns, "").unwrap();
+
+        assert_eq!(result, vec!["old format", "new format"]);
+        assert_eq!(parser.cache_len(), 2, "each pattern is cached on its own");
+    }
+
+    #[test]
+    fn test_invalid_entry_in_list_reports_its_index() {
+        // [CSV-UX-TimestampFeedbackV3]
+        let parser = CoreTimestampParser::new();
+        let patterns = vec![r"\d+".to_string(), "(".to_string()];
+
+        let error = parser
+            .strip_timestamps(&[LineText::from("entry")], &patterns, "")
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            TimestampParserError::InvalidPattern { index: 1, .. }
+        ));
+        assert_eq!(validate_pattern_list(&patterns), Err(error));
+    }
+
+    #[test]
+    fn test_split_pattern_list_skips_blank_lines() {
+        assert_eq!(
+            split_pattern_list("\\d+\r\n\r\n  \r\n\\[.*\\]"),
+            vec!["\\d+".to_string(), "\\[.*\\]".to_string()]
+        );
+        assert!(split_pattern_list("").is_empty());
+    }
+
+    #[test]
+    fn test_placeholder_keeps_lines_with_and_without_timestamps_apart() {
+        // [CSV-Core-TSReplaceV1]
+        let parser = CoreTimestampParser::new();
+        let lines: Vec<LineText> = vec!["[10:00] x".into(), "x".into()];
+        let patterns = vec![r"\[\d{2}:\d{2}\]".to_string()];
+
+        let result = parser.strip_timestamps(&lines, &patterns, "<TS>").unwrap();
+
+        assert_eq!(result, vec!["<TS> x", "x"]);
+    }
+
+    #[test]
+    fn test_placeholder_expands_named_groups() {
+        // [CSV-Core-TSReplaceV1]
+        let parser = CoreTimestampParser::new();
+        let lines: Vec<LineText> = vec!["10:00:00 UTC started".into()];
+        let patterns = vec![r"\d{2}:\d{2}:\d{2} (?P<zone>[A-Z]+)".to_string()];
+
+        let result = parser
+            .strip_timestamps(&lines, &patterns, "<TS ${zone}>")
+            .unwrap();
+
+        assert_eq!(result, vec!["<TS UTC> started"]);
+    }
+
+    #[test]
+    fn test_strip_group_limits_the_replaced_span() {
+        // [CSV-Core-TSReplaceV1] Only the time inside the brackets goes; the level stays.
+        let parser = CoreTimestampParser::new();
+        let lines: Vec<LineText> = vec!["[10:00:00 INFO] ready".into(), "[INFO] no time".into()];
+        let patterns = vec![r"\[(?P<strip>\d{2}:\d{2}:\d{2} )?INFO\]".to_string()];
+
+        let removed = parser.strip_timestamps(&lines, &patterns, "").unwrap();
+        let replaced = parser.strip_timestamps(&lines, &patterns, "<TS> ").unwrap();
+
+        assert_eq!(removed, vec!["[INFO] ready", "[INFO] no time"]);
+        assert_eq!(replaced, vec!["[<TS> INFO] ready", "[INFO] no time"]);
+    }
+
+    fn sample(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn detection_picks_the_bracketed_format_of_the_test_logs() {
+        // [CSV-Core-TSDetectV1]
+        let lines = include_str!("../../tests/log_file_A.txt");
+        let lines: Vec<String> = lines
+            .lines()
+            .take(TIMESTAMP_DETECTION_SAMPLE_LINES)
+            .map(str::to_string)
+            .collect();
+
+        let detected = detect_timestamp_pattern(&lines).unwrap();
+
+        assert_eq!(detected.name(), "Bracketed date and time");
+        let stripped = CoreTimestampParser::new()
+            .strip_timestamps(
+                &[LineText::from(lines[0].as_str())],
+                &[detected.pattern().to_string()],
+                "",
+            )
+            .unwrap();
+        assert_eq!(stripped, vec!["INFO: Application starting up..."]);
+    }
+
+    #[test]
+    fn detection_recognizes_each_library_format() {
+        // [CSV-Core-TSDetectV1]
+        let cases = [
+            ("ISO 8601", "2024-03-01T10:00:05.123Z started"),
+            ("log4j", "2024-03-01 10:00:05,123 INFO started"),
+            ("Date and time", "2024-03-01 10:00:05 started"),
+            ("Syslog", "Mar  1 10:00:05 host app[42]: started"),
+            ("Windows event log", "3/1/2024 10:00:05 AM started"),
+            ("Epoch milliseconds", "1709287205123 started"),
+            ("Bracketed time of day", "[10:00:05.123] started"),
+        ];
+
+        for (name, line) in cases {
+            let detected = detect_timestamp_pattern(&sample(&[line])).unwrap();
+            assert_eq!(detected.name(), name);
+            // [CSV-Core-TSExtractV1] The proposed format parses what the pattern captured.
+            let extractor = TimestampExtractor::new(detected.pattern(), detected.format()).unwrap();
+            assert!(extractor.parse_line(line).is_some(), "{name} did not parse");
+        }
+    }
+
+    #[test]
+    fn detection_needs_the_format_to_cover_most_lines() {
+        let lines = sample(&[
+            "2024-03-01 10:00:05,123 INFO started",
+            "plain line",
+            "",
+            "another plain line",
+        ]);
+
+        assert_eq!(detect_timestamp_pattern(&lines), None);
+        assert_eq!(detect_timestamp_pattern(&sample(&["", "  "])), None);
+    }
 }
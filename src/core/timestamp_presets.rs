@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::core::timestamp_extractor::validate_timestamp_format;
+use crate::core::timestamp_parser::{split_pattern_list, validate_pattern_list};
+
+/// A named timestamp pattern list with its time format per [CSV-Core-TSPresetsV1].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TimestampPreset {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    format: String,
+}
+
+impl TimestampPreset {
+    pub fn new(name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            format: String::new(),
+        }
+    }
+
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Timestamp patterns, one per line, as in the timestamp input.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// Checks that the preset is named and that its patterns and format compile.
+    pub fn validate(&self) -> Result<(), TimestampPresetError> {
+        let invalid = |message: String| TimestampPresetError::InvalidPreset {
+            name: self.name.clone(),
+            message,
+        };
+
+        if self.name.trim().is_empty() {
+            return Err(invalid("the preset has no name".to_string()));
+        }
+        validate_pattern_list(&split_pattern_list(&self.pattern))
+            .map_err(|err| invalid(err.to_string()))?;
+        validate_timestamp_format(&self.format).map_err(|err| invalid(err.to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub enum TimestampPresetError {
+    Io(io::Error),
+    InvalidJson(serde_json::Error),
+    InvalidPreset { name: String, message: String },
+}
+
+impl fmt::Display for TimestampPresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampPresetError::Io(err) => write!(f, "{err}"),
+            TimestampPresetError::InvalidJson(err) => write!(f, "not a preset file: {err}"),
+            TimestampPresetError::InvalidPreset { name, message } => {
+                write!(f, "preset '{name}' is invalid: {message}")
+            }
+        }
+    }
+}
+
+impl Error for TimestampPresetError {}
+
+/// On-disk shape of a shared preset file.
+#[derive(Serialize, Deserialize)]
+struct TimestampPresetFile {
+    presets: Vec<TimestampPreset>,
+}
+
+/// Serializes `presets` as the JSON document teams share per [CSV-Core-TSPresetsV1].
+pub fn presets_to_json(presets: &[TimestampPreset]) -> Result<String, TimestampPresetError> {
+    let file = TimestampPresetFile {
+        presets: presets.to_vec(),
+    };
+    serde_json::to_string_pretty(&file).map_err(TimestampPresetError::InvalidJson)
+}
+
+/// Parses a preset document, rejecting it as a whole if any preset is invalid.
+pub fn presets_from_json(json: &str) -> Result<Vec<TimestampPreset>, TimestampPresetError> {
+    let file: TimestampPresetFile =
+        serde_json::from_str(json).map_err(TimestampPresetError::InvalidJson)?;
+    for preset in &file.presets {
+        preset.validate()?;
+    }
+    Ok(file.presets)
+}
+
+pub fn export_presets(
+    path: &Path,
+    presets: &[TimestampPreset],
+) -> Result<(), TimestampPresetError> {
+    fs::write(path, presets_to_json(presets)?).map_err(TimestampPresetError::Io)
+}
+
+pub fn import_presets(path: &Path) -> Result<Vec<TimestampPreset>, TimestampPresetError> {
+    let json = fs::read_to_string(path).map_err(TimestampPresetError::Io)?;
+    presets_from_json(&json)
+}
+
+/// Adds `preset` to `presets`, replacing the one with the same name, if any, in place.
+pub fn upsert_preset(presets: &mut Vec<TimestampPreset>, preset: TimestampPreset) {
+    match presets
+        .iter_mut()
+        .find(|existing| existing.name() == preset.name())
+    {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team_presets() -> Vec<TimestampPreset> {
+        vec![
+            TimestampPreset::new(
+                "log4j",
+                r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2},\d{3})\s*",
+            )
+            .with_format("[year]-[month]-[day] [hour]:[minute]:[second],[subsecond]"),
+            TimestampPreset::new("nginx access", r"\[\d{2}/\w{3}/\d{4}:[^\]]+\] "),
+        ]
+    }
+
+    #[test]
+    fn presets_round_trip_through_json() {
+        // [CSV-Core-TSPresetsV1]
+        let presets = team_presets();
+
+        let json = presets_to_json(&presets).unwrap();
+
+        assert!(json.contains("\"presets\""));
+        assert_eq!(presets_from_json(&json).unwrap(), presets);
+    }
+
+    #[test]
+    fn presets_without_format_can_be_imported() {
+        let json = r#"{ "presets": [ { "name": "syslog", "pattern": "^\\w{3} [ \\d]\\d " } ] }"#;
+
+        let presets = presets_from_json(json).unwrap();
+
+        assert_eq!(
+            presets,
+            vec![TimestampPreset::new("syslog", r"^\w{3} [ \d]\d ")]
+        );
+    }
+
+    #[test]
+    fn import_rejects_documents_with_an_invalid_preset() {
+        // [CSV-Core-TSPresetsV1]
+        let json = r#"{ "presets": [ { "name": "broken", "pattern": "(" } ] }"#;
+
+        assert!(matches!(
+            presets_from_json(json),
+            Err(TimestampPresetError::InvalidPreset { name, .. }) if name == "broken"
+        ));
+        assert!(matches!(
+            presets_from_json("[]"),
+            Err(TimestampPresetError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn upsert_replaces_presets_by_name() {
+        let mut presets = team_presets();
+
+        upsert_preset(&mut presets, TimestampPreset::new("log4j", r"^\S+ \S+ "));
+        upsert_preset(&mut presets, TimestampPreset::new("iso", r"^\S+Z "));
+
+        let names: Vec<&str> = presets.iter().map(TimestampPreset::name).collect();
+        assert_eq!(names, vec!["log4j", "nginx access", "iso"]);
+        assert_eq!(presets[0].pattern(), r"^\S+ \S+ ");
+    }
+}
@@ -0,0 +1,247 @@
+use time::{Duration, OffsetDateTime};
+
+use crate::core::diff_engine::{DiffLine, DiffState};
+
+/// Ratio of right to left elapsed time above which a pair counts as a slowdown, unless
+/// the user picks another one.
+pub const DEFAULT_SLOWDOWN_RATIO: f64 = 2.0;
+
+/// Time that passed before one matched event on each side, measured from the previous
+/// matched event, per [CSV-Core-TimingV1].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingPair {
+    row: usize,
+    previous_row: usize,
+    left_elapsed: Duration,
+    right_elapsed: Duration,
+    is_slowdown: bool,
+}
+
+impl TimingPair {
+    pub fn new(
+        row: usize,
+        previous_row: usize,
+        left_elapsed: Duration,
+        right_elapsed: Duration,
+    ) -> Self {
+        Self {
+            row,
+            previous_row,
+            left_elapsed,
+            right_elapsed,
+            is_slowdown: false,
+        }
+    }
+
+    pub fn with_slowdown(mut self, is_slowdown: bool) -> Self {
+        self.is_slowdown = is_slowdown;
+        self
+    }
+
+    /// Diff row of the event the elapsed times lead up to.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Diff row of the matched event the elapsed times are measured from.
+    pub fn previous_row(&self) -> usize {
+        self.previous_row
+    }
+
+    pub fn left_elapsed(&self) -> Duration {
+        self.left_elapsed
+    }
+
+    pub fn right_elapsed(&self) -> Duration {
+        self.right_elapsed
+    }
+
+    /// Right elapsed time divided by left elapsed time. There is no ratio when the left
+    /// side took no time or either side went backwards, as moved lines can.
+    pub fn ratio(&self) -> Option<f64> {
+        if !self.left_elapsed.is_positive() || self.right_elapsed.is_negative() {
+            return None;
+        }
+        Some(self.right_elapsed.as_seconds_f64() / self.left_elapsed.as_seconds_f64())
+    }
+
+    pub fn is_slowdown(&self) -> bool {
+        self.is_slowdown
+    }
+}
+
+/// Elapsed times between consecutive matched events of a diff, per [CSV-Core-TimingV1].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimingAnalysis {
+    pairs: Vec<TimingPair>,
+    slowdown_ratio: f64,
+}
+
+impl TimingAnalysis {
+    /// Walks the `Unchanged` and `Moved` rows of `lines` in display order and pairs each
+    /// one that has a timestamp on both sides with the previous such row. Timestamps are
+    /// indexed by zero-based line number of their file.
+    pub fn analyze(
+        lines: &[DiffLine],
+        left_timestamps: &[Option<OffsetDateTime>],
+        right_timestamps: &[Option<OffsetDateTime>],
+        slowdown_ratio: f64,
+    ) -> Self {
+        let mut pairs = Vec::new();
+        let mut previous: Option<(usize, OffsetDateTime, OffsetDateTime)> = None;
+
+        for (row, line) in lines.iter().enumerate() {
+            if !matches!(line.state(), DiffState::Unchanged | DiffState::Moved) {
+                continue;
+            }
+            let (Some(left), Some(right)) = (
+                line.left()
+                    .and_then(|content| timestamp_at(left_timestamps, content.line_number())),
+                line.right()
+                    .and_then(|content| timestamp_at(right_timestamps, content.line_number())),
+            ) else {
+                continue;
+            };
+
+            if let Some((previous_row, previous_left, previous_right)) = previous {
+                let pair = TimingPair::new(
+                    row,
+                    previous_row,
+                    left - previous_left,
+                    right - previous_right,
+                );
+                let is_slowdown = pair.ratio().is_some_and(|ratio| ratio > slowdown_ratio);
+                pairs.push(pair.with_slowdown(is_slowdown));
+            }
+            previous = Some((row, left, right));
+        }
+
+        Self {
+            pairs,
+            slowdown_ratio,
+        }
+    }
+
+    pub fn pairs(&self) -> &[TimingPair] {
+        &self.pairs
+    }
+
+    pub fn slowdown_ratio(&self) -> f64 {
+        self.slowdown_ratio
+    }
+
+    /// Pairs flagged as slowdowns, the largest ratio first.
+    pub fn slowdowns(&self) -> Vec<&TimingPair> {
+        let mut slowdowns: Vec<&TimingPair> = self
+            .pairs
+            .iter()
+            .filter(|pair| pair.is_slowdown())
+            .collect();
+        slowdowns.sort_by(|a, b| {
+            let ratio = |pair: &TimingPair| pair.ratio().unwrap_or_default();
+            ratio(b).total_cmp(&ratio(a))
+        });
+        slowdowns
+    }
+}
+
+/// `line_number` is one-based, as in `LineContent`.
+fn timestamp_at(
+    timestamps: &[Option<OffsetDateTime>],
+    line_number: usize,
+) -> Option<OffsetDateTime> {
+    line_number
+        .checked_sub(1)
+        .and_then(|index| timestamps.get(index).copied().flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LineContent;
+
+    fn at_millis(millis: i64) -> Option<OffsetDateTime> {
+        Some(OffsetDateTime::UNIX_EPOCH + Duration::milliseconds(millis))
+    }
+
+    fn line(state: DiffState, left: Option<usize>, right: Option<usize>) -> DiffLine {
+        DiffLine::new(
+            state,
+            left.map(|number| LineContent::new(number, "event")),
+            right.map(|number| LineContent::new(number, "event")),
+        )
+    }
+
+    #[test]
+    fn consecutive_matched_events_are_paired_and_slowdowns_flagged() {
+        // [CSV-Core-TimingV1] "DB connection successful": 550 ms on the left, 4.2 s on the right.
+        let lines = vec![
+            line(DiffState::Unchanged, Some(1), Some(1)),
+            line(DiffState::Deleted, Some(2), None),
+            line(DiffState::Unchanged, Some(3), Some(2)),
+            line(DiffState::Added, None, Some(3)),
+            line(DiffState::Unchanged, Some(4), Some(4)),
+        ];
+        let left = vec![at_millis(0), at_millis(100), at_millis(550), at_millis(650)];
+        let right = vec![
+            at_millis(0),
+            at_millis(4_200),
+            at_millis(4_250),
+            at_millis(4_300),
+        ];
+
+        let analysis = TimingAnalysis::analyze(&lines, &left, &right, DEFAULT_SLOWDOWN_RATIO);
+
+        assert_eq!(
+            analysis.pairs(),
+            [
+                TimingPair::new(
+                    2,
+                    0,
+                    Duration::milliseconds(550),
+                    Duration::milliseconds(4_200)
+                )
+                .with_slowdown(true),
+                TimingPair::new(
+                    4,
+                    2,
+                    Duration::milliseconds(100),
+                    Duration::milliseconds(100)
+                ),
+            ]
+        );
+        let slowdowns = analysis.slowdowns();
+        assert_eq!(slowdowns.len(), 1);
+        assert!((slowdowns[0].ratio().unwrap() - 4.2 / 0.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rows_without_timestamps_are_skipped_and_ratio_threshold_is_configurable() {
+        // [CSV-Core-TimingV1]
+        let lines = vec![
+            line(DiffState::Unchanged, Some(1), Some(1)),
+            line(DiffState::Moved, Some(2), Some(2)),
+            line(DiffState::Unchanged, Some(3), Some(3)),
+        ];
+        let left = vec![at_millis(0), None, at_millis(1_000)];
+        let right = vec![at_millis(0), at_millis(500), at_millis(2_500)];
+
+        let strict = TimingAnalysis::analyze(&lines, &left, &right, 2.0);
+        let lenient = TimingAnalysis::analyze(&lines, &left, &right, 3.0);
+
+        assert_eq!(strict.pairs().len(), 1);
+        assert_eq!(strict.pairs()[0].previous_row(), 0);
+        assert!(strict.pairs()[0].is_slowdown());
+        assert!(!lenient.pairs()[0].is_slowdown());
+        assert_eq!(lenient.slowdown_ratio(), 3.0);
+    }
+
+    #[test]
+    fn pairs_without_left_elapsed_time_have_no_ratio() {
+        let simultaneous = TimingPair::new(1, 0, Duration::ZERO, Duration::seconds(5));
+        let backwards = TimingPair::new(1, 0, Duration::seconds(1), Duration::seconds(-1));
+
+        assert_eq!(simultaneous.ratio(), None);
+        assert_eq!(backwards.ratio(), None);
+    }
+}
@@ -4,8 +4,11 @@ use std::sync::{Arc, Mutex};
 use simplelog::{ConfigBuilder, LevelFilter, WriteLogger};
 use time::macros::format_description;
 
+use ChronoSchismLogViewer::app_logic::diff_jobs::ThreadJobExecutor;
 use ChronoSchismLogViewer::app_logic::handler::AppLogic;
-use ChronoSchismLogViewer::core::diff_engine::{DiffEngineOperations, HeckelDiffEngine};
+use ChronoSchismLogViewer::core::diff_engine::{
+    DiffAlgorithm, DiffEngineOperations, HeckelDiffEngine, MyersDiffEngine, PatienceDiffEngine,
+};
 use ChronoSchismLogViewer::core::settings_manager::{
     CoreSettingsManager, SettingsManagerOperations,
 };
@@ -33,19 +36,10 @@ fn run() -> Result<(), Box<dyn Error>> {
     log::info!("Starting {APP_NAME}");
 
     let diff_engine: Arc<dyn DiffEngineOperations> = Arc::new(HeckelDiffEngine::new());
-    let timestamp_parser: Arc<dyn TimestampParserOperations> = Arc::new(CoreTimestampParser::new());
+    let core_timestamp_parser = Arc::new(CoreTimestampParser::new());
+    let timestamp_parser: Arc<dyn TimestampParserOperations> = core_timestamp_parser.clone();
     let settings_manager: Arc<dyn SettingsManagerOperations> = Arc::new(CoreSettingsManager::new());
 
-    let shared_logic = Arc::new(Mutex::new(AppLogic::new(
-        diff_engine,
-        timestamp_parser,
-        settings_manager,
-        APP_CLASS_NAME,
-    )));
-
-    let event_handler: Arc<Mutex<dyn PlatformEventHandler>> = shared_logic.clone();
-    let ui_state_provider: Arc<Mutex<dyn UiStateProvider>> = shared_logic;
-
     let platform = PlatformInterface::new(APP_CLASS_NAME.to_string())?;
 
     let window_id = platform.create_window(WindowConfig {
@@ -54,10 +48,39 @@ fn run() -> Result<(), Box<dyn Error>> {
         height: 900,
     })?;
 
+    // [CSV-UX-ResponsiveV1] Finished background jobs wake the UI thread, so their results
+    // appear without waiting for the next user input.
+    let job_executor =
+        ThreadJobExecutor::new().with_notifier(Arc::new(platform.create_waker(window_id)));
+    let mut app_logic = AppLogic::new(
+        diff_engine,
+        timestamp_parser,
+        settings_manager,
+        APP_CLASS_NAME,
+    )
+    .with_job_executor(Arc::new(job_executor));
+    app_logic.register_diff_engine(DiffAlgorithm::Myers, Arc::new(MyersDiffEngine::new()));
+    app_logic.register_diff_engine(DiffAlgorithm::Patience, Arc::new(PatienceDiffEngine::new()));
+
+    let shared_logic = Arc::new(Mutex::new(app_logic));
+
+    let event_handler: Arc<Mutex<dyn PlatformEventHandler>> = shared_logic.clone();
+    let ui_state_provider: Arc<Mutex<dyn UiStateProvider>> = shared_logic;
+
     let layout_commands = ui_description_layer::build_main_window_layout(window_id);
 
     platform.main_event_loop(event_handler, ui_state_provider, layout_commands)?;
 
+    let cache_stats = core_timestamp_parser.cache_stats();
+    log::info!(
+        "[CSV-Core-RegexCacheV2] Regex cache: {} hits, {} misses, {} evictions, {}/{} entries",
+        cache_stats.hits(),
+        cache_stats.misses(),
+        cache_stats.evictions(),
+        cache_stats.len(),
+        cache_stats.capacity()
+    );
+
     Ok(())
 }
 
@@ -1,40 +1,25 @@
 use crate::app_logic::ids::{
-    CONTROL_ID_LEFT_VIEWER, CONTROL_ID_RIGHT_VIEWER, CONTROL_ID_TIMESTAMP_INPUT,
-    LABEL_TIMESTAMP_PROMPT, MENU_ACTION_EXIT, MENU_ACTION_OPEN_LEFT, MENU_ACTION_OPEN_RIGHT,
-    PANEL_INPUT_BAR, PANEL_VIEWER_CONTAINER,
-};
-use commanductui::types::{
-    DockStyle, LabelClass, LayoutRule, MenuItemConfig, PlatformCommand, WindowId,
+    CONTROL_ID_LEFT_VIEWER, CONTROL_ID_MIN_MOVED_BLOCK_SIZE, CONTROL_ID_MOVE_GUTTER,
+    CONTROL_ID_NORMALIZATION_RULES, CONTROL_ID_PAIRING_PATTERN, CONTROL_ID_PATTERN_TESTER,
+    CONTROL_ID_PRESET_NAME, CONTROL_ID_RIGHT_TIMESTAMP_INPUT, CONTROL_ID_RIGHT_VIEWER,
+    CONTROL_ID_SLOWDOWN_RATIO, CONTROL_ID_TIMESTAMP_FORMAT, CONTROL_ID_TIMESTAMP_INPUT,
+    CONTROL_ID_TIMESTAMP_REPLACEMENT, CONTROL_ID_TIMING_COLUMN, LABEL_MIN_MOVED_BLOCK_SIZE_PROMPT,
+    LABEL_NORMALIZATION_PROMPT, LABEL_PAIRING_PATTERN_PROMPT, LABEL_PATTERN_TESTER_PROMPT,
+    LABEL_PRESET_NAME_PROMPT, LABEL_RIGHT_TIMESTAMP_PROMPT, LABEL_SLOWDOWN_RATIO_PROMPT,
+    LABEL_STATUS, LABEL_TIMESTAMP_DIAGNOSTIC, LABEL_TIMESTAMP_FORMAT_PROMPT,
+    LABEL_TIMESTAMP_PROMPT, LABEL_TIMESTAMP_REPLACEMENT_PROMPT, PANEL_INPUT_BAR,
+    PANEL_PATTERN_TESTER, PANEL_RULES_BAR, PANEL_STATUS_BAR, PANEL_VIEWER_CONTAINER,
 };
+use crate::app_logic::main_menu::{DirectoryMenuState, build_main_menu_items};
+use commanductui::types::{DockStyle, LabelClass, LayoutRule, PlatformCommand, WindowId};
 use commanductui::{Color, ControlStyle, StyleId};
 
 /// Builds the static command list that describes the main application window.
 /// This satisfies [CSV-UI-SideBySideV1] by defining the side-by-side viewer panels
 /// and the timestamp input field at the top of the window.
 pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
-    let file_menu_items = vec![
-        MenuItemConfig {
-            action: Some(MENU_ACTION_OPEN_LEFT),
-            text: "Open &Left File...".to_string(),
-            children: Vec::new(),
-        },
-        MenuItemConfig {
-            action: Some(MENU_ACTION_OPEN_RIGHT),
-            text: "Open &Right File...".to_string(),
-            children: Vec::new(),
-        },
-        MenuItemConfig {
-            action: Some(MENU_ACTION_EXIT),
-            text: "E&xit".to_string(),
-            children: Vec::new(),
-        },
-    ];
-
-    let menu_items = vec![MenuItemConfig {
-        action: None,
-        text: "&File".to_string(),
-        children: file_menu_items,
-    }];
+    // [CSV-Core-TSPresetsV1] No presets are loaded yet; the presenter rebuilds the menu.
+    let menu_items = build_main_menu_items(&[], None, None, DirectoryMenuState::Idle);
 
     let mut commands = Vec::new();
 
@@ -64,6 +49,26 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
         },
     });
 
+    // [CSV-UI-HighlightV1] Row backgrounds for each diff state in the viewers.
+    for (style_id, background) in [
+        (StyleId::DiffAdded, (0xD6, 0xF5, 0xD6)),
+        (StyleId::DiffDeleted, (0xF8, 0xD3, 0xD3)),
+        (StyleId::DiffMoved, (0xD4, 0xE4, 0xF7)),
+        (StyleId::DiffModified, (0xFA, 0xEF, 0xC8)),
+        // [CSV-Diff-IntraLineV1] A stronger shade of the modified color for changed words.
+        (StyleId::DiffChangedText, (0xF2, 0xC9, 0x6B)),
+    ] {
+        let (r, g, b) = background;
+        commands.push(PlatformCommand::DefineStyle {
+            style_id,
+            style: ControlStyle {
+                background_color: Some(Color { r, g, b }),
+                text_color: None,
+                font: None,
+            },
+        });
+    }
+
     commands.push(PlatformCommand::CreateMainMenu {
         window_id,
         menu_items,
@@ -74,17 +79,32 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
         parent_control_id: None,
         control_id: PANEL_INPUT_BAR,
     });
+    commands.push(PlatformCommand::CreatePanel {
+        window_id,
+        parent_control_id: None,
+        control_id: PANEL_RULES_BAR,
+    });
     commands.push(PlatformCommand::CreatePanel {
         window_id,
         parent_control_id: None,
         control_id: PANEL_VIEWER_CONTAINER,
     });
+    commands.push(PlatformCommand::CreatePanel {
+        window_id,
+        parent_control_id: None,
+        control_id: PANEL_PATTERN_TESTER,
+    });
+    commands.push(PlatformCommand::CreatePanel {
+        window_id,
+        parent_control_id: None,
+        control_id: PANEL_STATUS_BAR,
+    });
 
     commands.push(PlatformCommand::CreateLabel {
         window_id,
         parent_panel_id: PANEL_INPUT_BAR,
         control_id: LABEL_TIMESTAMP_PROMPT,
-        initial_text: "Timestamp Pattern (regex):".to_string(),
+        initial_text: "Timestamp Patterns (regex, one per line):".to_string(),
         class: LabelClass::Default,
     });
 
@@ -94,6 +114,129 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
         control_id: CONTROL_ID_TIMESTAMP_INPUT,
         initial_text: String::new(),
         read_only: false,
+        multiline: true,
+        vertical_scroll: true,
+    });
+
+    // [CSV-UX-RegexDiagnosticsV1] Where and why the pattern fails, right next to it.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_INPUT_BAR,
+        control_id: LABEL_TIMESTAMP_DIAGNOSTIC,
+        initial_text: String::new(),
+        class: LabelClass::Default,
+    });
+
+    // [CSV-Core-TSPatternListV1] Optional separate list for the right file.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_INPUT_BAR,
+        control_id: LABEL_RIGHT_TIMESTAMP_PROMPT,
+        initial_text: "Right File Patterns (empty = same):".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_INPUT_BAR),
+        control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: true,
+        vertical_scroll: true,
+    });
+
+    // [CSV-UI-MovedBlocksV1] Fewest contiguous moved lines that still form a moved block.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_INPUT_BAR,
+        control_id: LABEL_MIN_MOVED_BLOCK_SIZE_PROMPT,
+        initial_text: "Min Moved Block:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_INPUT_BAR),
+        control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: false,
+        vertical_scroll: false,
+    });
+
+    // [CSV-UI-NormalizationRulesV1] One rule per line: `name; action; pattern`.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_RULES_BAR,
+        control_id: LABEL_NORMALIZATION_PROMPT,
+        initial_text: "Normalization Rules (name; action; regex):".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_RULES_BAR),
+        control_id: CONTROL_ID_NORMALIZATION_RULES,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: true,
+        vertical_scroll: true,
+    });
+
+    // [CSV-UI-TimingV1] Ratio of right to left step time that counts as a slowdown.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_RULES_BAR,
+        control_id: LABEL_SLOWDOWN_RATIO_PROMPT,
+        initial_text: "Slowdown Ratio:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_RULES_BAR),
+        control_id: CONTROL_ID_SLOWDOWN_RATIO,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: false,
+        vertical_scroll: false,
+    });
+
+    // [CSV-Core-TSPresetsV1] Name under which Presets/Save stores the current pattern.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_RULES_BAR,
+        control_id: LABEL_PRESET_NAME_PROMPT,
+        initial_text: "Preset Name:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_RULES_BAR),
+        control_id: CONTROL_ID_PRESET_NAME,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: false,
+        vertical_scroll: false,
+    });
+
+    // [CSV-File-DirectoryCompareV1] Part of file names that differs between runs.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_RULES_BAR,
+        control_id: LABEL_PAIRING_PATTERN_PROMPT,
+        initial_text: "Pair Names Ignoring:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_RULES_BAR),
+        control_id: CONTROL_ID_PAIRING_PATTERN,
+        initial_text: String::new(),
+        read_only: false,
         multiline: false,
         vertical_scroll: false,
     });
@@ -108,6 +251,17 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
         vertical_scroll: true,
     });
 
+    // [CSV-UI-MovedBlocksV1] Narrow gutter between the viewers showing moved-block connectors.
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_VIEWER_CONTAINER),
+        control_id: CONTROL_ID_MOVE_GUTTER,
+        initial_text: String::new(),
+        read_only: true,
+        multiline: true,
+        vertical_scroll: false,
+    });
+
     commands.push(PlatformCommand::CreateInput {
         window_id,
         parent_control_id: Some(PANEL_VIEWER_CONTAINER),
@@ -118,39 +272,300 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
         vertical_scroll: true,
     });
 
+    // [CSV-UI-TimingV1] Step times of both runs next to the right viewer, row for row.
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_VIEWER_CONTAINER),
+        control_id: CONTROL_ID_TIMING_COLUMN,
+        initial_text: String::new(),
+        read_only: true,
+        multiline: true,
+        vertical_scroll: false,
+    });
+
+    // [CSV-Core-TSReplaceV1] Placeholder for timestamp matches; empty removes them.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_INPUT_BAR,
+        control_id: LABEL_TIMESTAMP_REPLACEMENT_PROMPT,
+        initial_text: "Replace With ($name):".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_INPUT_BAR),
+        control_id: CONTROL_ID_TIMESTAMP_REPLACEMENT,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: false,
+        vertical_scroll: false,
+    });
+
+    // [CSV-Core-TSExtractV1] Format description for turning matches into time values.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_INPUT_BAR,
+        control_id: LABEL_TIMESTAMP_FORMAT_PROMPT,
+        initial_text: "Time Format:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_INPUT_BAR),
+        control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+        initial_text: String::new(),
+        read_only: false,
+        multiline: false,
+        vertical_scroll: false,
+    });
+
+    // [CSV-UX-PatternTesterV1] Live preview of the patterns on the first lines of each file.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_PATTERN_TESTER,
+        control_id: LABEL_PATTERN_TESTER_PROMPT,
+        initial_text: "Pattern Test:".to_string(),
+        class: LabelClass::Default,
+    });
+
+    commands.push(PlatformCommand::CreateInput {
+        window_id,
+        parent_control_id: Some(PANEL_PATTERN_TESTER),
+        control_id: CONTROL_ID_PATTERN_TESTER,
+        initial_text: String::new(),
+        read_only: true,
+        multiline: true,
+        vertical_scroll: true,
+    });
+
+    // [CSV-UX-TimestampFeedbackV3] Names the invalid timestamp pattern entry.
+    commands.push(PlatformCommand::CreateLabel {
+        window_id,
+        parent_panel_id: PANEL_STATUS_BAR,
+        control_id: LABEL_STATUS,
+        initial_text: String::new(),
+        class: LabelClass::StatusBar,
+    });
+
     let layout_rules = vec![
         LayoutRule {
             control_id: PANEL_INPUT_BAR,
             parent_control_id: None,
             dock_style: DockStyle::Top,
             order: 0,
-            fixed_size: Some(48),
+            fixed_size: Some(72),
             margin: (8, 8, 4, 8),
         },
+        LayoutRule {
+            control_id: PANEL_RULES_BAR,
+            parent_control_id: None,
+            dock_style: DockStyle::Top,
+            order: 1,
+            fixed_size: Some(96),
+            margin: (4, 8, 4, 8),
+        },
         LayoutRule {
             control_id: PANEL_VIEWER_CONTAINER,
             parent_control_id: None,
             dock_style: DockStyle::Fill,
-            order: 1,
+            order: 2,
             fixed_size: None,
             margin: (4, 8, 8, 8),
         },
+        LayoutRule {
+            control_id: PANEL_PATTERN_TESTER,
+            parent_control_id: None,
+            dock_style: DockStyle::Bottom,
+            order: 3,
+            fixed_size: Some(140),
+            margin: (0, 8, 4, 8),
+        },
+        LayoutRule {
+            control_id: PANEL_STATUS_BAR,
+            parent_control_id: None,
+            dock_style: DockStyle::Bottom,
+            order: 4,
+            fixed_size: Some(24),
+            margin: (0, 8, 4, 8),
+        },
         LayoutRule {
             control_id: LABEL_TIMESTAMP_PROMPT,
             parent_control_id: Some(PANEL_INPUT_BAR),
-            dock_style: DockStyle::Left,
+            dock_style: DockStyle::ProportionalFill { weight: 0.35 },
             order: 0,
-            fixed_size: Some(220),
+            fixed_size: None,
             margin: (8, 8, 8, 8),
         },
         LayoutRule {
             control_id: CONTROL_ID_TIMESTAMP_INPUT,
             parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 1.0 },
+            order: 1,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_TIMESTAMP_DIAGNOSTIC,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.45 },
+            order: 2,
+            fixed_size: None,
+            margin: (8, 8, 8, 4),
+        },
+        LayoutRule {
+            control_id: LABEL_RIGHT_TIMESTAMP_PROMPT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.35 },
+            order: 3,
+            fixed_size: None,
+            margin: (8, 8, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_RIGHT_TIMESTAMP_INPUT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 1.0 },
+            order: 4,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_TIMESTAMP_REPLACEMENT_PROMPT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.25 },
+            order: 5,
+            fixed_size: None,
+            margin: (8, 8, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_TIMESTAMP_REPLACEMENT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.4 },
+            order: 6,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_TIMESTAMP_FORMAT_PROMPT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.2 },
+            order: 7,
+            fixed_size: None,
+            margin: (8, 8, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_TIMESTAMP_FORMAT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::ProportionalFill { weight: 0.6 },
+            order: 8,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_MIN_MOVED_BLOCK_SIZE,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::Right,
+            order: 9,
+            fixed_size: Some(50),
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_MIN_MOVED_BLOCK_SIZE_PROMPT,
+            parent_control_id: Some(PANEL_INPUT_BAR),
+            dock_style: DockStyle::Right,
+            order: 10,
+            fixed_size: Some(120),
+            margin: (8, 0, 8, 8),
+        },
+        LayoutRule {
+            control_id: LABEL_STATUS,
+            parent_control_id: Some(PANEL_STATUS_BAR),
+            dock_style: DockStyle::Fill,
+            order: 0,
+            fixed_size: None,
+            margin: (0, 8, 0, 8),
+        },
+        LayoutRule {
+            control_id: LABEL_PATTERN_TESTER_PROMPT,
+            parent_control_id: Some(PANEL_PATTERN_TESTER),
+            dock_style: DockStyle::Left,
+            order: 0,
+            fixed_size: Some(110),
+            margin: (8, 8, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_PATTERN_TESTER,
+            parent_control_id: Some(PANEL_PATTERN_TESTER),
             dock_style: DockStyle::Fill,
             order: 1,
             fixed_size: None,
             margin: (8, 8, 8, 0),
         },
+        LayoutRule {
+            control_id: LABEL_NORMALIZATION_PROMPT,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Left,
+            order: 0,
+            fixed_size: Some(220),
+            margin: (8, 8, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_SLOWDOWN_RATIO,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 1,
+            fixed_size: Some(60),
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_SLOWDOWN_RATIO_PROMPT,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 2,
+            fixed_size: Some(110),
+            margin: (8, 0, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_PRESET_NAME,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 3,
+            fixed_size: Some(140),
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_PRESET_NAME_PROMPT,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 4,
+            fixed_size: Some(90),
+            margin: (8, 0, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_PAIRING_PATTERN,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 5,
+            fixed_size: Some(140),
+            margin: (8, 8, 8, 0),
+        },
+        LayoutRule {
+            control_id: LABEL_PAIRING_PATTERN_PROMPT,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Right,
+            order: 6,
+            fixed_size: Some(130),
+            margin: (8, 0, 8, 8),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_NORMALIZATION_RULES,
+            parent_control_id: Some(PANEL_RULES_BAR),
+            dock_style: DockStyle::Fill,
+            order: 7,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
+        },
         LayoutRule {
             control_id: CONTROL_ID_LEFT_VIEWER,
             parent_control_id: Some(PANEL_VIEWER_CONTAINER),
@@ -159,13 +574,29 @@ pub fn build_main_window_layout(window_id: WindowId) -> Vec<PlatformCommand> {
             fixed_size: None,
             margin: (8, 4, 8, 8),
         },
+        LayoutRule {
+            control_id: CONTROL_ID_MOVE_GUTTER,
+            parent_control_id: Some(PANEL_VIEWER_CONTAINER),
+            dock_style: DockStyle::ProportionalFill { weight: 0.08 },
+            order: 1,
+            fixed_size: None,
+            margin: (8, 0, 8, 0),
+        },
         LayoutRule {
             control_id: CONTROL_ID_RIGHT_VIEWER,
             parent_control_id: Some(PANEL_VIEWER_CONTAINER),
             dock_style: DockStyle::ProportionalFill { weight: 1.0 },
-            order: 1,
+            order: 2,
             fixed_size: None,
-            margin: (8, 8, 8, 4),
+            margin: (8, 0, 8, 4),
+        },
+        LayoutRule {
+            control_id: CONTROL_ID_TIMING_COLUMN,
+            parent_control_id: Some(PANEL_VIEWER_CONTAINER),
+            dock_style: DockStyle::ProportionalFill { weight: 0.3 },
+            order: 3,
+            fixed_size: None,
+            margin: (8, 8, 8, 0),
         },
     ];
 